@@ -12,6 +12,17 @@ pub enum EPrimitiveType {
     TriangleStrip = 5,
 }
 
+impl EPrimitiveType {
+    /// Number of triangles drawn with the given amount of indices
+    pub fn triangle_count(&self, index_count: u32) -> u32 {
+        match self {
+            EPrimitiveType::Triangles => index_count / 3,
+            EPrimitiveType::TriangleStrip => index_count.saturating_sub(2),
+            _ => 0,
+        }
+    }
+}
+
 impl TigerReadable for EPrimitiveType {
     fn read_ds_endian<R: std::io::prelude::Read + std::io::prelude::Seek>(
         reader: &mut R,
@@ -92,4 +103,18 @@ impl ELodCategory {
                 | ELodCategory::Lod_Detail
         )
     }
+
+    /// Returns the LOD level of this category, 0 being the highest detail and 3 the lowest
+    pub fn lod_level(&self) -> u8 {
+        match self {
+            ELodCategory::Lod_0_0
+            | ELodCategory::Lod_0_1
+            | ELodCategory::Lod_0_2
+            | ELodCategory::Lod_0_3
+            | ELodCategory::Lod_Detail => 0,
+            ELodCategory::Lod_1_0 => 1,
+            ELodCategory::Lod_2_0 | ELodCategory::Lod_2_1 => 2,
+            ELodCategory::Lod_3_0 => 3,
+        }
+    }
 }
@@ -113,7 +113,7 @@ impl TigerReadable for TfxRenderStage {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
 pub enum TfxFeatureRenderer {
     StaticObjects = 0,
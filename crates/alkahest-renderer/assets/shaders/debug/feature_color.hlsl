@@ -0,0 +1,8 @@
+// Flat color drawn in place of materials by the feature colors debug view
+cbuffer scope_alkahest_feature_color : register(b7) {
+    float4 feature_color;
+};
+
+float4 PSMain() : SV_Target0 {
+    return feature_color;
+}
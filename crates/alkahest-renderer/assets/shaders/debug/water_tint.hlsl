@@ -0,0 +1,4 @@
+// Flat color drawn in place of water materials by the water tint debug option
+float4 PSMain() : SV_Target0 {
+    return float4(0.0, 0.55, 1.0, 1.0);
+}
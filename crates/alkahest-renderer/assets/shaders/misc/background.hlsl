@@ -0,0 +1,21 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_background : register(b0) {
+    float4 background_color;
+};
+
+Texture2D<float> RtDepth : register(t0);
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    // Reverse-Z, so anything at 0 is empty space
+    if (RtDepth.Load(int3(input.position.xy, 0)) != 0.0) {
+        discard;
+    }
+
+    return background_color;
+}
+
+#endif
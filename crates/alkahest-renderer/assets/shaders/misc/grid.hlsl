@@ -0,0 +1,63 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_grid : register(b0) {
+    float minor_spacing;
+    float major_spacing;
+    // Distance from the camera at which the grid has faded out completely
+    float fade_distance;
+    float opacity;
+    float4 minor_color;
+    float4 major_color;
+};
+
+Texture2D<float> RtDepth : register(t0);
+
+float3 pixel_to_world(float2 pixel, float raw_depth) {
+    float4 view_position = mul(target_pixel_to_camera, float4(pixel, raw_depth, 1.0));
+    return mul(camera_to_world, float4(view_position.xyz / view_position.w, 1.0)).xyz;
+}
+
+// Coverage of the lines of a grid with the given spacing, antialiased over roughly a pixel
+float grid_lines(float2 position, float spacing) {
+    float2 coord = position / spacing;
+    float2 width = max(fwidth(coord), 0.00001);
+    float2 line_distance = abs(frac(coord - 0.5) - 0.5) / width;
+    // Lines closer together than a pixel turn into noise, so fade them out before that point
+    float density_fade = 1.0 - saturate(max(width.x, width.y) * 2.0 - 0.5);
+    return (1.0 - saturate(min(line_distance.x, line_distance.y))) * density_fade;
+}
+
+// Draws a grid on the Z=0 plane, occluded by the geometry in the depth buffer
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float2 pixel = input.position.xy;
+
+    // Reverse-Z, so a raw depth of 1 is the near plane
+    float3 ray_origin = pixel_to_world(pixel, 1.0);
+    float3 ray_direction = normalize(pixel_to_world(pixel, 0.5) - ray_origin);
+    float t = -ray_origin.z / (abs(ray_direction.z) < 0.00001 ? 0.00001 : ray_direction.z);
+    float3 grid_position = ray_origin + ray_direction * t;
+
+    // Derivatives have to be taken before any pixel is discarded
+    float minor = grid_lines(grid_position.xy, minor_spacing);
+    float major = grid_lines(grid_position.xy, major_spacing);
+
+    if (t <= 0.0) {
+        discard;
+    }
+
+    float raw_depth = RtDepth.Load(int3(pixel, 0));
+    // Anything at 0 is the sky, which never occludes the grid
+    if (raw_depth != 0.0 && distance(pixel_to_world(pixel, raw_depth), ray_origin) < t) {
+        discard;
+    }
+
+    float fade = 1.0 - saturate(t / fade_distance);
+    float4 color = lerp(minor_color, major_color, major);
+    float coverage = max(minor, major);
+    return float4(color.rgb, color.a * coverage * fade * fade * opacity);
+}
+
+#endif
@@ -0,0 +1,68 @@
+#include "scopes/view.hlsli"
+
+#define MAX_ICONS 256
+
+struct IconInstance {
+    // xyz: world position, w: size in pixels
+    float4 position_size;
+    float4 color;
+    // Atlas UV rectangle (min xy, max xy)
+    float4 uv_rect;
+};
+
+cbuffer scope_alkahest_icon_billboards : register(b0) {
+    IconInstance icons[MAX_ICONS];
+};
+
+cbuffer scope_alkahest_icon_billboard_params : register(b1) {
+    // Icons are pulled this far towards the camera, so nodes sitting on (or just inside of) a surface aren't hidden by it
+    float depth_offset;
+    // Whether the target is linear, rather than already gamma corrected
+    float linear_output;
+    float2 _pad;
+};
+
+struct VSOutput {
+    float4 position : SV_POSITION;
+    float2 uv : TEXCOORD0;
+    float4 color : COLOR0;
+};
+
+VSOutput VSMain(uint vertex_i : SV_VertexID) {
+    static const float2 CORNERS[6] = {
+        float2(-1, -1), float2(-1, 1), float2(1, 1),
+        float2(-1, -1), float2(1, 1), float2(1, -1),
+    };
+
+    IconInstance icon = icons[vertex_i / 6];
+    float2 corner = CORNERS[vertex_i % 6];
+
+    float3 to_camera = camera_position - icon.position_size.xyz;
+    float3 position = icon.position_size.xyz + normalize(to_camera) * min(depth_offset, length(to_camera) * 0.5);
+
+    // Quads face the camera and keep a constant size on screen, like the 2D overlay icons
+    VSOutput output;
+    output.position = mul(world_to_projective, float4(position, 1.0));
+    output.position.xy += corner * icon.position_size.w * inverse_target_resolution * output.position.w;
+    output.uv = lerp(icon.uv_rect.xy, icon.uv_rect.zw, corner * float2(0.5, -0.5) + 0.5);
+    output.color = icon.color;
+
+    return output;
+}
+
+#ifdef STAGE_PS
+
+Texture2D<float> IconAtlas : register(t0);
+SamplerState Sampler : register(s0);
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float coverage = IconAtlas.Sample(Sampler, input.uv);
+    if (coverage <= 0.0) {
+        discard;
+    }
+
+    float3 color = linear_output > 0.5 ? pow(input.color.rgb, 2.2) : input.color.rgb;
+    return float4(color, input.color.a * coverage);
+}
+
+#endif
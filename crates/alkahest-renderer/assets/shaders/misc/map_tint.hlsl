@@ -0,0 +1,42 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+static const int MAX_MAPS = 8;
+
+cbuffer scope_alkahest_map_tint : register(b0) {
+    float opacity;
+    float3 _pad;
+    float4 colors[MAX_MAPS];
+};
+
+// 0 where nothing was drawn, otherwise the index of the map + 1
+Texture2D<uint> MapIds : register(t0);
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    int2 pixel = int2(input.position.xy);
+    int2 max_pixel = int2(target_resolution) - 1;
+
+    uint id = MapIds.Load(int3(pixel, 0));
+    if (id == 0) {
+        discard;
+    }
+
+    // Pixels bordering geometry of another map mark the seams between them
+    static const int2 offsets[4] = { int2(1, 0), int2(-1, 0), int2(0, 1), int2(0, -1) };
+    bool seam = false;
+    [unroll]
+    for (int i = 0; i < 4; i++) {
+        uint neighbour = MapIds.Load(int3(clamp(pixel + offsets[i], 0, max_pixel), 0));
+        seam = seam || (neighbour != 0 && neighbour != id);
+    }
+
+    if (seam) {
+        return float4(1.0, 1.0, 1.0, 1.0);
+    }
+
+    return float4(colors[(id - 1) % MAX_MAPS].rgb, opacity);
+}
+
+#endif
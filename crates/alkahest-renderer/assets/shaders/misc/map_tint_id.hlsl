@@ -0,0 +1,7 @@
+cbuffer cb_map_id : register(b7) {
+    uint map_id;
+}
+
+uint PSMain() : SV_Target0 {
+    return map_id;
+}
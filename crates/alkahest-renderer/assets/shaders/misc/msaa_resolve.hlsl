@@ -0,0 +1,25 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+Texture2DMS<float> RtDepthMS : register(t0);
+Texture2DMS<float4> Rt1MS : register(t1);
+
+// Resolves the multisampled depth buffer, keeping the closest sample so edges match the resolved color targets.
+// RT1 holds encoded normals, which turn into garbage when averaged, so it takes the first sample instead
+float4 PSMain(VSOutput input, out float out_depth : SV_Depth) : SV_Target0 {
+    uint width, height, sample_count;
+    RtDepthMS.GetDimensions(width, height, sample_count);
+
+    // Reverse-Z, so the closest sample has the largest depth
+    float depth = 0.0;
+    for (uint i = 0; i < sample_count; i++) {
+        depth = max(depth, RtDepthMS.Load(int2(input.position.xy), i));
+    }
+    out_depth = depth;
+
+    return Rt1MS.Load(int2(input.position.xy), 0);
+}
+
+#endif
@@ -0,0 +1,19 @@
+#include "scopes/view.hlsli"
+#include "dither.hlsli"
+
+cbuffer scope_alkahest_near_fade : register(b7) {
+    float fade_distance;
+};
+
+// Replaces the pixel shader of the geometry during the near fade depth prepass.
+// Fragments close to the camera are discarded in a dithered pattern, so geometry fades out before it hits the near plane
+void PSMain(float4 position : SV_Position) {
+    float4 view_position = mul(target_pixel_to_camera, float4(position.xyz, 1.0));
+    float distance = length(view_position.xyz / view_position.w);
+    if (distance >= fade_distance) {
+        return;
+    }
+
+    // Keeps the fragment where the distance into the fade is above the dither threshold
+    dither_discard(position.xy, distance / fade_distance);
+}
@@ -0,0 +1,38 @@
+// Vertex shader for drawing the shader ball with a game technique.
+// Outputs the same interpolants as the entity vertex shader override, which is what entity pixel shaders expect
+#include "scopes/view.hlsli"
+
+cbuffer appearance : register(b0) {
+    float4x4 modelMatrix;
+};
+
+void VSMain(
+    float3 in_position : POSITION,
+    float2 in_texcoord : TEXCOORD0,
+    float3 in_normal : NORMAL,
+    out float4 o0 : TEXCOORD0,
+    out float4 o1 : TEXCOORD1,
+    out float4 o2 : TEXCOORD2,
+    out float4 o3 : TEXCOORD3,
+    out float3 o4 : TEXCOORD4,
+    out float3 o5 : TEXCOORD5,
+    out float4 o8 : TEXCOORD8,
+    out float4 out_position : SV_POSITION0
+) {
+    float3 world_position = mul(modelMatrix, float4(in_position, 1.0)).xyz;
+    float3 normal = normalize(mul((float3x3)modelMatrix, in_normal));
+    // The ball model has no tangents, any vector perpendicular to the normal will do for a preview
+    float3 up = abs(normal.z) < 0.999 ? float3(0.0, 0.0, 1.0) : float3(1.0, 0.0, 0.0);
+    float3 tangent = normalize(cross(up, normal));
+
+    // Normal and ambient occlusion
+    o0 = float4(normal, 1.0);
+    o1 = float4(tangent, 1.0);
+    o2 = float4(cross(normal, tangent), 1.0);
+    o3 = in_texcoord.xyxy;
+    o4 = world_position;
+    o5 = in_position;
+    // Vertex color
+    o8 = float4(1.0, 1.0, 1.0, 1.0);
+    out_position = mul(world_to_projective, float4(world_position, 1.0));
+}
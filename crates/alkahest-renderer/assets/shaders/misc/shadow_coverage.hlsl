@@ -0,0 +1,41 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+static const int MAX_CASCADES = 4;
+
+cbuffer scope_alkahest_shadow_coverage : register(b0) {
+    uint cascade_count;
+    float opacity;
+    float2 _pad;
+    // View depth at which each cascade ends
+    float4 splits;
+    float4 colors[MAX_CASCADES];
+};
+
+Texture2D<float> RtDepth : register(t0);
+
+// Tints geometry with the color of the cascade its view depth falls into
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float raw_depth = RtDepth.Load(int3(input.position.xy, 0));
+    // Reverse-Z, so anything at 0 is empty space
+    if (raw_depth == 0.0) {
+        discard;
+    }
+
+    float4 view_position = mul(target_pixel_to_camera, float4(input.position.xy, raw_depth, 1.0));
+    float3 world_position = mul(camera_to_world, float4(view_position.xyz / view_position.w, 1.0)).xyz;
+    float view_depth = dot(world_position - camera_position, -camera_backward);
+
+    for (uint i = 0; i < cascade_count; i++) {
+        if (view_depth <= splits[i]) {
+            return float4(colors[i].rgb, opacity);
+        }
+    }
+
+    discard;
+    return 0;
+}
+
+#endif
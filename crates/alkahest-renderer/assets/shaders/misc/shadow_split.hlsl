@@ -0,0 +1,73 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+static const int MAX_SHADOW_MAPS = 8;
+// Keeps surfaces from shadowing themselves due to depth precision
+static const float DEPTH_BIAS = 0.0005;
+
+cbuffer scope_alkahest_shadow_split : register(b0) {
+    uint shadow_map_count;
+    float opacity;
+    float2 _pad;
+    float4x4 world_to_shadow[MAX_SHADOW_MAPS];
+    float4 resolution[MAX_SHADOW_MAPS];
+    float4 color_stationary;
+    float4 color_moving;
+};
+
+Texture2D<float> RtDepth : register(t0);
+// Depth of stationary geometry only
+Texture2DArray<float> StationaryDepth[MAX_SHADOW_MAPS] : register(t1);
+// Depth of stationary and moving geometry
+Texture2DArray<float> FullDepth[MAX_SHADOW_MAPS] : register(t9);
+
+// Tints geometry by whether the first shadow map covering it shadows it through the cached stationary pass or the per-frame moving pass
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float raw_depth = RtDepth.Load(int3(input.position.xy, 0));
+    // Reverse-Z, so anything at 0 is empty space
+    if (raw_depth == 0.0) {
+        discard;
+    }
+
+    float4 view_position = mul(target_pixel_to_camera, float4(input.position.xy, raw_depth, 1.0));
+    float3 world_position = mul(camera_to_world, float4(view_position.xyz / view_position.w, 1.0)).xyz;
+
+    [unroll]
+    for (uint i = 0; i < MAX_SHADOW_MAPS; i++) {
+        if (i >= shadow_map_count) {
+            break;
+        }
+
+        float4 shadow_position = mul(world_to_shadow[i], float4(world_position, 1.0));
+        if (shadow_position.w <= 0.0) {
+            continue;
+        }
+
+        float3 ndc = shadow_position.xyz / shadow_position.w;
+        if (any(abs(ndc.xy) > 1.0) || ndc.z < 0.0 || ndc.z > 1.0) {
+            continue;
+        }
+
+        float2 uv = ndc.xy * float2(0.5, -0.5) + 0.5;
+        int2 texel = int2(min(uv * resolution[i].xy, resolution[i].xy - 1.0));
+        float depth = ndc.z - DEPTH_BIAS;
+
+        if (depth > StationaryDepth[i].Load(int4(texel, 0, 0))) {
+            return float4(color_stationary.rgb, opacity);
+        }
+
+        if (depth > FullDepth[i].Load(int4(texel, 0, 0))) {
+            return float4(color_moving.rgb, opacity);
+        }
+
+        // Lit by the first shadow map that covers it
+        discard;
+    }
+
+    discard;
+    return 0;
+}
+
+#endif
@@ -0,0 +1,26 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_bloom : register(b0) {
+    float2 source_texel_size;
+    float threshold;
+    float knee;
+    float intensity;
+    uint prefilter;
+    float2 _pad;
+};
+
+Texture2D Source : register(t0);
+Texture2D Bloom : register(t1);
+SamplerState Sampler : register(s0);
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float4 result = Source.Load(int3(input.position.xy, 0));
+    // The bloom texture is half the resolution of the source, so it's sampled bilinearly
+    result.rgb += Bloom.Sample(Sampler, input.uv).rgb * intensity;
+    return result;
+}
+
+#endif
@@ -0,0 +1,45 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_bloom : register(b0) {
+    // Size of a single texel of the source texture
+    float2 source_texel_size;
+    float threshold;
+    // Width of the soft transition around the threshold
+    float knee;
+    float intensity;
+    // Only the first downsample from the shading result applies the threshold
+    uint prefilter;
+    float2 _pad;
+};
+
+Texture2D Source : register(t0);
+SamplerState Sampler : register(s0);
+
+float3 apply_threshold(float3 color) {
+    float brightness = max(color.r, max(color.g, color.b));
+    float soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee + 0.00001);
+    float contribution = max(soft, brightness - threshold) / max(brightness, 0.00001);
+    return color * contribution;
+}
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    // Four bilinear taps between texels average a 4x4 area of the source
+    float4 offset = source_texel_size.xyxy * float4(-1.0, -1.0, 1.0, 1.0);
+    float3 color = Source.Sample(Sampler, input.uv + offset.xy).rgb
+                 + Source.Sample(Sampler, input.uv + offset.zy).rgb
+                 + Source.Sample(Sampler, input.uv + offset.xw).rgb
+                 + Source.Sample(Sampler, input.uv + offset.zw).rgb;
+    color *= 0.25;
+
+    if (prefilter) {
+        color = apply_threshold(color);
+    }
+
+    return float4(color, 0.0);
+}
+
+#endif
@@ -0,0 +1,36 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_bloom : register(b0) {
+    // Size of a single texel of the source texture
+    float2 source_texel_size;
+    float threshold;
+    float knee;
+    float intensity;
+    uint prefilter;
+    float2 _pad;
+};
+
+Texture2D Source : register(t0);
+SamplerState Sampler : register(s0);
+
+// Additively blended onto the next larger mip, which is why alpha is 0
+float4 PSMain(VSOutput input) : SV_Target0 {
+    // 3x3 tent filter
+    float4 offset = source_texel_size.xyxy * float4(1.0, 1.0, -1.0, 0.0);
+    float3 color = Source.Sample(Sampler, input.uv - offset.xy).rgb
+                 + Source.Sample(Sampler, input.uv - offset.wy).rgb * 2.0
+                 + Source.Sample(Sampler, input.uv - offset.zy).rgb
+                 + Source.Sample(Sampler, input.uv + offset.zw).rgb * 2.0
+                 + Source.Sample(Sampler, input.uv).rgb * 4.0
+                 + Source.Sample(Sampler, input.uv + offset.xw).rgb * 2.0
+                 + Source.Sample(Sampler, input.uv + offset.zy).rgb
+                 + Source.Sample(Sampler, input.uv + offset.wy).rgb * 2.0
+                 + Source.Sample(Sampler, input.uv + offset.xy).rgb;
+
+    return float4(color / 16.0, 0.0);
+}
+
+#endif
@@ -0,0 +1,28 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_chromatic_aberration : register(b0) {
+    // Offset of the red and blue channels at the corners of the screen, in pixels
+    float strength;
+    float3 _pad;
+};
+
+Texture2D Source : register(t0);
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    int2 pixel = int2(input.position.xy);
+    int2 max_pixel = int2(target_resolution) - 1;
+
+    // Channels are shifted outwards from the center, increasing towards the edges
+    float2 uv = input.position.xy * inverse_target_resolution;
+    int2 offset = int2(round((uv - 0.5) * 2.0 * strength));
+
+    float4 result = Source.Load(int3(pixel, 0));
+    result.r = Source.Load(int3(clamp(pixel + offset, 0, max_pixel), 0)).r;
+    result.b = Source.Load(int3(clamp(pixel - offset, 0, max_pixel), 0)).b;
+    return result;
+}
+
+#endif
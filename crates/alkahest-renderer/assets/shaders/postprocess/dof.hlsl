@@ -0,0 +1,70 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_dof : register(b0) {
+    float focal_distance;
+    float focal_range;
+    // Maximum blur radius, in pixels
+    float blur_strength;
+    float _pad;
+    // Blur direction in pixels, either (1, 0) or (0, 1)
+    float2 direction;
+    float2 _pad2;
+};
+
+Texture2D Source : register(t0);
+Texture2D<float> RtDepth : register(t1);
+
+static const int SAMPLE_COUNT = 8;
+
+// Circle of confusion, 0 for pixels in focus and 1 for pixels that are blurred the most
+float circle_of_confusion(int2 pixel) {
+    float raw_depth = RtDepth.Load(int3(pixel, 0));
+    // Reverse-Z, so anything at 0 is the sky
+    if (raw_depth == 0.0) {
+        return 1.0;
+    }
+
+    float4 view_position = mul(target_pixel_to_camera, float4(pixel + 0.5, raw_depth, 1.0));
+    float distance = length(view_position.xyz / view_position.w);
+
+    // Everything within half the focal range is sharp, after which the blur ramps up over another focal range
+    float half_range = focal_range * 0.5;
+    return saturate((abs(distance - focal_distance) - half_range) / max(focal_range, 0.001));
+}
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    int2 pixel = int2(input.position.xy);
+    int2 max_pixel = int2(target_resolution) - 1;
+
+    float center_coc = circle_of_confusion(pixel);
+    float4 center = Source.Load(int3(pixel, 0));
+    float radius = center_coc * blur_strength;
+    if (radius < 0.5) {
+        return center;
+    }
+
+    float4 result = center;
+    float total_weight = 1.0;
+    for (int i = 1; i <= SAMPLE_COUNT; i++) {
+        float t = float(i) / SAMPLE_COUNT;
+        // Gaussian falloff over the radius
+        float gaussian = exp(-t * t * 2.0);
+
+        [unroll]
+        for (int side = -1; side <= 1; side += 2) {
+            int2 sample_pixel = clamp(pixel + int2(round(direction * side * t * radius)), 0, max_pixel);
+
+            // Weighting by the CoC of the sample keeps sharp pixels from bleeding into the blur around them
+            float weight = gaussian * circle_of_confusion(sample_pixel);
+            result += Source.Load(int3(sample_pixel, 0)) * weight;
+            total_weight += weight;
+        }
+    }
+
+    return result / total_weight;
+}
+
+#endif
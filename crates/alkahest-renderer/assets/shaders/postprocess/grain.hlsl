@@ -0,0 +1,33 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_grain : register(b0) {
+    float intensity;
+    // Seconds, the grain pattern changes at 24 frames per second like actual film
+    float grain_time;
+    float2 _pad;
+};
+
+Texture2D Source : register(t0);
+
+// https://www.shadertoy.com/view/4djSRW
+float hash13(float3 p3) {
+    p3 = frac(p3 * 0.1031);
+    p3 += dot(p3, p3.zyx + 31.32);
+    return frac((p3.x + p3.y) * p3.z);
+}
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float4 result = Source.Load(int3(input.position.xy, 0));
+
+    float frame = floor(grain_time * 24.0);
+    float noise = hash13(float3(input.position.xy, frame)) - 0.5;
+
+    // Multiplicative, so the grain works regardless of the exposure of the HDR input
+    result.rgb *= max(1.0 + noise * intensity * 2.0, 0.0);
+    return result;
+}
+
+#endif
@@ -0,0 +1,36 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+// Must match AutoExposureRenderer::SIZE
+static const float OUTPUT_SIZE = 64.0;
+static const int SAMPLES_PER_AXIS = 4;
+
+Texture2D Source : register(t0);
+
+// Average log2 luminance of the block of the shading result covered by this pixel
+float PSMain(VSOutput input) : SV_Target0 {
+    uint width, height;
+    Source.GetDimensions(width, height);
+
+    float2 block_size = float2(width, height) / OUTPUT_SIZE;
+    float2 block_start = floor(input.position.xy) * block_size;
+
+    float total = 0.0;
+    [unroll]
+    for (int y = 0; y < SAMPLES_PER_AXIS; y++) {
+        [unroll]
+        for (int x = 0; x < SAMPLES_PER_AXIS; x++) {
+            float2 pixel = block_start + (float2(x, y) + 0.5) / SAMPLES_PER_AXIS * block_size;
+            float3 color = Source.Load(int3(pixel, 0)).rgb;
+            float luminance = dot(color, float3(0.2126, 0.7152, 0.0722));
+            // Keeps pure black pixels from dragging the average down to -infinity
+            total += log2(max(luminance, 0.0001));
+        }
+    }
+
+    return total / (SAMPLES_PER_AXIS * SAMPLES_PER_AXIS);
+}
+
+#endif
@@ -16,6 +16,9 @@ Texture2D RtDepth       : register(t0);
 Texture2D RtNormal      : register(t1);
 Texture2D NoiseTexture  : register(t2);
 
+// Point sampled, unless linear depth sampling is enabled for debugging
+SamplerState DepthSampler : register(s1);
+
 float3 WorldPosFromDepth(float depth, float2 viewportPos) {
     float4 clipSpacePos = float4(viewportPos, depth, 1.0);
 
@@ -24,7 +27,7 @@ float3 WorldPosFromDepth(float depth, float2 viewportPos) {
 }
 
 float3 SampleWorldPos(float2 uv) {
-    float depth = RtDepth.Sample(def_point_clamp, uv).x;
+    float depth = RtDepth.SampleLevel(DepthSampler, uv, 0).x;
     return WorldPosFromDepth(depth, uv * target_resolution);
 }
 
@@ -45,13 +48,12 @@ float4 PSMain(
 //     if (LinearizeDepth(RtDepth.SampleLevel(def_point_clamp, input.uv, 0).r ) <= 1)
 //         return 1.0f;
 
-    float2 noiseScale = target_resolution / 4.0;
-
     float3 fragPosWorld = SampleWorldPos(input.uv);
     float3 normal = normalize(DecodeNormal(RtNormal.Sample(def_point_clamp, input.uv).xyz));
     fragPosWorld += normal * bias;
 
-    float3 randDir = NoiseTexture.Sample(def_point_clamp, input.uv * noiseScale).xyz;
+    // Tiled across the screen, which the clamping sampler can't do
+    float3 randDir = NoiseTexture.Load(int3(uint2(input.position.xy) % 4, 0)).xyz;
 
     float3 tangent = normalize(randDir - normal * dot(randDir, normal));
     float3 bitangent = cross(normal, tangent);
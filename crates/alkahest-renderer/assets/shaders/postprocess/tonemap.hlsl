@@ -0,0 +1,56 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+static const uint TONEMAP_REINHARD = 1;
+static const uint TONEMAP_ACES = 2;
+static const uint TONEMAP_UNCHARTED2 = 3;
+
+cbuffer scope_alkahest_tonemap : register(b0) {
+    uint tonemap_operator;
+    float exposure;
+    float2 _pad;
+};
+
+Texture2D Source : register(t0);
+
+// Narkowicz's fit of the ACES filmic curve
+float3 tonemap_aces(float3 x) {
+    return saturate((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14));
+}
+
+float3 uncharted2_curve(float3 x) {
+    const float A = 0.15; // Shoulder strength
+    const float B = 0.50; // Linear strength
+    const float C = 0.10; // Linear angle
+    const float D = 0.20; // Toe strength
+    const float E = 0.02; // Toe numerator
+    const float F = 0.30; // Toe denominator
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+float3 tonemap_uncharted2(float3 x) {
+    // Linear white point
+    const float W = 11.2;
+    return saturate(uncharted2_curve(x * 2.0) / uncharted2_curve(W));
+}
+
+// Maps the HDR shading result into the 0-1 range of the postprocess buffers, with luminance in alpha for FXAA
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float3 color = max(Source.Load(int3(input.position.xy, 0)).rgb, 0.0) * exposure;
+
+    if (tonemap_operator == TONEMAP_REINHARD) {
+        color = color / (1.0 + color);
+    } else if (tonemap_operator == TONEMAP_ACES) {
+        color = tonemap_aces(color);
+    } else if (tonemap_operator == TONEMAP_UNCHARTED2) {
+        color = tonemap_uncharted2(color);
+    } else {
+        color = saturate(color);
+    }
+
+    return float4(color, dot(color, float3(0.3, 0.59, 0.11)));
+}
+
+#endif
@@ -0,0 +1,28 @@
+// VSMain
+#include "screen_space.hlsli"
+
+#ifdef STAGE_PS
+
+cbuffer scope_alkahest_vignette : register(b0) {
+    float4 color;
+    float strength;
+    // Distance from the center at which the vignette starts, where 1 is the corners of the screen
+    float radius;
+    float2 _pad;
+};
+
+Texture2D Source : register(t0);
+
+float4 PSMain(VSOutput input) : SV_Target0 {
+    float4 result = Source.Load(int3(input.position.xy, 0));
+
+    float2 uv = input.position.xy * inverse_target_resolution;
+    float distance = length(uv - 0.5) * sqrt(2.0);
+    float amount = strength * smoothstep(radius, radius + 0.5, distance);
+
+    // Tints multiplicatively, so the vignette works regardless of the exposure of the HDR input
+    result.rgb *= lerp(float3(1.0, 1.0, 1.0), color.rgb, saturate(amount));
+    return result;
+}
+
+#endif
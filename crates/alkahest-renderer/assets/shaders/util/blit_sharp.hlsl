@@ -0,0 +1,50 @@
+#include "screen_space.hlsli"
+
+Texture2D Source : register(t0);
+SamplerState Sampler : register(s0);
+
+// Bicubic Catmull-Rom filter using 9 bilinear taps
+// https://gist.github.com/TheRealMJP/c83b8c0f46b63f3a88a5986f4fa982b1
+float4 SampleCatmullRom(float2 uv) {
+    float2 texSize;
+    Source.GetDimensions(texSize.x, texSize.y);
+
+    float2 samplePos = uv * texSize;
+    float2 texPos1 = floor(samplePos - 0.5) + 0.5;
+    float2 f = samplePos - texPos1;
+
+    float2 w0 = f * (-0.5 + f * (1.0 - 0.5 * f));
+    float2 w1 = 1.0 + f * f * (-2.5 + 1.5 * f);
+    float2 w2 = f * (0.5 + f * (2.0 - 1.5 * f));
+    float2 w3 = f * f * (-0.5 + 0.5 * f);
+
+    float2 w12 = w1 + w2;
+    float2 offset12 = w2 / w12;
+
+    float2 texPos0 = (texPos1 - 1) / texSize;
+    float2 texPos3 = (texPos1 + 2) / texSize;
+    float2 texPos12 = (texPos1 + offset12) / texSize;
+
+    float4 result = 0.0;
+    result += Source.SampleLevel(Sampler, float2(texPos0.x, texPos0.y), 0.0) * w0.x * w0.y;
+    result += Source.SampleLevel(Sampler, float2(texPos12.x, texPos0.y), 0.0) * w12.x * w0.y;
+    result += Source.SampleLevel(Sampler, float2(texPos3.x, texPos0.y), 0.0) * w3.x * w0.y;
+
+    result += Source.SampleLevel(Sampler, float2(texPos0.x, texPos12.y), 0.0) * w0.x * w12.y;
+    result += Source.SampleLevel(Sampler, float2(texPos12.x, texPos12.y), 0.0) * w12.x * w12.y;
+    result += Source.SampleLevel(Sampler, float2(texPos3.x, texPos12.y), 0.0) * w3.x * w12.y;
+
+    result += Source.SampleLevel(Sampler, float2(texPos0.x, texPos3.y), 0.0) * w0.x * w3.y;
+    result += Source.SampleLevel(Sampler, float2(texPos12.x, texPos3.y), 0.0) * w12.x * w3.y;
+    result += Source.SampleLevel(Sampler, float2(texPos3.x, texPos3.y), 0.0) * w3.x * w3.y;
+
+    // Catmull-Rom can overshoot, which results in negative values around sharp edges
+    return max(result, 0.0);
+}
+
+void PSMain(
+    VSOutput input,
+    out float4 rt : SV_Target0
+) {
+    rt = SampleCatmullRom(input.uv);
+}
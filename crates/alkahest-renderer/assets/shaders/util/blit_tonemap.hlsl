@@ -0,0 +1,14 @@
+// VSMain
+#include "screen_space.hlsli"
+
+Texture2D Source : register(t0);
+SamplerState Sampler : register(s0);
+
+// Reinhard, maps HDR values into 0-1 for previewing float textures
+void PSMain(
+    VSOutput input,
+    out float4 rt : SV_Target0
+) {
+    float3 color = max(Source.Sample(Sampler, input.uv).rgb, 0.0);
+    rt = float4(color / (1.0 + color), 1.0);
+}
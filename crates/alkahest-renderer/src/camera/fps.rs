@@ -11,6 +11,10 @@ pub struct FpsCamera {
     pub up: Vec3,
     pub position: Vec3,
     target_position: Vec3,
+    /// Velocity from keyboard input during the last update
+    input_velocity: Vec3,
+    /// Velocity from gamepad input, consumed by the next update
+    gamepad_velocity: Vec3,
 }
 
 impl FpsCamera {
@@ -35,6 +39,8 @@ impl Default for FpsCamera {
             up: Vec3::Z,
             position: Vec3::ZERO,
             target_position: Vec3::ZERO,
+            input_velocity: Vec3::ZERO,
+            gamepad_velocity: Vec3::ZERO,
             orientation: Vec2::ZERO,
         }
     }
@@ -127,17 +133,28 @@ impl CameraController for FpsCamera {
             }
         }
 
+        let mut velocity = Vec3::ZERO;
         if let Some(tween) = tween {
             if tween.is_aborted() {
                 self.target_position += direction * speed;
+                velocity = direction * speed;
             } else {
                 self.target_position = tween.update_pos().unwrap_or(self.target_position);
                 self.orientation = tween.update_angle().unwrap_or(self.orientation);
             }
         } else {
             self.target_position += direction * speed;
+            velocity = direction * speed;
         }
 
+        // Gamepad input is applied after this update, so it's picked up a frame late
+        self.input_velocity = if delta_time > 0.0 {
+            velocity / delta_time + self.gamepad_velocity
+        } else {
+            Vec3::ZERO
+        };
+        self.gamepad_velocity = Vec3::ZERO;
+
         if tween.as_ref().is_some_and(Tween::is_finished) {
             *tween = None;
         }
@@ -176,6 +193,7 @@ impl CameraController for FpsCamera {
         let mut speed = delta_time * 25.0;
         speed *= speed_mul;
         self.target_position += direction * speed;
+        self.gamepad_velocity = direction * 25.0 * speed_mul;
 
         self.orientation += (look.yx() * Vec2::new(-1., 1.)) * 1.5;
 
@@ -230,4 +248,8 @@ impl CameraController for FpsCamera {
         self.orientation = orientation;
         self.update_vectors();
     }
+
+    fn input_velocity(&self) -> Vec3 {
+        self.input_velocity
+    }
 }
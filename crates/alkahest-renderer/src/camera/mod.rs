@@ -9,7 +9,11 @@ pub mod tween;
 pub mod viewport;
 pub use viewport::Viewport;
 
-use self::{fps::FpsCamera, tween::Tween};
+use self::{
+    fps::FpsCamera,
+    orbit::{GazeFocusMode, OrbitPivot},
+    tween::Tween,
+};
 use crate::{
     ecs::culling::Frustum,
     input::InputState,
@@ -51,6 +55,12 @@ pub trait CameraController {
 
     fn set_position(&mut self, position: Vec3);
     fn set_orientation(&mut self, orientation: Vec2);
+
+    /// Velocity the camera is being moved at by keyboard or gamepad input, in meters per second.
+    /// Excludes tweens and teleports
+    fn input_velocity(&self) -> Vec3 {
+        Vec3::ZERO
+    }
     // fn set_rotation(&mut self, rotation: Quat);
     // fn look_at(&mut self, target: Vec3);
 }
@@ -96,6 +106,20 @@ pub struct Camera {
     pub speed_mul: f32,
     pub smooth_movement: f32,
     pub smooth_look: f32,
+
+    /// Widen the field of view as the camera moves faster
+    pub fov_kick: bool,
+    /// Maximum amount of degrees the field of view is widened by
+    pub fov_kick_strength: f32,
+    /// Current widening of the field of view, in degrees
+    fov_kick_offset: f32,
+    /// Temporary change to the field of view from zooming, in degrees. Doesn't touch the configured FOV
+    fov_zoom_offset: f32,
+
+    /// What focusing on the gaze point does
+    pub gaze_focus: GazeFocusMode,
+    /// Point the camera orbits around when looking around. Cleared as soon as the camera is moved
+    orbit: Option<OrbitPivot>,
 }
 
 impl Camera {
@@ -135,6 +159,14 @@ impl Camera {
             speed_mul: 1.0,
             smooth_movement: 1.0,
             smooth_look: 0.0,
+
+            fov_kick: false,
+            fov_kick_strength: 20.0,
+            fov_kick_offset: 0.0,
+            fov_zoom_offset: 0.0,
+
+            gaze_focus: GazeFocusMode::default(),
+            orbit: None,
         };
 
         camera.update_matrices();
@@ -163,10 +195,12 @@ impl Camera {
         }
         self.speed_mul = (self.speed_mul + scroll * 0.05).clamp(0.05, 25.0);
         self.controller.update_mouse(delta, scroll);
+        self.update_orbit();
         self.update_matrices();
     }
 
     pub fn update(&mut self, input: &InputState, delta_time: f32) {
+        let previous_position = self.position();
         self.controller.update(
             &mut self.tween,
             input,
@@ -175,16 +209,98 @@ impl Camera {
             self.smooth_movement,
             self.smooth_look,
         );
+
+        // Moving the camera in any way stops orbiting
+        if self.position().distance_squared(previous_position) > 1e-8 {
+            self.orbit = None;
+        }
+
+        self.update_fov_kick(delta_time);
         self.update_matrices();
     }
 
+    /// Orbit around the given point when looking around, keeping the current distance to it
+    pub fn set_orbit_pivot(&mut self, pivot: Vec3) {
+        self.orbit = Some(OrbitPivot::new(pivot, self.position()));
+    }
+
+    pub fn orbit_pivot(&self) -> Option<Vec3> {
+        self.orbit.map(|o| o.pivot)
+    }
+
+    /// Moves the camera back onto the orbit after its orientation has changed
+    fn update_orbit(&mut self) {
+        if let Some(orbit) = self.orbit {
+            self.controller
+                .set_position(orbit.camera_position(self.forward()));
+        }
+    }
+
+    /// Speed (in meters per second) at which the FOV kick reaches its full strength
+    const FOV_KICK_MAX_SPEED: f32 = 200.0;
+    /// Upper limit for [`Self::fov_kick_strength`], in degrees
+    pub const FOV_KICK_MAX_STRENGTH: f32 = 45.0;
+
+    fn update_fov_kick(&mut self, delta_time: f32) {
+        // Based on the input velocity rather than the distance moved, so tweens and teleports don't kick the FOV
+        let target = if self.fov_kick {
+            let speed = self.controller.input_velocity().length();
+            (speed / Self::FOV_KICK_MAX_SPEED).clamp(0.0, 1.0)
+                * self
+                    .fov_kick_strength
+                    .clamp(0.0, Self::FOV_KICK_MAX_STRENGTH)
+        } else {
+            0.0
+        };
+
+        // Eases in and out at the same rate the camera position does
+        if self.smooth_movement > 0.0 {
+            self.fov_kick_offset += (target - self.fov_kick_offset)
+                * (delta_time * (15.0 / self.smooth_movement)).min(1.0);
+        } else {
+            self.fov_kick_offset = target;
+        }
+    }
+
+    /// Smallest field of view that can be zoomed to, in degrees
+    const ZOOM_FOV_MIN: f32 = 5.0;
+    /// Largest field of view that can be zoomed to, in degrees
+    const ZOOM_FOV_MAX: f32 = 120.0;
+    /// Factor the field of view is multiplied by per zoom step
+    const ZOOM_STEP: f32 = 0.9;
+
+    /// Zooms in by the given amount of steps (or out, when negative) without changing the configured FOV
+    pub fn zoom(&mut self, steps: f32) {
+        let fov = self.fov();
+        let zoomed = (self.zoomed_fov() * Self::ZOOM_STEP.powf(steps))
+            .clamp(Self::ZOOM_FOV_MIN, Self::ZOOM_FOV_MAX.max(fov));
+        self.fov_zoom_offset = zoomed - fov;
+        self.update_matrices();
+    }
+
+    pub fn reset_zoom(&mut self) {
+        self.fov_zoom_offset = 0.0;
+        self.update_matrices();
+    }
+
+    /// Field of view with the zoom applied, excluding the FOV kick
+    pub fn zoomed_fov(&self) -> f32 {
+        // The configured FOV might have been lowered since zooming in
+        (self.fov() + self.fov_zoom_offset).max(Self::ZOOM_FOV_MIN.min(self.fov()))
+    }
+
     pub fn update_gamepad(&mut self, movement: Vec2, look: Vec2, speed: f32, delta_time: f32) {
         if movement.length() > 0.0 || look.length() > 0.0 {
             self.tween = None;
         }
 
+        if movement.length() > 0.0 {
+            self.orbit = None;
+        }
+
         self.controller
             .update_gamepad(movement, look, self.speed_mul * speed, delta_time);
+        self.update_orbit();
         self.update_matrices();
     }
 
@@ -192,7 +308,10 @@ impl Camera {
         self.world_to_camera = self.controller.view_matrix();
         self.camera_to_world = self.world_to_camera.inverse();
 
-        self.camera_to_projective = self.projection.matrix(self.viewport.aspect_ratio());
+        self.camera_to_projective = self
+            .projection
+            .with_fov_offset(self.zoomed_fov() - self.fov() + self.fov_kick_offset)
+            .matrix(self.viewport.aspect_ratio());
         self.projective_to_camera = self.camera_to_projective.inverse();
 
         self.world_to_projective = self.camera_to_projective * self.world_to_camera;
@@ -241,6 +360,7 @@ impl Camera {
     }
 
     pub fn set_position(&mut self, position: Vec3) {
+        self.orbit = None;
         self.controller.set_position(position);
     }
 
@@ -283,9 +403,18 @@ impl Camera {
         match &self.projection {
             CameraProjection::Perspective { fov, .. }
             | CameraProjection::PerspectiveBounded { fov, .. } => *fov,
-            CameraProjection::Orthographic { .. } => 90.0,
+            CameraProjection::Orthographic { .. } | CameraProjection::OrthographicSized { .. } => {
+                90.0
+            }
         }
     }
+
+    /// Position from which a sphere fits in view vertically, looking in the current direction
+    pub fn framing_position(&self, center: Vec3, radius: f32) -> Vec3 {
+        let half_fov_y = (self.fov() * 0.5).to_radians();
+        let distance = radius / half_fov_y.tan();
+        center - self.forward().normalize() * distance
+    }
 }
 
 impl View for Camera {
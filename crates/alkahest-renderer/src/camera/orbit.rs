@@ -1 +1,32 @@
+use glam::Vec3;
 
+/// What focusing on the gaze point does
+#[derive(Default, Clone, Copy, PartialEq, strum::EnumIter, strum::Display)]
+pub enum GazeFocusMode {
+    /// Fly towards the gaze point, stopping a fixed distance in front of it
+    #[default]
+    Dolly,
+    /// Keep the camera in place and orbit around the gaze point when looking around
+    OrbitPivot,
+}
+
+/// Point the camera rotates around when looking around, at a fixed distance
+#[derive(Clone, Copy)]
+pub struct OrbitPivot {
+    pub pivot: Vec3,
+    pub distance: f32,
+}
+
+impl OrbitPivot {
+    pub fn new(pivot: Vec3, camera_position: Vec3) -> Self {
+        Self {
+            pivot,
+            distance: camera_position.distance(pivot),
+        }
+    }
+
+    /// Position of the camera when looking in the given direction
+    pub fn camera_position(&self, forward: Vec3) -> Vec3 {
+        self.pivot - forward * self.distance
+    }
+}
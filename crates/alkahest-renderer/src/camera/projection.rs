@@ -14,6 +14,11 @@ pub enum CameraProjection {
     Orthographic {
         extents: glam::Vec3,
     },
+    /// Orthographic projection in front of the camera, following the aspect ratio of the viewport
+    OrthographicSized {
+        /// Half the height of the view, in meters
+        size: f32,
+    },
 }
 
 impl CameraProjection {
@@ -27,10 +32,41 @@ impl CameraProjection {
         Self::PerspectiveBounded { fov, near, far }
     }
 
+    /// Returns this projection with the field of view widened by `offset` degrees. Orthographic projections are left unchanged
+    pub fn with_fov_offset(&self, offset: f32) -> Self {
+        match self.clone() {
+            Self::Perspective { fov, near } => Self::Perspective {
+                fov: (fov + offset).min(179.0),
+                near,
+            },
+            Self::PerspectiveBounded { fov, near, far } => Self::PerspectiveBounded {
+                fov: (fov + offset).min(179.0),
+                near,
+                far,
+            },
+            p => p,
+        }
+    }
+
+    /// Distance covered in front of the camera by [`Self::OrthographicSized`]
+    pub const ORTHOGRAPHIC_DEPTH: f32 = 10000.0;
+
     pub fn orthographic(extents: glam::Vec3) -> Self {
         Self::Orthographic { extents }
     }
 
+    /// `size` is half the height of the view, in meters
+    pub fn orthographic_sized(size: f32) -> Self {
+        Self::OrthographicSized { size }
+    }
+
+    pub fn is_orthographic(&self) -> bool {
+        matches!(
+            self,
+            Self::Orthographic { .. } | Self::OrthographicSized { .. }
+        )
+    }
+
     pub fn matrix(&self, aspect: f32) -> glam::Mat4 {
         match self {
             Self::Perspective { fov, near } => {
@@ -42,6 +78,15 @@ impl CameraProjection {
             Self::Orthographic { extents } => glam::Mat4::orthographic_rh(
                 -extents.x, extents.x, -extents.y, extents.y, -extents.z, extents.z,
             ),
+            // Near plane at the camera and far plane in front of it, swapped to get reverse Z like the infinite perspective projection
+            Self::OrthographicSized { size } => glam::Mat4::orthographic_rh(
+                -size * aspect,
+                size * aspect,
+                -size,
+                *size,
+                Self::ORTHOGRAPHIC_DEPTH,
+                0.0,
+            ),
         }
     }
 }
@@ -10,7 +10,7 @@ use super::visibility::VisibilityBundle;
 #[derive(Component, Copy, Clone)]
 pub struct EntityWorldId(pub u64);
 
-#[derive(Component, strum::Display, Copy, Clone, PartialEq, Eq)]
+#[derive(Component, strum::Display, strum::EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum ResourceOrigin {
     Map,
 
@@ -19,6 +19,27 @@ pub enum ResourceOrigin {
     Ambient,
 }
 
+impl ResourceOrigin {
+    /// Marker shown next to node icons
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            ResourceOrigin::Map => "M",
+            ResourceOrigin::Activity => "A",
+            ResourceOrigin::ActivityBruteforce => "Ab",
+            ResourceOrigin::Ambient => "AM",
+        }
+    }
+
+    pub fn color(&self) -> Color32 {
+        match self {
+            ResourceOrigin::Map => Color32::LIGHT_RED,
+            ResourceOrigin::Activity => Color32::GREEN,
+            ResourceOrigin::ActivityBruteforce => Color32::RED,
+            ResourceOrigin::Ambient => Color32::from_rgb(0, 255, 255),
+        }
+    }
+}
+
 // pub struct HavokShape(pub TagHash, pub Option<CustomDebugShape>);
 
 pub struct ActivityGroup(pub u32);
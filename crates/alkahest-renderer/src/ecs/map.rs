@@ -131,6 +131,13 @@ pub struct NodeMetadata {
     pub name: Option<String>,
 }
 
+/// Models that failed to load while building the map.
+/// Entities referencing these are still spawned, just without a model
+#[derive(Resource, Default)]
+pub struct MapLoadFailures {
+    pub models: Vec<TagHash>,
+}
+
 #[derive(Resource)]
 pub struct MapStaticAO {
     pub ao_buffer: VertexBuffer,
@@ -11,9 +11,11 @@ pub mod map;
 pub mod render;
 pub mod resources;
 pub mod route;
+pub mod statistics;
 pub mod tags;
 pub mod transform;
 pub mod utility;
+pub mod validation;
 pub mod visibility;
 
 pub type Scene = bevy_ecs::world::World;
@@ -173,6 +173,7 @@ impl DecoratorRenderer {
                         0,
                         instance_start,
                     );
+                    renderer.gpu.count_draw_call();
                 },
             )?;
         }
@@ -1,35 +1,55 @@
+use std::{
+    collections::hash_map::Entry,
+    f32::consts::FRAC_1_SQRT_2,
+    ffi::CStr,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
 use alkahest_data::{
+    buffers::{IndexBufferHeader, VertexBufferHeader},
+    dxgi::DxgiFormat,
     entity::{SDynamicMesh, SDynamicMeshPart, SDynamicModel, Unk808072c5},
+    geometry::EPrimitiveType,
     occlusion::Aabb,
     technique::TfxScopeBits,
     tfx::{TfxFeatureRenderer, TfxRenderStage, TfxShaderStage},
 };
 use alkahest_pm::package_manager;
+use anyhow::Context;
 use bevy_ecs::{
-    change_detection::DetectChanges, component::Component, entity::Entity, system::Query,
+    change_detection::DetectChanges,
+    component::Component,
+    entity::Entity,
+    query::With,
+    system::{In, Query, Res},
     world::Ref,
 };
 use destiny_pkg::TagHash;
-use glam::{Vec4, Vec4Swizzles};
+use glam::{Mat4, Vec2, Vec3, Vec4, Vec4Swizzles};
+use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::json;
 use tiger_parse::PackageManagerExt;
 
 use crate::{
     ecs::{
         channels::ObjectChannels,
         render::{decorators::DecoratorRenderer, static_geometry::ModelBuffers},
+        resources::SelectedEntity,
         transform::Transform,
         visibility::{ViewVisibility, VisibilityHelper},
         Scene,
     },
-    gpu::buffer::ConstantBuffer,
+    gpu::{buffer::ConstantBuffer, global_state::RenderStates},
     gpu_event,
     handle::Handle,
     loaders::AssetManager,
-    renderer::Renderer,
+    renderer::{Renderer, RendererShared},
     tfx::{externs, scope::ScopeSkinning, technique::Technique, view::RenderStageSubscriptions},
-    util::packages::TagHashExt,
+    util::{color::Color, packages::TagHashExt, Hocus},
 };
 
 pub struct DynamicModel {
@@ -45,6 +65,8 @@ pub struct DynamicModel {
 
     pub selected_mesh: usize,
     pub selected_variant: usize,
+    /// Index of a part in the selected mesh. When set, only this part is drawn into the selection outline
+    pub highlighted_part: Option<usize>,
     variant_count: usize,
 
     identifier_count: usize,
@@ -120,6 +142,7 @@ impl DynamicModel {
             selected_variant: 0,
             variant_count,
             selected_mesh: 0,
+            highlighted_part: None,
             identifier_count,
             mesh_buffers,
             technique_map,
@@ -147,18 +170,90 @@ impl DynamicModel {
         self.identifier_count
     }
 
+    /// Number of triangles in the highest detail LOD of all meshes
+    pub fn triangle_count(&self) -> u64 {
+        let mut drawn = FxHashSet::default();
+        self.model
+            .meshes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, mesh)| mesh.parts.iter().map(move |p| (i, p)))
+            .filter(|(_, p)| p.lod_category.lod_level() == 0)
+            // Parts are shared between render stages
+            .filter(|(i, p)| drawn.insert((*i, p.index_start, p.index_count)))
+            .map(|(_, p)| p.primitive_type.triangle_count(p.index_count) as u64)
+            .sum()
+    }
+
+    /// Readable tree of the meshes and parts in this model, with the render stages they're drawn in
+    pub fn describe(&self) -> String {
+        use std::fmt::Write as _;
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "Dynamic model {} ({:?}): {} meshes, {} variants, {} identifiers",
+            self.hash,
+            self.feature_type,
+            self.mesh_count(),
+            self.variant_count,
+            self.identifier_count
+        )
+        .unwrap();
+
+        for (mesh_index, mesh) in self.model.meshes.iter().enumerate() {
+            writeln!(out, "  Mesh {mesh_index}: {} parts", mesh.parts.len()).unwrap();
+
+            let stages = self.mesh_stages.get(mesh_index).copied();
+            for stage in stages.iter().flat_map(|s| s.stages()) {
+                let range = mesh.get_range_for_stage(stage);
+                writeln!(
+                    out,
+                    "    {stage:?}: parts {}..{}, input layout {}",
+                    range.start,
+                    range.end,
+                    mesh.get_input_layout_for_stage(stage)
+                )
+                .unwrap();
+            }
+
+            for (part_index, part) in mesh.parts.iter().enumerate() {
+                write!(
+                    out,
+                    "    Part {part_index}: indices {}..{}, {:?}, {:?}, ID {}, technique {}",
+                    part.index_start,
+                    part.index_start + part.index_count,
+                    part.primitive_type,
+                    part.lod_category,
+                    part.external_identifier,
+                    part.technique
+                )
+                .unwrap();
+                if part.variant_shader_index != u16::MAX {
+                    write!(out, ", variant shader {}", part.variant_shader_index).unwrap();
+                }
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
     fn get_variant_technique(&self, index: u16, variant: usize) -> Option<Handle<Technique>> {
         if index == u16::MAX {
             None
         } else {
-            self.technique_map
-                .get(index as usize)
-                .as_ref()
-                .map(|variant_range| {
-                    self.techniques[variant_range.technique_start as usize
-                        + (variant % variant_range.technique_count as usize)]
-                        .clone()
-                })
+            let variant_range = self.technique_map.get(index as usize)?;
+            if variant_range.technique_count == 0 {
+                return None;
+            }
+
+            self.techniques
+                .get(
+                    variant_range.technique_start as usize
+                        + (variant % variant_range.technique_count as usize),
+                )
+                .cloned()
         }
     }
 
@@ -180,6 +275,7 @@ impl DynamicModel {
                     .gpu
                     .lock_context()
                     .DrawIndexed(part.index_count, part.index_start, 0);
+                renderer.gpu.count_draw_call();
             },
         )
     }
@@ -219,29 +315,44 @@ impl DynamicModel {
             "DynamicModel::draw",
             &format!("mesh={}", self.selected_mesh)
         );
-        // ensure!(self.selected_mesh < self.mesh_count(), "Invalid mesh index");
-        // ensure!(
-        //     self.selected_variant < self.variant_count() || self.variant_count() == 0,
-        //     "Material variant out of range"
-        // );
-
-        let mesh = &self.model.meshes[self.selected_mesh];
-        let stages = &self.mesh_stages[self.selected_mesh];
+        // The selection can be edited from the inspector, so an out of range mesh is skipped instead of taking the frame down
+        let (Some(mesh), Some(stages), Some(mesh_buffers), Some(part_techniques)) = (
+            self.model.meshes.get(self.selected_mesh),
+            self.mesh_stages.get(self.selected_mesh),
+            self.mesh_buffers.get(self.selected_mesh),
+            self.part_techniques.get(self.selected_mesh),
+        ) else {
+            return Ok(());
+        };
         if !stages.is_subscribed(render_stage) {
             return Ok(());
         }
 
-        renderer
+        if !renderer
             .gpu
-            .set_input_layout(mesh.get_input_layout_for_stage(render_stage) as usize);
-        self.mesh_buffers[self.selected_mesh].bind(renderer);
+            .set_input_layout(mesh.get_input_layout_for_stage(render_stage) as usize)
+        {
+            return Ok(());
+        }
+        mesh_buffers.bind(renderer);
+        let lod_level = renderer.select_lod_level(mesh.parts.iter().map(|p| p.lod_category));
         for part_index in mesh.get_range_for_stage(render_stage) {
-            let part = &mesh.parts[part_index];
+            let (Some(part), Some(part_technique)) =
+                (mesh.parts.get(part_index), part_techniques.get(part_index))
+            else {
+                continue;
+            };
             if identifier != u16::MAX && part.external_identifier != identifier {
                 continue;
             }
 
-            if !part.lod_category.is_highest_detail() {
+            if renderer.pickbuffer.is_drawing_outline
+                && self.highlighted_part.is_some_and(|p| p != part_index)
+            {
+                continue;
+            }
+
+            if part.lod_category.lod_level() != lod_level {
                 continue;
             }
 
@@ -249,9 +360,7 @@ impl DynamicModel {
                 self.get_variant_technique(part.variant_shader_index, self.selected_variant);
 
             let mut all_scopes = TfxScopeBits::empty();
-            if let Some(technique) =
-                renderer.get_technique_shared(&self.part_techniques[self.selected_mesh][part_index])
-            {
+            if let Some(technique) = renderer.get_technique_shared(part_technique) {
                 technique
                     .bind_with_channels(renderer, object_channels)
                     .expect("Failed to bind technique");
@@ -269,9 +378,11 @@ impl DynamicModel {
                 all_scopes |= technique.used_scopes;
             }
 
-            if stages.contains(RenderStageSubscriptions::COMPUTE_SKINNING)
-                || all_scopes.contains(TfxScopeBits::SKINNING)
-            {
+            let use_skinning_vs = renderer.settings.force_skinning_vs.unwrap_or_else(|| {
+                stages.contains(RenderStageSubscriptions::COMPUTE_SKINNING)
+                    || all_scopes.contains(TfxScopeBits::SKINNING)
+            });
+            if use_skinning_vs {
                 unsafe {
                     renderer
                         .gpu
@@ -292,6 +403,542 @@ impl DynamicModel {
     pub fn occlusion_bounds(&self) -> Aabb {
         Aabb::from_center_extents(self.model.model_offset.xyz(), self.model.model_scale.xyz())
     }
+
+    /// Writes the highest detail parts of every mesh to a Wavefront OBJ file, with a group per part.
+    ///
+    /// Vertices are decoded with the input layout of the stage each part is drawn in (see [`Self::decode_vertices`]), so a mesh gets a block of vertices for every layout its parts use
+    pub fn export_obj(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        writeln!(w, "# Exported from {}", self.hash)?;
+
+        // OBJ indices are 1-based and global to the file
+        let mut position_base = 1;
+        let mut texcoord_base = 1;
+        for (mesh_index, mesh) in self.model.meshes.iter().enumerate() {
+            let indices = read_index_buffer(mesh.index_buffer)
+                .with_context(|| format!("Failed to read index buffer of mesh {mesh_index}"))?;
+
+            // Base position and texture coordinate index of the vertices written for every input layout
+            let mut vertex_blocks: FxHashMap<u8, (u32, Option<u32>)> = FxHashMap::default();
+            for (part_index, stages) in highest_detail_parts(mesh).into_values() {
+                let part = &mesh.parts[part_index];
+                let start = part.index_start as usize;
+                let Some(part_indices) = indices.get(start..start + part.index_count as usize)
+                else {
+                    warn!("Part {part_index} of mesh {mesh_index} is out of bounds, skipping");
+                    continue;
+                };
+
+                let input_layout = part_input_layout(mesh, &stages);
+                let (positions, texcoords) = match vertex_blocks.entry(input_layout) {
+                    Entry::Occupied(e) => *e.get(),
+                    Entry::Vacant(e) => {
+                        let vertices =
+                            self.decode_vertices(mesh, input_layout).with_context(|| {
+                                format!("Failed to decode vertices of mesh {mesh_index}")
+                            })?;
+
+                        let positions = position_base;
+                        for p in &vertices.positions {
+                            writeln!(w, "v {} {} {}", p.x, p.y, p.z)?;
+                        }
+                        position_base += vertices.positions.len() as u32;
+
+                        let texcoords = vertices.texcoords.as_ref().map(|_| texcoord_base);
+                        for uv in vertices.texcoords.iter().flatten() {
+                            // OBJ texture coordinates start at the bottom
+                            writeln!(w, "vt {} {}", uv.x, 1.0 - uv.y)?;
+                            texcoord_base += 1;
+                        }
+
+                        *e.insert((positions, texcoords))
+                    }
+                };
+
+                writeln!(w, "g mesh{mesh_index}_part{part_index}")?;
+                for [a, b, c] in part_triangles(part.primitive_type, part_indices) {
+                    if let Some(texcoords) = texcoords {
+                        let [a, b, c] = [a, b, c].map(|i| (positions + i, texcoords + i));
+                        writeln!(w, "f {}/{} {}/{} {}/{}", a.0, a.1, b.0, b.1, c.0, c.1)?;
+                    } else {
+                        let [a, b, c] = [a, b, c].map(|i| positions + i);
+                        writeln!(w, "f {a} {b} {c}")?;
+                    }
+                }
+            }
+        }
+
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Writes the highest detail parts of every mesh to a glTF 2.0 file, with the vertex data in a `.bin` file next to it.
+    ///
+    /// Every part becomes a primitive with a placeholder material named after its technique, and the render stages the part is drawn in (along with their input layouts) are stored in the primitive extras.
+    /// Vertices are decoded the same way as [`Self::export_obj`] does, with an accessor per input layout used by the mesh.
+    /// `mesh_to_world` is used as the transform of every mesh node, which sit under a root node that converts from Z-up to glTF's Y-up
+    pub fn export_gltf(&self, mesh_to_world: Mat4, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let bin_path = path.with_extension("bin");
+        let bin_name = bin_path
+            .file_name()
+            .context("Invalid export path")?
+            .to_string_lossy()
+            .to_string();
+
+        let mut buffers = GltfBuffers::default();
+        let mut materials: IndexSet<TagHash> = IndexSet::new();
+        let mut meshes = vec![];
+        let mut nodes = vec![];
+        for (mesh_index, mesh) in self.model.meshes.iter().enumerate() {
+            let indices = read_index_buffer(mesh.index_buffer)
+                .with_context(|| format!("Failed to read index buffer of mesh {mesh_index}"))?;
+
+            // Vertex count and primitive attributes of every input layout used by the mesh
+            let mut layout_attributes: FxHashMap<u8, (usize, serde_json::Value)> =
+                FxHashMap::default();
+            let mut primitives = vec![];
+            for (part_index, stages) in highest_detail_parts(mesh).into_values() {
+                let part = &mesh.parts[part_index];
+                let start = part.index_start as usize;
+                let Some(part_indices) = indices.get(start..start + part.index_count as usize)
+                else {
+                    warn!("Part {part_index} of mesh {mesh_index} is out of bounds, skipping");
+                    continue;
+                };
+
+                let input_layout = part_input_layout(mesh, &stages);
+                let (vertex_count, attributes) = match layout_attributes.entry(input_layout) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => {
+                        let vertices =
+                            self.decode_vertices(mesh, input_layout).with_context(|| {
+                                format!("Failed to decode vertices of mesh {mesh_index}")
+                            })?;
+                        // Accessors can't be empty, the parts using these vertices are skipped below
+                        let attributes = if vertices.positions.is_empty() {
+                            serde_json::Value::Null
+                        } else {
+                            buffers.push_vertices(&vertices)
+                        };
+                        e.insert((vertices.positions.len(), attributes))
+                    }
+                };
+                let vertex_count = *vertex_count;
+
+                let triangles = part_triangles(part.primitive_type, part_indices)
+                    .into_iter()
+                    .filter(|t| t.iter().all(|&i| (i as usize) < vertex_count))
+                    .collect_vec();
+                if triangles.is_empty() {
+                    continue;
+                }
+
+                let index_accessor = buffers.push_accessor(
+                    bytemuck::cast_slice(&triangles),
+                    triangles.len() * 3,
+                    "SCALAR",
+                    GLTF_UNSIGNED_INT,
+                    GLTF_ELEMENT_ARRAY_BUFFER,
+                );
+
+                let render_stages = stages
+                    .iter()
+                    .map(|&stage| {
+                        json!({
+                            "stage": format!("{stage:?}"),
+                            "input_layout": mesh.get_input_layout_for_stage(stage),
+                        })
+                    })
+                    .collect_vec();
+                let mut primitive = json!({
+                    "attributes": attributes.clone(),
+                    "indices": index_accessor,
+                    "extras": {
+                        "part": part_index,
+                        "render_stages": render_stages,
+                    },
+                });
+                if let Some(technique) = self.part_technique(part) {
+                    primitive["material"] = json!(materials.insert_full(technique).0);
+                }
+
+                primitives.push(primitive);
+            }
+
+            if primitives.is_empty() {
+                continue;
+            }
+
+            nodes.push(json!({
+                "name": format!("mesh{mesh_index}"),
+                "mesh": meshes.len(),
+                "matrix": mesh_to_world.to_cols_array(),
+            }));
+            meshes.push(json!({
+                "name": format!("mesh{mesh_index}"),
+                "primitives": primitives,
+            }));
+        }
+
+        anyhow::ensure!(!meshes.is_empty(), "Model has no exportable meshes");
+
+        // Rotates -90 degrees around X, turning Z-up into Y-up
+        let root = json!({
+            "name": self.hash.to_string(),
+            "rotation": [-FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2],
+            "children": (1..=nodes.len()).collect_vec(),
+        });
+        nodes.insert(0, root);
+
+        let mut document = json!({
+            "asset": {
+                "version": "2.0",
+                "generator": concat!("Alkahest ", env!("CARGO_PKG_VERSION")),
+            },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": nodes,
+            "meshes": meshes,
+            "accessors": buffers.accessors,
+            "bufferViews": buffers.buffer_views,
+            "buffers": [{ "uri": bin_name, "byteLength": buffers.data.len() }],
+        });
+        // glTF doesn't allow empty arrays
+        if !materials.is_empty() {
+            document["materials"] = materials
+                .iter()
+                .map(|technique| json!({ "name": technique.to_string() }))
+                .collect();
+        }
+
+        std::fs::write(&bin_path, &buffers.data)
+            .with_context(|| format!("Failed to write {}", bin_path.display()))?;
+        let mut w = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut w, &document)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// The technique of a part, falling back to the technique of the selected material variant
+    fn part_technique(&self, part: &SDynamicMeshPart) -> Option<TagHash> {
+        if part.technique.is_some() {
+            return Some(part.technique);
+        }
+
+        self.get_variant_technique(part.variant_shader_index, self.selected_variant)
+            .and_then(|t| t.id().tiger_taghash())
+    }
+}
+
+/// Parts of a mesh with the highest level of detail, keyed by their index range as parts are shared between render stages.
+/// Every entry holds the index of the part and the stages it's drawn in
+fn highest_detail_parts(mesh: &SDynamicMesh) -> IndexMap<(u32, u32), (usize, Vec<TfxRenderStage>)> {
+    let mut parts: IndexMap<(u32, u32), (usize, Vec<TfxRenderStage>)> = IndexMap::new();
+    for stage in TfxRenderStage::VARIANTS {
+        for part_index in mesh.get_range_for_stage(stage) {
+            let Some(part) = mesh.parts.get(part_index) else {
+                continue;
+            };
+            if !part.lod_category.is_highest_detail() {
+                continue;
+            }
+
+            parts
+                .entry((part.index_start, part.index_count))
+                .or_insert_with(|| (part_index, vec![]))
+                .1
+                .push(stage);
+        }
+    }
+
+    parts
+}
+
+/// Input layout to decode the vertices of a part with. Prefers the GBuffer stage, which has the most complete layout
+fn part_input_layout(mesh: &SDynamicMesh, stages: &[TfxRenderStage]) -> u8 {
+    let stage = stages
+        .iter()
+        .copied()
+        .find(|&s| s == TfxRenderStage::GenerateGbuffer)
+        .or(stages.first().copied())
+        .unwrap_or(TfxRenderStage::GenerateGbuffer);
+    mesh.get_input_layout_for_stage(stage)
+}
+
+/// Positions and texture coordinates of a mesh, see [`DynamicModel::decode_vertices`]
+struct DecodedVertices {
+    positions: Vec<Vec3>,
+    /// Only present if the input layout has texture coordinates for every vertex
+    texcoords: Option<Vec<Vec2>>,
+}
+
+impl DynamicModel {
+    /// Decodes the positions (`POSITION0`) and texture coordinates (`TEXCOORD0`) of a mesh as described by an input layout.
+    ///
+    /// SNORM attributes are transformed with `model_scale`/`model_offset` and `texcoord_scale`/`texcoord_offset` like the vertex shaders do, float attributes are used as-is
+    fn decode_vertices(
+        &self,
+        mesh: &SDynamicMesh,
+        input_layout: u8,
+    ) -> anyhow::Result<DecodedVertices> {
+        let position = VertexAttribute::find(input_layout, c"POSITION", 0)
+            .with_context(|| format!("Input layout {input_layout} has no position"))?;
+        let positions = position
+            .read(mesh)
+            .with_context(|| format!("Failed to read positions (input layout {input_layout})"))?
+            .into_iter()
+            .map(|v| {
+                if position.is_snorm() {
+                    v.xyz() * self.model.model_scale.xyz() + self.model.model_offset.xyz()
+                } else {
+                    v.xyz()
+                }
+            })
+            .collect_vec();
+
+        let texcoords = match VertexAttribute::find(input_layout, c"TEXCOORD", 0) {
+            Some(texcoord) => {
+                let texcoords = texcoord
+                    .read(mesh)
+                    .with_context(|| {
+                        format!("Failed to read texture coordinates (input layout {input_layout})")
+                    })?
+                    .into_iter()
+                    .take(positions.len())
+                    .map(|v| {
+                        if texcoord.is_snorm() {
+                            v.xy() * self.model.texcoord_scale + self.model.texcoord_offset
+                        } else {
+                            v.xy()
+                        }
+                    })
+                    .collect_vec();
+                Some(texcoords).filter(|t| t.len() == positions.len())
+            }
+            None => None,
+        };
+
+        Ok(DecodedVertices {
+            positions,
+            texcoords,
+        })
+    }
+}
+
+/// Location and format of a vertex attribute in the vertex buffers of a mesh
+struct VertexAttribute {
+    buffer_index: u32,
+    offset: usize,
+    format: DxgiFormat,
+}
+
+impl VertexAttribute {
+    /// Looks up an attribute in one of the input layouts in [`RenderStates::input_layout_descs`].
+    /// Elements are appended without padding, so the offset is the size of the elements before it in the same buffer
+    fn find(input_layout: u8, semantic_name: &CStr, semantic_index: u32) -> Option<Self> {
+        let elements = RenderStates::input_layout_descs()
+            .get(input_layout as usize)?
+            .elements;
+        let index = elements
+            .iter()
+            .position(|e| e.semantic_name == semantic_name && e.semantic_index == semantic_index)?;
+        let element = &elements[index];
+
+        Some(Self {
+            buffer_index: element.buffer_index,
+            offset: elements[..index]
+                .iter()
+                .filter(|e| e.buffer_index == element.buffer_index)
+                .map(|e| e._stride as usize)
+                .sum(),
+            format: element.format,
+        })
+    }
+
+    fn is_snorm(&self) -> bool {
+        matches!(
+            self.format,
+            DxgiFormat::R16G16B16A16_SNORM | DxgiFormat::R16G16_SNORM
+        )
+    }
+
+    /// Reads the attribute for every vertex in the buffer, with missing components set to 0
+    fn read(&self, mesh: &SDynamicMesh) -> anyhow::Result<Vec<Vec4>> {
+        let hash = match self.buffer_index {
+            0 => mesh.vertex0_buffer,
+            1 => mesh.vertex1_buffer,
+            i => anyhow::bail!("Unsupported vertex buffer index {i}"),
+        };
+
+        let (size, components, is_float) = match self.format {
+            DxgiFormat::R16G16B16A16_SNORM => (8, 4, false),
+            DxgiFormat::R16G16_SNORM => (4, 2, false),
+            DxgiFormat::R32G32B32A32_FLOAT => (16, 4, true),
+            DxgiFormat::R32G32B32_FLOAT => (12, 3, true),
+            DxgiFormat::R32G32_FLOAT => (8, 2, true),
+            f => anyhow::bail!("Unsupported vertex format {f:?}"),
+        };
+
+        let (data, stride) = read_vertex_buffer(hash, self.offset + size)?;
+        Ok(data
+            .chunks_exact(stride)
+            .map(|v| {
+                let v = &v[self.offset..self.offset + size];
+                let mut out = [0.0; 4];
+                for (i, c) in out.iter_mut().take(components).enumerate() {
+                    *c = if is_float {
+                        f32::from_le_bytes([v[i * 4], v[i * 4 + 1], v[i * 4 + 2], v[i * 4 + 3]])
+                    } else {
+                        read_snorm16(v, i * 2)
+                    };
+                }
+                Vec4::from_array(out)
+            })
+            .collect())
+    }
+}
+
+/// Reads a vertex buffer, making sure every vertex is at least `min_stride` bytes long
+fn read_vertex_buffer(hash: TagHash, min_stride: usize) -> anyhow::Result<(Vec<u8>, usize)> {
+    let header: VertexBufferHeader = package_manager().read_tag_struct(hash)?;
+    let entry = package_manager()
+        .get_entry(hash)
+        .context("Entry not found")?;
+    let data = package_manager().read_tag(entry.reference)?;
+    anyhow::ensure!(
+        header.stride as usize >= min_stride,
+        "Invalid vertex stride {} (expected at least {min_stride})",
+        header.stride
+    );
+
+    Ok((data, header.stride as usize))
+}
+
+fn read_index_buffer(hash: TagHash) -> anyhow::Result<Vec<u32>> {
+    let header: IndexBufferHeader = package_manager().read_tag_struct(hash)?;
+    let entry = package_manager()
+        .get_entry(hash)
+        .context("Entry not found")?;
+    let data = package_manager().read_tag(entry.reference)?;
+
+    Ok(if header.is_32bit {
+        data.chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    } else {
+        data.chunks_exact(2)
+            .map(|c| match u16::from_le_bytes([c[0], c[1]]) {
+                u16::MAX => u32::MAX,
+                i => i as u32,
+            })
+            .collect()
+    })
+}
+
+fn read_snorm16(data: &[u8], offset: usize) -> f32 {
+    let v = i16::from_le_bytes([data[offset], data[offset + 1]]);
+    (v as f32 / i16::MAX as f32).max(-1.0)
+}
+
+/// Converts the indices of a part to a triangle list, dropping degenerate triangles.
+/// Triangle strips are split at restart indices (`u32::MAX`)
+fn part_triangles(primitive_type: EPrimitiveType, indices: &[u32]) -> Vec<[u32; 3]> {
+    match primitive_type {
+        EPrimitiveType::Triangles => indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect(),
+        EPrimitiveType::TriangleStrip => indices
+            .split(|&i| i == u32::MAX)
+            .flat_map(|strip| {
+                strip.windows(3).enumerate().map(|(i, t)| {
+                    // Every other triangle in a strip has its winding flipped
+                    if i % 2 == 0 {
+                        [t[0], t[1], t[2]]
+                    } else {
+                        [t[1], t[0], t[2]]
+                    }
+                })
+            })
+            .filter(|[a, b, c]| a != b && b != c && a != c)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+const GLTF_FLOAT: u32 = 5126;
+const GLTF_UNSIGNED_INT: u32 = 5125;
+const GLTF_ARRAY_BUFFER: u32 = 34962;
+const GLTF_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Binary buffer of a glTF export, along with the views and accessors pointing into it
+#[derive(Default)]
+struct GltfBuffers {
+    data: Vec<u8>,
+    buffer_views: Vec<serde_json::Value>,
+    accessors: Vec<serde_json::Value>,
+}
+
+impl GltfBuffers {
+    /// Appends `data` in its own buffer view, returning the index of the accessor for it.
+    /// All components are 4 bytes, so every view stays aligned
+    fn push_accessor(
+        &mut self,
+        data: &[u8],
+        count: usize,
+        ty: &str,
+        component_type: u32,
+        target: u32,
+    ) -> usize {
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.data.len(),
+            "byteLength": data.len(),
+            "target": target,
+        }));
+        self.data.extend_from_slice(data);
+
+        self.accessors.push(json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": ty,
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// Appends the positions and texture coordinates of a mesh, returning the primitive attributes pointing to them
+    fn push_vertices(&mut self, vertices: &DecodedVertices) -> serde_json::Value {
+        let positions = &vertices.positions;
+        let (min, max) = positions
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), &p| {
+                (min.min(p), max.max(p))
+            });
+        let position_accessor = self.push_accessor(
+            bytemuck::cast_slice(positions),
+            positions.len(),
+            "VEC3",
+            GLTF_FLOAT,
+            GLTF_ARRAY_BUFFER,
+        );
+        self.accessors[position_accessor]["min"] = json!(min.to_array());
+        self.accessors[position_accessor]["max"] = json!(max.to_array());
+        let mut attributes = json!({ "POSITION": position_accessor });
+
+        if let Some(texcoords) = &vertices.texcoords {
+            attributes["TEXCOORD_0"] = json!(self.push_accessor(
+                bytemuck::cast_slice(texcoords),
+                texcoords.len(),
+                "VEC2",
+                GLTF_FLOAT,
+                GLTF_ARRAY_BUFFER,
+            ));
+        }
+
+        attributes
+    }
 }
 
 #[derive(Component)]
@@ -436,28 +1083,47 @@ pub fn draw_dynamic_model_system(
         _ => 99,
     });
 
-    for (e, _feature_type) in entities {
+    for (e, feature_type) in entities {
         let dynamic = scene.get::<DynamicModelComponent>(e).unwrap();
         let object_channels = scene.get::<ObjectChannels>(e);
 
-        renderer.pickbuffer.with_entity(e, || {
-            dynamic
-                .draw(renderer, render_stage, object_channels)
-                .unwrap();
+        // Passes that replace the pixel shader themselves (eg. picking) take precedence
+        let tint_water = renderer.settings.water_tint
+            && feature_type == TfxFeatureRenderer::Water
+            && renderer.gpu.custom_pixel_shader.is_none();
+        if tint_water {
+            *renderer.gpu.custom_pixel_shader.pocus() =
+                Some(renderer.gpu.util_resources.water_tint_ps.clone());
+        }
+
+        renderer.with_lod(scene.get::<Aabb>(e), scene.get::<Transform>(e), || {
+            renderer.pickbuffer.with_entity(e, || {
+                renderer.with_feature_color(render_stage, feature_type, || {
+                    dynamic
+                        .draw(renderer, render_stage, object_channels)
+                        .unwrap();
+                });
+            });
         });
+
+        if tint_water {
+            *renderer.gpu.custom_pixel_shader.pocus() = None;
+        }
     }
 
     if renderer.should_render(Some(render_stage), Some(TfxFeatureRenderer::SpeedtreeTrees)) {
-        for (e, decorator, vis) in scene
-            .query::<(Entity, &DecoratorRenderer, Option<&ViewVisibility>)>()
-            .iter(scene)
-        {
-            if vis.is_visible(renderer.active_view) {
-                renderer.pickbuffer.with_entity(e, || {
-                    decorator.draw(renderer, render_stage).unwrap();
-                });
+        renderer.with_feature_color(render_stage, TfxFeatureRenderer::SpeedtreeTrees, || {
+            for (e, decorator, vis) in scene
+                .query::<(Entity, &DecoratorRenderer, Option<&ViewVisibility>)>()
+                .iter(scene)
+            {
+                if vis.is_visible(renderer.active_view) {
+                    renderer.pickbuffer.with_entity(e, || {
+                        decorator.draw(renderer, render_stage).unwrap();
+                    });
+                }
             }
-        }
+        });
     }
 }
 
@@ -520,3 +1186,33 @@ pub fn update_dynamic_model_system(
         }
     }
 }
+
+/// Marks an entity whose model failed to load, so it can still be shown as a placeholder
+#[derive(Component)]
+pub struct ModelPlaceholder {
+    pub model: TagHash,
+}
+
+pub fn draw_model_placeholders_system(
+    In(renderer): In<RendererShared>,
+    selected: Res<SelectedEntity>,
+    q_placeholder: Query<(Entity, &Transform, Option<&ViewVisibility>), With<ModelPlaceholder>>,
+) {
+    // There's no way to know the bounds of a model that didn't load, so the placeholder is a 1m cube
+    const PLACEHOLDER_SIZE: f32 = 1.0;
+
+    for (e, transform, vis) in q_placeholder.iter() {
+        if !vis.is_visible(renderer.active_view) {
+            continue;
+        }
+
+        let color = selected.select_fade_color(Color::from_rgb(1.0, 0.0, 1.0), Some(e));
+        let mat =
+            transform.local_to_world() * Mat4::from_scale(Vec3::splat(PLACEHOLDER_SIZE * 0.5));
+        renderer.immediate.cube(
+            mat,
+            color * Color::from_rgba_premultiplied(1.0, 1.0, 1.0, 0.35),
+        );
+        renderer.immediate.cube_outline(mat, color);
+    }
+}
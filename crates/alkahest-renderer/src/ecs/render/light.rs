@@ -31,7 +31,7 @@ use crate::{
         visibility::{ViewVisibility, VisibilityHelper},
         Scene,
     },
-    gpu::GpuContext,
+    gpu::{DepthMode, GpuContext},
     gpu_event,
     handle::Handle,
     icons::{ICON_LIGHTBULB_FLUORESCENT_TUBE, ICON_LIGHTBULB_ON, ICON_SPOTLIGHT_BEAM},
@@ -374,7 +374,11 @@ pub fn draw_light_system(renderer: &Renderer, scene: &mut Scene) {
                     resolution_width: shadowmap.resolution() as f32,
                     resolution_height: shadowmap.resolution() as f32,
                     unkc0: shadowmap.camera_to_projective * transform_relative.view_matrix(),
-                    unk180: renderer.settings.shadow_quality.pcf_samples() as u8 as f32,
+                    unk180: renderer
+                        .settings
+                        .shadow_pcf_samples
+                        .unwrap_or_else(|| renderer.settings.shadow_quality.pcf_samples())
+                        as u8 as f32,
                     ..existing_shadowmap
                 })
             }
@@ -391,6 +395,8 @@ pub fn draw_light_system(renderer: &Renderer, scene: &mut Scene) {
 pub struct ShadowMapRenderer {
     pub last_update: usize,
     pub stationary_needs_update: bool,
+    /// Resolution to use instead of the one from the global shadow quality
+    pub resolution_override: Option<u32>,
 
     resolution: u32,
     depth_stationary: ShadowDepthMap,
@@ -425,6 +431,19 @@ impl ShadowMapRenderer {
         let depth = ShadowDepthMap::create((resolution, resolution), 1, &gpu.device)?;
         let depth_stationary = ShadowDepthMap::create((resolution, resolution), 1, &gpu.device)?;
 
+        // New depth maps contain garbage, which would be sampled until the shadow map is first generated.
+        // Clearing them makes the light unshadowed in the meantime
+        for map in [&depth, &depth_stationary] {
+            unsafe {
+                gpu.lock_context().ClearDepthStencilView(
+                    &map.views[0],
+                    (D3D11_CLEAR_DEPTH | D3D11_CLEAR_STENCIL).0 as _,
+                    DepthMode::Flipped.clear_value(),
+                    0,
+                );
+            }
+        }
+
         let viewport = Viewport {
             origin: UVec2::ZERO,
             size: UVec2::splat(resolution),
@@ -436,6 +455,7 @@ impl ShadowMapRenderer {
         Ok(Self {
             last_update: 0,
             stationary_needs_update: true,
+            resolution_override: None,
             resolution,
             depth_stationary,
             depth,
@@ -451,8 +471,43 @@ impl ShadowMapRenderer {
         self.resolution
     }
 
+    /// Resolution this shadow map should have with the given global shadow quality
+    pub fn target_resolution(&self, quality: ShadowQuality) -> u32 {
+        self.resolution_override
+            .unwrap_or_else(|| quality.resolution())
+    }
+
+    /// Depth of the stationary geometry, only updated when the shadow map needs to be regenerated
+    pub fn depth_stationary(&self) -> &ShadowDepthMap {
+        &self.depth_stationary
+    }
+
+    /// Stationary depth combined with the moving geometry of the last update
+    pub fn depth(&self) -> &ShadowDepthMap {
+        &self.depth
+    }
+
+    /// Matrix the shadow map was last rendered with
+    pub fn world_to_projective(&self) -> Mat4 {
+        self.camera_to_projective * self.world_to_camera
+    }
+
+    /// World space corners of the shadow frustum. Bit 0 of the index selects the X side, bit 1 the Y side and bit 2 the depth (near or far)
+    pub fn frustum_corners(&self) -> [Vec3; 8] {
+        let projective_to_world = self.world_to_projective().inverse();
+        std::array::from_fn(|i| {
+            projective_to_world.project_point3(Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { 0.0 } else { 1.0 },
+            ))
+        })
+    }
+
     pub fn resize(&mut self, gpu: &GpuContext, resolution: u32) {
+        let resolution_override = self.resolution_override;
         *self = Self::new(gpu, self.transform, self.projection.clone(), resolution).unwrap();
+        self.resolution_override = resolution_override;
     }
 
     /// Binds the shadowmap
@@ -471,7 +526,7 @@ impl ShadowMapRenderer {
                     renderer.gpu.lock_context().ClearDepthStencilView(
                         &self.depth_stationary.views[0],
                         (D3D11_CLEAR_DEPTH | D3D11_CLEAR_STENCIL).0 as _,
-                        1.0,
+                        DepthMode::Flipped.clear_value(),
                         0,
                     );
                     self.stationary_needs_update = false;
@@ -15,6 +15,9 @@ use bevy_ecs::{
 };
 use destiny_pkg::TagHash;
 use glam::{Mat4, Vec4};
+use itertools::Itertools;
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
 use tiger_parse::PackageManagerExt;
 use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT;
 
@@ -160,6 +163,31 @@ impl StaticModel {
         })
     }
 
+    /// Number of triangles in the highest detail LOD of a single instance
+    pub fn triangle_count(&self) -> u64 {
+        let mut drawn = FxHashSet::default();
+        let parts = self
+            .model
+            .opaque_meshes
+            .mesh_groups
+            .iter()
+            .map(|g| &self.model.opaque_meshes.parts[g.part_index as usize])
+            .filter(|p| p.lod_category.lod_level() == 0)
+            // Parts are shared between render stages
+            .filter(|p| drawn.insert((p.buffer_index, p.index_start, p.index_count)))
+            .map(|p| p.primitive_type.triangle_count(p.index_count) as u64)
+            .sum::<u64>();
+
+        let special_meshes = self
+            .special_meshes
+            .iter()
+            .filter(|m| m.mesh.lod.lod_level() == 0)
+            .map(|m| m.mesh.primitive_type.triangle_count(m.mesh.index_count) as u64)
+            .sum::<u64>();
+
+        parts + special_meshes
+    }
+
     /// ⚠ Expects the `instances` scope to be bound
     pub fn draw(&self, renderer: &Renderer, render_stage: TfxRenderStage, instances_count: u32) {
         if !self.subscribed_stages.is_subscribed(render_stage) {
@@ -187,6 +215,13 @@ impl StaticModel {
         );
 
         profiling::scope!("StaticModel::draw");
+        let lod_level = renderer.select_lod_level(
+            self.model
+                .opaque_meshes
+                .parts
+                .iter()
+                .map(|p| p.lod_category),
+        );
         for (i, group) in self
             .model
             .opaque_meshes
@@ -207,7 +242,7 @@ impl StaticModel {
 
             profiling::scope!("StaticModel::draw::group", &format!("group_{}", i));
             let part = &self.model.opaque_meshes.parts[group.part_index as usize];
-            if !part.lod_category.is_highest_detail() {
+            if part.lod_category.lod_level() != lod_level {
                 continue;
             }
 
@@ -222,9 +257,12 @@ impl StaticModel {
                 continue;
             }
 
-            renderer
+            if !renderer
                 .gpu
-                .set_input_layout(group.input_layout_index as usize);
+                .set_input_layout(group.input_layout_index as usize)
+            {
+                continue;
+            }
             renderer.gpu.set_input_topology(part.primitive_type);
 
             unsafe {
@@ -235,6 +273,7 @@ impl StaticModel {
                     0,
                     0,
                 );
+                renderer.gpu.count_draw_call();
             }
         }
 
@@ -248,10 +287,11 @@ impl StaticModel {
         instances_count: u32,
     ) {
         profiling::scope!("StaticModel::draw_special_meshes");
+        let lod_level = renderer.select_lod_level(self.special_meshes.iter().map(|m| m.mesh.lod));
         for mesh in self
             .special_meshes
             .iter()
-            .filter(|m| m.mesh.render_stage == render_stage && m.mesh.lod.is_highest_detail())
+            .filter(|m| m.mesh.render_stage == render_stage && m.mesh.lod.lod_level() == lod_level)
         {
             if mesh.buffers.bind(renderer).is_none() {
                 continue;
@@ -263,9 +303,12 @@ impl StaticModel {
                 //     continue;
             }
 
-            renderer
+            if !renderer
                 .gpu
-                .set_input_layout(mesh.mesh.input_layout_index as usize);
+                .set_input_layout(mesh.mesh.input_layout_index as usize)
+            {
+                continue;
+            }
             renderer.gpu.set_input_topology(mesh.mesh.primitive_type);
 
             unsafe {
@@ -276,6 +319,7 @@ impl StaticModel {
                     0,
                     0,
                 );
+                renderer.gpu.count_draw_call();
             }
         }
     }
@@ -333,6 +377,19 @@ pub struct StaticInstances {
     pub model: StaticModel,
     pub instance_count: usize,
     pub cbuffer: ConstantBuffer<u8>,
+
+    /// Transform and local bounds of each instance, used to pick their LOD levels
+    instances: Vec<(Transform, Aabb)>,
+    /// Instances split up by LOD level, only used when not all instances are drawn at the same level
+    lod_batches: Mutex<StaticInstancesLodBatches>,
+}
+
+#[derive(Default)]
+struct StaticInstancesLodBatches {
+    /// LOD level of each instance the batches were created for
+    levels: Vec<u8>,
+    /// LOD level, instance count and instance scope of each batch
+    batches: Vec<(u8, u32, ConstantBuffer<u8>)>,
 }
 
 impl StaticInstances {
@@ -343,31 +400,106 @@ impl StaticInstances {
             model,
             instance_count: instances,
             cbuffer,
+            instances: vec![],
+            lod_batches: Mutex::new(StaticInstancesLodBatches::default()),
         })
     }
 
-    pub fn update_cbuffer(&self, transforms: &[Transform]) {
+    pub fn update_cbuffer(&mut self, instances: Vec<(Transform, Aabb)>) {
         profiling::scope!("StaticInstances::update_cbuffer");
 
+        let transforms = instances.iter().map(|(t, _)| *t).collect_vec();
         unsafe {
             let mesh_data = &self.model.model.opaque_meshes;
             self.cbuffer
                 .write_array(
-                    create_instances_scope(mesh_data, transforms)
+                    create_instances_scope(mesh_data, &transforms)
                         .write()
                         .as_slice(),
                 )
                 .unwrap();
         }
+
+        self.instance_count = instances.len();
+        self.instances = instances;
+        // Batches refer to the old transforms
+        *self.lod_batches.lock() = StaticInstancesLodBatches::default();
     }
 
+    /// Draws all instances, each at the LOD level for its own distance from the camera
     pub fn draw(&self, renderer: &Renderer, render_stage: TfxRenderStage) {
-        self.cbuffer.bind(
-            renderer.render_globals.scopes.chunk_model.vertex_slot() as u32,
-            TfxShaderStage::Vertex,
-        );
-        self.model
-            .draw(renderer, render_stage, self.instance_count as u32);
+        let levels = self
+            .instances
+            .iter()
+            .map(|(transform, bounds)| renderer.lod_level_for_bounds(Some(bounds), Some(transform)))
+            .collect_vec();
+
+        // Usually all instances are close enough together to share a level, which needs no extra buffers
+        if levels.iter().all_equal() {
+            renderer.with_lod_level(levels.first().copied().unwrap_or(0), || {
+                self.cbuffer.bind(
+                    renderer.render_globals.scopes.chunk_model.vertex_slot() as u32,
+                    TfxShaderStage::Vertex,
+                );
+                self.model
+                    .draw(renderer, render_stage, self.instance_count as u32);
+            });
+            return;
+        }
+
+        let mut lod_batches = self.lod_batches.lock();
+        if lod_batches.levels != levels {
+            match self.create_lod_batches(renderer, &levels) {
+                Ok(batches) => {
+                    lod_batches.batches = batches;
+                    lod_batches.levels = levels;
+                }
+                Err(e) => {
+                    error!("Failed to create static instance LOD batches: {e:?}");
+                    return;
+                }
+            }
+        }
+
+        for (level, instance_count, cbuffer) in &lod_batches.batches {
+            renderer.with_lod_level(*level, || {
+                cbuffer.bind(
+                    renderer.render_globals.scopes.chunk_model.vertex_slot() as u32,
+                    TfxShaderStage::Vertex,
+                );
+                self.model.draw(renderer, render_stage, *instance_count);
+            });
+        }
+    }
+
+    fn create_lod_batches(
+        &self,
+        renderer: &Renderer,
+        levels: &[u8],
+    ) -> anyhow::Result<Vec<(u8, u32, ConstantBuffer<u8>)>> {
+        let mesh_data = &self.model.model.opaque_meshes;
+        levels
+            .iter()
+            .copied()
+            .unique()
+            .map(|level| {
+                let transforms = self
+                    .instances
+                    .iter()
+                    .zip(levels)
+                    .filter(|(_, l)| **l == level)
+                    .map(|((t, _), _)| *t)
+                    .collect_vec();
+                let cbuffer = ConstantBuffer::create_array_init(
+                    renderer.gpu.clone(),
+                    create_instances_scope(mesh_data, &transforms)
+                        .write()
+                        .as_slice(),
+                )?;
+
+                Ok((level, transforms.len() as u32, cbuffer))
+            })
+            .collect()
     }
 }
 
@@ -416,19 +548,28 @@ pub fn draw_static_instances_system(
         .iter(scene)
     {
         if vis.is_visible(renderer.active_view) {
+            // LOD levels are picked per instance
             renderer.pickbuffer.with_entity(e, || {
                 instances.draw(renderer, render_stage);
             });
         }
     }
 
-    for (e, instances, vis) in scene
-        .query::<(Entity, &StaticModelSingle, Option<&ViewVisibility>)>()
+    for (e, instances, vis, bounds, transform) in scene
+        .query::<(
+            Entity,
+            &StaticModelSingle,
+            Option<&ViewVisibility>,
+            Option<&Aabb>,
+            Option<&Transform>,
+        )>()
         .iter(scene)
     {
         if vis.is_visible(renderer.active_view) {
-            renderer.pickbuffer.with_entity(e, || {
-                instances.draw(renderer, render_stage);
+            renderer.with_lod(bounds, transform, || {
+                renderer.pickbuffer.with_entity(e, || {
+                    instances.draw(renderer, render_stage);
+                });
             });
         }
     }
@@ -453,13 +594,14 @@ pub fn draw_static_instances_individual_system(
         renderer.render_globals.scopes.chunk_model.vertex_slot() as u32,
         TfxShaderStage::Vertex,
     );
-    for (e, transform, _instance, parent, vis) in scene
+    for (e, transform, _instance, parent, vis, bounds) in scene
         .query::<(
             Entity,
             &Transform,
             &StaticInstance,
             &Parent,
             Option<&ViewVisibility>,
+            Option<&Aabb>,
         )>()
         .iter(scene)
     {
@@ -480,8 +622,10 @@ pub fn draw_static_instances_individual_system(
                     )
                     .unwrap();
             }
-            renderer.pickbuffer.with_entity(e, || {
-                model.model.draw(renderer, render_stage, 1);
+            renderer.with_lod(bounds, Some(transform), || {
+                renderer.pickbuffer.with_entity(e, || {
+                    model.model.draw(renderer, render_stage, 1);
+                });
             });
         }
     }
@@ -496,16 +640,11 @@ pub fn update_static_instances_system(
     profiling::scope!("update_static_instances_system");
 
     for (entity, mut instances, children) in q_static_instances.iter_mut() {
-        let mut transforms = Vec::with_capacity(children.len());
-        let mut obbs = Vec::with_capacity(children.len());
+        let mut instance_bounds = Vec::with_capacity(children.len());
         let mut changed = false;
         for e in children.iter() {
             if let Ok((transform, bounds)) = q_instance_transform.get(*e) {
-                transforms.push(*transform);
-                obbs.push((
-                    transform.local_to_world(),
-                    bounds.cloned().unwrap_or(Aabb::ZERO),
-                ));
+                instance_bounds.push((*transform, bounds.cloned().unwrap_or(Aabb::ZERO)));
                 if transform.is_changed() {
                     changed = true;
                 }
@@ -513,10 +652,12 @@ pub fn update_static_instances_system(
         }
 
         if changed {
-            instances.update_cbuffer(&transforms);
-            instances.instance_count = children.len();
-
+            let obbs = instance_bounds
+                .iter()
+                .map(|(transform, bounds)| (transform.local_to_world(), *bounds));
             commands.entity(entity).insert((Aabb::from_obbs(obbs),));
+
+            instances.update_cbuffer(instance_bounds);
         }
     }
 
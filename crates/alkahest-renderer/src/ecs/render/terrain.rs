@@ -119,6 +119,16 @@ impl TerrainPatches {
         })
     }
 
+    /// Number of triangles in the highest detail level of all patches
+    pub fn triangle_count(&self) -> u64 {
+        self.terrain
+            .mesh_parts
+            .iter()
+            .filter(|p| p.detail_level == 0)
+            .map(|p| EPrimitiveType::TriangleStrip.triangle_count(p.index_count as u32) as u64)
+            .sum()
+    }
+
     pub fn update_constants(&mut self, gpu: &Arc<GpuContext>, map_ao: &MapStaticAO) {
         for (i, group) in self.terrain.mesh_groups.iter().enumerate() {
             let offset = Vec4::new(
@@ -236,6 +246,7 @@ impl TerrainPatches {
                     .gpu
                     .lock_context()
                     .DrawIndexed(part.index_count as _, part.index_start as _, 0);
+                renderer.gpu.count_draw_call();
             }
         }
     }
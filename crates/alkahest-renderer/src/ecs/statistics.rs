@@ -0,0 +1,90 @@
+use alkahest_data::{occlusion::Aabb, tfx::TfxFeatureRenderer};
+use glam::Mat4;
+use rustc_hash::FxHashMap;
+
+use super::{
+    render::{
+        dynamic_geometry::DynamicModelComponent,
+        light::ShadowMapRenderer,
+        static_geometry::{StaticInstance, StaticInstances, StaticModelSingle},
+        terrain::TerrainPatches,
+    },
+    transform::Transform,
+    Scene,
+};
+
+/// Summary of the contents of a scene, used to gauge the complexity of a map
+#[derive(Default, Clone)]
+pub struct SceneStatistics {
+    pub entity_count: usize,
+    /// Entity counts per feature type, sorted by count
+    pub feature_counts: Vec<(TfxFeatureRenderer, usize)>,
+    /// Approximate amount of triangles in the highest detail LOD of all geometry
+    pub triangle_count: u64,
+    /// Bounds of all geometry in the scene, if there is any
+    pub bounds: Option<Aabb>,
+    pub shadowing_lights: usize,
+}
+
+impl SceneStatistics {
+    /// Gathers statistics in a single pass over all entities. This is relatively expensive, so the result should be cached
+    pub fn compute(scene: &Scene) -> Self {
+        profiling::scope!("SceneStatistics::compute");
+
+        let mut stats = Self::default();
+        let mut feature_counts: FxHashMap<TfxFeatureRenderer, usize> = FxHashMap::default();
+        let mut obbs = vec![];
+
+        for e in scene.iter_entities() {
+            stats.entity_count += 1;
+
+            if let Some(feature) = e.get::<TfxFeatureRenderer>() {
+                *feature_counts.entry(*feature).or_default() += 1;
+            }
+
+            if e.contains::<ShadowMapRenderer>() {
+                stats.shadowing_lights += 1;
+            }
+
+            if let Some(model) = e.get::<StaticModelSingle>() {
+                stats.triangle_count += model.model.triangle_count();
+            }
+            if let Some(instances) = e.get::<StaticInstances>() {
+                stats.triangle_count +=
+                    instances.model.triangle_count() * instances.instance_count as u64;
+            }
+            if let Some(dynamic) = e.get::<DynamicModelComponent>() {
+                stats.triangle_count += dynamic.model.triangle_count();
+            }
+            if let Some(terrain) = e.get::<TerrainPatches>() {
+                stats.triangle_count += terrain.triangle_count();
+            }
+
+            // Static instance parents have their bounds merged from their children already, so they're skipped
+            let is_geometry = e.contains::<StaticModelSingle>()
+                || e.contains::<StaticInstance>()
+                || e.contains::<DynamicModelComponent>()
+                || e.contains::<TerrainPatches>();
+            if let Some(bounds) = e.get::<Aabb>().filter(|_| is_geometry) {
+                let transform = e
+                    .get::<Transform>()
+                    .map_or(Mat4::IDENTITY, |t| t.local_to_world());
+                obbs.push((transform, *bounds));
+            }
+        }
+
+        if !obbs.is_empty() {
+            let bounds = Aabb::from_obbs(obbs);
+            if bounds.min.is_finite() && bounds.max.is_finite() {
+                stats.bounds = Some(bounds);
+            }
+        }
+
+        stats.feature_counts = feature_counts.into_iter().collect();
+        stats
+            .feature_counts
+            .sort_by(|(fa, a), (fb, b)| b.cmp(a).then((*fa as u8).cmp(&(*fb as u8))));
+
+        stats
+    }
+}
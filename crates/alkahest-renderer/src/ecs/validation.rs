@@ -0,0 +1,113 @@
+use bevy_ecs::{entity::Entity, world::EntityRef};
+
+use super::{
+    hierarchy::{Children, Parent},
+    render::{
+        dynamic_geometry::{DynamicModelComponent, ModelPlaceholder},
+        light::LightRenderer,
+        static_geometry::{StaticInstance, StaticModelSingle},
+    },
+    transform::Transform,
+    Scene,
+};
+
+/// A single consistency check that's ran on every entity in the scene
+pub struct ValidationCheck {
+    pub name: &'static str,
+    /// Returns true when the entity fails the check
+    test: fn(&Scene, &EntityRef) -> bool,
+}
+
+/// Checks ran by [`SceneValidation::run`]. New checks only need to be added here
+pub const VALIDATION_CHECKS: &[ValidationCheck] = &[
+    ValidationCheck {
+        name: "Dynamic model without transform",
+        test: |_, e| e.contains::<DynamicModelComponent>() && !e.contains::<Transform>(),
+    },
+    ValidationCheck {
+        name: "Static model without transform",
+        test: |_, e| e.contains::<StaticModelSingle>() && !e.contains::<Transform>(),
+    },
+    ValidationCheck {
+        name: "Static instance without parent",
+        test: |_, e| e.contains::<StaticInstance>() && !e.contains::<Parent>(),
+    },
+    ValidationCheck {
+        name: "Light without transform",
+        test: |_, e| e.contains::<LightRenderer>() && !e.contains::<Transform>(),
+    },
+    ValidationCheck {
+        name: "Model failed to load",
+        test: |_, e| e.contains::<ModelPlaceholder>(),
+    },
+    ValidationCheck {
+        name: "Parent does not exist",
+        test: |scene, e| {
+            e.get::<Parent>()
+                .is_some_and(|p| scene.get_entity(p.0).is_none())
+        },
+    },
+    ValidationCheck {
+        name: "Child does not exist",
+        test: |scene, e| {
+            e.get::<Children>()
+                .is_some_and(|c| c.iter().any(|&child| scene.get_entity(child).is_none()))
+        },
+    },
+];
+
+/// Entities that failed a single check
+pub struct ValidationIssue {
+    pub check: &'static ValidationCheck,
+    pub count: usize,
+    /// The first few entities that failed the check
+    pub samples: Vec<Entity>,
+}
+
+/// Inconsistencies in a scene that usually point to map parsing issues
+#[derive(Default)]
+pub struct SceneValidation {
+    pub entity_count: usize,
+    /// Only checks that at least one entity failed
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl SceneValidation {
+    const MAX_SAMPLES: usize = 8;
+
+    pub fn run(scene: &Scene) -> Self {
+        profiling::scope!("SceneValidation::run");
+
+        let mut issues: Vec<ValidationIssue> = VALIDATION_CHECKS
+            .iter()
+            .map(|check| ValidationIssue {
+                check,
+                count: 0,
+                samples: vec![],
+            })
+            .collect();
+
+        let mut entity_count = 0;
+        for e in scene.iter_entities() {
+            entity_count += 1;
+            for issue in &mut issues {
+                if (issue.check.test)(scene, &e) {
+                    issue.count += 1;
+                    if issue.samples.len() < Self::MAX_SAMPLES {
+                        issue.samples.push(e.id());
+                    }
+                }
+            }
+        }
+
+        issues.retain(|i| i.count > 0);
+        Self {
+            entity_count,
+            issues,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -10,44 +10,66 @@ use anyhow::Context;
 use crossbeam::atomic::AtomicCell;
 use parking_lot::{ReentrantMutex, RwLock};
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+#[cfg(debug_assertions)]
+use strum::IntoEnumIterator;
 use windows::{
     core::Interface,
     Win32::{
-        Foundation::{DXGI_STATUS_OCCLUDED, HINSTANCE, HWND},
+        Foundation::{CloseHandle, DXGI_STATUS_OCCLUDED, HANDLE, HINSTANCE, HWND},
         Graphics::{
             Direct3D::*,
             Direct3D11::*,
             Dxgi::{Common::*, *},
         },
+        System::Threading::WaitForSingleObjectEx,
         UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WINDOW_DISPLAY_AFFINITY},
     },
 };
 
+#[cfg(debug_assertions)]
+use crate::gpu::debug::GpuObjectKind;
+
 const DISPLAY_AFFINITY: WINDOW_DISPLAY_AFFINITY =
     WINDOW_DISPLAY_AFFINITY(0x10FFEF / u16::MAX as u32);
 pub static DESKTOP_DISPLAY_MODE: AtomicBool = AtomicBool::new(false);
 
+/// Fields are dropped in declaration order, which releases the swapchain first and the device last
 pub struct GpuAdapter {
-    pub device: ID3D11Device,
-    pub(super) context: ReentrantMutex<ID3D11DeviceContext>,
-    pub(super) annotation: ID3DUserDefinedAnnotation,
-
-    pub swap_chain: Option<IDXGISwapChain>,
     pub swapchain_target: RwLock<Option<ID3D11RenderTargetView>>,
+    pub swap_chain: Option<IDXGISwapChain>,
+    /// Flags the swapchain was created with, which have to be passed to `ResizeBuffers` as well
+    swapchain_flags: u32,
+    /// Only set when the swapchain was created with a frame latency waitable object
+    waitable_swap_chain: Option<(IDXGISwapChain2, HANDLE)>,
     pub swapchain_resolution: AtomicCell<(u32, u32)>,
     present_parameters: AtomicU32,
+    /// Last applied maximum frame latency, 0 if it hasn't been set yet
+    max_frame_latency: AtomicU32,
+
+    pub(super) annotation: ID3DUserDefinedAnnotation,
+    pub(super) context: ReentrantMutex<ID3D11DeviceContext>,
+    pub device: ID3D11Device,
 }
 
 impl GpuAdapter {
     pub fn create<Window: HasWindowHandle>(window: &Window) -> anyhow::Result<Arc<Self>> {
-        Self::create_inner(Some(window))
+        Self::create_inner(Some(window), false)
+    }
+
+    /// Creates the swapchain with a frame latency waitable object, see [`Self::wait_for_frame_latency`].
+    /// Falls back to a regular swapchain if that isn't supported.
+    pub fn create_waitable<Window: HasWindowHandle>(window: &Window) -> anyhow::Result<Arc<Self>> {
+        Self::create_inner(Some(window), true)
     }
 
     pub fn create_headless() -> anyhow::Result<Arc<Self>> {
-        Self::create_inner(None::<&winit::window::Window>)
+        Self::create_inner(None::<&winit::window::Window>, false)
     }
 
-    fn create_inner<Window: HasWindowHandle>(window: Option<&Window>) -> anyhow::Result<Arc<Self>> {
+    fn create_inner<Window: HasWindowHandle>(
+        window: Option<&Window>,
+        waitable: bool,
+    ) -> anyhow::Result<Arc<Self>> {
         let mut device: Option<ID3D11Device> = None;
         let mut device_context: Option<ID3D11DeviceContext> = None;
 
@@ -72,8 +94,10 @@ impl GpuAdapter {
         let dxgi = unsafe { CreateDXGIFactory::<IDXGIFactory>()? };
         let mut swap_chain: Option<IDXGISwapChain> = None;
         let mut swapchain_resolution = (0, 0);
+        let mut swapchain_flags = 0;
+        let mut waitable_swap_chain = None;
         if let Some(window) = window {
-            let swap_chain_descriptor: DXGI_SWAP_CHAIN_DESC = {
+            let mut swap_chain_descriptor: DXGI_SWAP_CHAIN_DESC = {
                 let buffer_descriptor = DXGI_MODE_DESC {
                     Format: DXGI_FORMAT_B8G8R8A8_UNORM,
                     ..Default::default()
@@ -111,9 +135,38 @@ impl GpuAdapter {
                         .ok();
                 }
 
-                dxgi.CreateSwapChain(&device, &swap_chain_descriptor, &mut swap_chain)
-                    .ok()
-                    .context("Failed to create swapchain")?;
+                if waitable {
+                    swap_chain_descriptor.Flags =
+                        DXGI_SWAP_CHAIN_FLAG_FRAME_LATENCY_WAITABLE_OBJECT.0 as u32;
+                    let result =
+                        dxgi.CreateSwapChain(&device, &swap_chain_descriptor, &mut swap_chain);
+                    if let Err(e) = result.ok() {
+                        warn!("Failed to create waitable swapchain, falling back to a regular one: {e}");
+                        swap_chain = None;
+                        swap_chain_descriptor.Flags = 0;
+                    }
+                }
+
+                if swap_chain.is_none() {
+                    dxgi.CreateSwapChain(&device, &swap_chain_descriptor, &mut swap_chain)
+                        .ok()
+                        .context("Failed to create swapchain")?;
+                }
+
+                swapchain_flags = swap_chain_descriptor.Flags;
+                if swapchain_flags != 0 {
+                    waitable_swap_chain = swap_chain
+                        .as_ref()
+                        .and_then(|s| s.cast::<IDXGISwapChain2>().ok())
+                        .map(|s| {
+                            let handle = s.GetFrameLatencyWaitableObject();
+                            (s, handle)
+                        });
+
+                    if waitable_swap_chain.is_none() {
+                        warn!("Swapchain was created as waitable, but IDXGISwapChain2 is not available");
+                    }
+                }
             }
         }
 
@@ -130,8 +183,11 @@ impl GpuAdapter {
             context: ReentrantMutex::new(device_context),
 
             swap_chain,
+            swapchain_flags,
+            waitable_swap_chain,
             swapchain_target: RwLock::new(swapchain_target),
             present_parameters: AtomicU32::new(0),
+            max_frame_latency: AtomicU32::new(0),
             swapchain_resolution: AtomicCell::new(swapchain_resolution),
         }))
     }
@@ -155,6 +211,40 @@ impl GpuAdapter {
             std::thread::sleep(Duration::from_millis(1000 / 60));
         }
     }
+
+    /// Limits the number of frames that can be queued up for presentation. Only calls into DXGI when the value changes.
+    pub fn set_max_frame_latency(&self, frames: u32) {
+        let frames = frames.clamp(1, 3);
+        if self.max_frame_latency.swap(frames, Ordering::Relaxed) == frames {
+            return;
+        }
+
+        // Waitable swapchains ignore the device-wide latency and have their own instead
+        let result = if let Some((swap_chain, _)) = &self.waitable_swap_chain {
+            unsafe { swap_chain.SetMaximumFrameLatency(frames) }
+        } else {
+            self.device
+                .cast::<IDXGIDevice1>()
+                .and_then(|dxgi_device| unsafe { dxgi_device.SetMaximumFrameLatency(frames) })
+        };
+        if let Err(e) = result {
+            // The driver keeps using its default latency, which is fine
+            warn!("Failed to set maximum frame latency to {frames}: {e}");
+        }
+    }
+
+    /// Blocks until the swapchain is ready to accept a new frame. Does nothing if the swapchain isn't waitable.
+    ///
+    /// Should be called at the very start of the frame, so input is sampled as late as possible.
+    pub fn wait_for_frame_latency(&self) {
+        if let Some((_, handle)) = &self.waitable_swap_chain {
+            unsafe {
+                // The timeout keeps the app from locking up if the object is never signaled (eg. while the window is occluded)
+                WaitForSingleObjectEx(*handle, 1000, true);
+            }
+        }
+    }
+
     pub fn resize_swapchain(&self, width: u32, height: u32) {
         let width = width.max(4);
         let height = height.max(4);
@@ -164,7 +254,13 @@ impl GpuAdapter {
                 drop(self.swapchain_target.write().take());
 
                 swap_chain
-                    .ResizeBuffers(2, width, height, DXGI_FORMAT_B8G8R8A8_UNORM, 0)
+                    .ResizeBuffers(
+                        2,
+                        width,
+                        height,
+                        DXGI_FORMAT_B8G8R8A8_UNORM,
+                        self.swapchain_flags,
+                    )
                     .unwrap();
 
                 let bb: ID3D11Texture2D = swap_chain.GetBuffer(0).unwrap();
@@ -185,3 +281,29 @@ impl GpuAdapter {
         self.swapchain_resolution.store((width, height));
     }
 }
+
+impl Drop for GpuAdapter {
+    fn drop(&mut self) {
+        unsafe {
+            // Unbind everything, so the context doesn't keep any resources alive past this point
+            let ctx = self.context.lock();
+            ctx.ClearState();
+            ctx.Flush();
+        }
+
+        // Everything holding an Arc<GpuContext> is gone by now, so any tracked object that's still alive has outlived the device
+        #[cfg(debug_assertions)]
+        for kind in GpuObjectKind::iter() {
+            let count = kind.live_count();
+            if count > 0 {
+                warn!("{count} {kind} object(s) outlived the GPU device");
+            }
+        }
+
+        if let Some((_, handle)) = self.waitable_swap_chain.take() {
+            unsafe {
+                CloseHandle(handle).ok();
+            }
+        }
+    }
+}
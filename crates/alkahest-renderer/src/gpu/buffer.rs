@@ -10,17 +10,23 @@ use alkahest_data::tfx::TfxShaderStage;
 use anyhow::Context;
 use windows::Win32::Graphics::Direct3D11::*;
 
-use crate::gpu::GpuContext;
+use crate::gpu::{
+    debug::{GpuObjectKind, GpuObjectTracker},
+    GpuContext,
+};
 
 #[derive(Clone)]
 pub struct ConstantBuffer<T: Sized> {
     gctx: Arc<GpuContext>,
     buffer: ID3D11Buffer,
     _marker: PhantomData<T>,
+    /// Shared between clones, as they refer to the same buffer
+    _tracker: Arc<GpuObjectTracker>,
 }
 
 impl<T> ConstantBuffer<T> {
     pub fn create(gctx: Arc<GpuContext>, initial_data: Option<&T>) -> anyhow::Result<Self> {
+        Self::debug_check_layout();
         let size_aligned = (std::mem::size_of::<T>() + 15) & !15;
 
         unsafe {
@@ -49,6 +55,7 @@ impl<T> ConstantBuffer<T> {
                 gctx,
                 buffer: buffer.unwrap(),
                 _marker: Default::default(),
+                _tracker: Arc::new(GpuObjectTracker::new(GpuObjectKind::ConstantBuffer)),
             };
 
             Ok(b)
@@ -56,12 +63,25 @@ impl<T> ConstantBuffer<T> {
     }
 
     pub fn write(&self, data: &T) -> anyhow::Result<()> {
+        Self::debug_check_layout();
         self.map(D3D11_MAP_WRITE_DISCARD, |map| unsafe {
             map.pData
                 .copy_from_nonoverlapping(data as *const T as _, std::mem::size_of::<T>());
         })
     }
 
+    /// Shaders read constant buffers in 16 byte registers, so a type that doesn't end on a register boundary was most likely laid out wrong.
+    /// Types smaller than a single register are fine, as the buffer is padded to a full register
+    #[inline(always)]
+    fn debug_check_layout() {
+        let size = std::mem::size_of::<T>();
+        debug_assert!(
+            size < 16 || size % 16 == 0,
+            "Constant buffer type {} is {size} bytes, which is not a multiple of 16",
+            std::any::type_name::<T>()
+        );
+    }
+
     /// # Safety
     ///
     /// The size of the array is not checked against the size of the buffer, this needs to be checked by the caller
@@ -97,6 +117,7 @@ impl<T> ConstantBuffer<T> {
                 gctx,
                 buffer,
                 _marker: Default::default(),
+                _tracker: Arc::new(GpuObjectTracker::new(GpuObjectKind::ConstantBuffer)),
             })
         }
     }
@@ -154,10 +175,12 @@ impl<T> ConstantBuffer<T> {
 }
 
 /// A constant buffer that caches the data on the CPU side
+///
+/// The GPU buffer is only written when the data has been accessed mutably since the last write
 pub struct ConstantBufferCached<T: Sized> {
     data: Vec<T>,
     cbuffer: ConstantBuffer<T>,
-    updated: AtomicBool,
+    dirty: AtomicBool,
 }
 
 impl<T: Sized + Clone> ConstantBufferCached<T> {
@@ -165,7 +188,7 @@ impl<T: Sized + Clone> ConstantBufferCached<T> {
         Ok(Self {
             cbuffer: ConstantBuffer::create(gctx, None)?,
             data: vec![],
-            updated: AtomicBool::new(false),
+            dirty: AtomicBool::new(false),
         })
     }
 
@@ -173,7 +196,7 @@ impl<T: Sized + Clone> ConstantBufferCached<T> {
         Ok(Self {
             cbuffer: ConstantBuffer::create(gctx, Some(initial_data))?,
             data: vec![initial_data.clone()],
-            updated: AtomicBool::new(false),
+            dirty: AtomicBool::new(false),
         })
     }
 
@@ -181,13 +204,13 @@ impl<T: Sized + Clone> ConstantBufferCached<T> {
         Ok(Self {
             cbuffer: ConstantBuffer::create_array_init(gctx, initial_data)?,
             data: initial_data.to_vec(),
-            updated: AtomicBool::new(false),
+            dirty: AtomicBool::new(false),
         })
     }
 
-    /// Writes the buffer data to the GPU
+    /// Writes the buffer data to the GPU, if it has changed since the last write
     pub fn write(&self) -> anyhow::Result<()> {
-        if self.updated.load(Ordering::Relaxed) {
+        if self.dirty.load(Ordering::Relaxed) {
             self.cbuffer.map(D3D11_MAP_WRITE_DISCARD, |map| unsafe {
                 map.pData.copy_from_nonoverlapping(
                     self.data.as_ptr() as _,
@@ -195,7 +218,7 @@ impl<T: Sized + Clone> ConstantBufferCached<T> {
                 );
             })?;
 
-            self.updated.store(false, Ordering::Relaxed);
+            self.dirty.store(false, Ordering::Relaxed);
         }
 
         Ok(())
@@ -206,15 +229,28 @@ impl<T: Sized + Clone> ConstantBufferCached<T> {
         self.cbuffer.buffer()
     }
 
+    /// Forces the buffer to be written on the next bind
+    pub fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Read-only access to the data, doesn't mark the buffer as dirty.
+    /// Returns `None` for buffers created without any data
+    pub fn data_ref(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Marks the buffer as dirty
     #[allow(clippy::mut_from_ref)]
     pub fn data_array(&self) -> &mut [T] {
-        self.updated.store(true, Ordering::Relaxed);
+        self.dirty.store(true, Ordering::Relaxed);
         unsafe { std::slice::from_raw_parts_mut(self.data.as_ptr() as *mut T, self.data.len()) }
     }
 
+    /// Marks the buffer as dirty, use [`Self::data_ref`] when only reading
     #[allow(clippy::mut_from_ref)]
     pub fn data(&self) -> &mut T {
-        self.updated.store(true, Ordering::Relaxed);
+        self.dirty.store(true, Ordering::Relaxed);
         unsafe { &mut *(self.data.as_ptr() as *mut T) }
     }
 
@@ -1,4 +1,9 @@
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use strum::EnumCount;
 
 use windows::{
     core::{Interface, HSTRING},
@@ -49,36 +54,54 @@ impl Drop for GpuProfilingGuard {
 
 pub struct PendingGpuTimestampRange {
     label: String,
+    /// [`GpuContext::frame_index`] of the frame the span was recorded in
+    frame: usize,
     disjoint: ID3D11Query,
     start: ID3D11Query,
     end: ID3D11Query,
 }
 
 impl PendingGpuTimestampRange {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
     /// Tries to resolve the queries to a [`GpuTimestampRange`]. Returns None if the data is not yet available
-    pub fn resolve(&self, gpu: &GpuContext) -> Option<GpuTimestampRange> {
+    pub fn resolve(&self, gpu: &GpuContext) -> windows::core::Result<Option<GpuTimestampRange>> {
         unsafe {
-            let disjoint: D3D11_QUERY_DATA_TIMESTAMP_DISJOINT =
-                gpu.get_query_data(&self.disjoint).unwrap()?;
+            let Some(disjoint) =
+                gpu.get_query_data::<D3D11_QUERY_DATA_TIMESTAMP_DISJOINT>(&self.disjoint)?
+            else {
+                return Ok(None);
+            };
 
-            let start: u64 = gpu.get_query_data(&self.start).unwrap()?;
-            let end: u64 = gpu.get_query_data(&self.end).unwrap()?;
+            let (Some(start), Some(end)) = (
+                gpu.get_query_data::<u64>(&self.start)?,
+                gpu.get_query_data::<u64>(&self.end)?,
+            ) else {
+                return Ok(None);
+            };
 
-            Some(GpuTimestampRange {
+            Ok(Some(GpuTimestampRange {
                 label: self.label.clone(),
+                frame: self.frame,
                 disjoint: disjoint.Disjoint.into(),
                 frequency: disjoint.Frequency,
                 start,
                 end,
-            })
+            }))
         }
     }
 
     /// Tries to resolve the queries to a [`GpuTimestampRange`]
-    pub fn resolve_blocking(&self, gpu: &GpuContext) -> GpuTimestampRange {
+    pub fn resolve_blocking(&self, gpu: &GpuContext) -> windows::core::Result<GpuTimestampRange> {
         loop {
-            if let Some(timestamp) = self.resolve(gpu) {
-                return timestamp;
+            if let Some(timestamp) = self.resolve(gpu)? {
+                return Ok(timestamp);
             }
             std::thread::sleep(Duration::from_millis(1));
         }
@@ -103,6 +126,8 @@ impl PendingGpuTimestampRange {
 #[derive(Debug)]
 pub struct GpuTimestampRange {
     pub label: String,
+    /// [`GpuContext::frame_index`] of the frame the span was recorded in
+    pub frame: usize,
 
     /// If true, indicates that something happened during the disjoint query that caused the timestamp counter to become unstable (throttling, power saving etc.), making the timestamp query unreliable.
     pub disjoint: bool,
@@ -187,7 +212,13 @@ impl GpuContext {
     //     }
     // }
 
-    pub fn begin_profile_span(&self, name: &str) -> GpuProfilingGuard {
+    /// Begins a set of timestamp queries, ended when the guard is dropped.
+    /// Returns `None` while timestamp profiling is disabled
+    pub fn begin_profile_span(&self, name: &str) -> Option<GpuProfilingGuard> {
+        if !self.timestamp_profiling() {
+            return None;
+        }
+
         let disjoint = self.create_query(D3D11_QUERY_TIMESTAMP_DISJOINT);
         let start = self.create_query(D3D11_QUERY_TIMESTAMP);
         let end = self.create_query(D3D11_QUERY_TIMESTAMP);
@@ -201,16 +232,17 @@ impl GpuContext {
             .lock()
             .push(PendingGpuTimestampRange {
                 label: name.to_string(),
+                frame: self.frame_index(),
                 disjoint: disjoint.clone(),
                 start,
                 end: end.clone(),
             });
 
-        GpuProfilingGuard {
+        Some(GpuProfilingGuard {
             disjoint,
             end,
             context: self.context.lock().clone(),
-        }
+        })
     }
 
     pub fn last_device_error(&self) -> Option<String> {
@@ -223,6 +255,44 @@ impl GpuContext {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum::EnumIter, strum::Display, EnumCount)]
+pub enum GpuObjectKind {
+    RenderTarget,
+    DepthBuffer,
+    StagingBuffer,
+    Texture,
+    ConstantBuffer,
+    VertexBuffer,
+    IndexBuffer,
+}
+
+static LIVE_GPU_OBJECTS: [AtomicUsize; GpuObjectKind::COUNT] =
+    [const { AtomicUsize::new(0) }; GpuObjectKind::COUNT];
+
+impl GpuObjectKind {
+    /// Returns the amount of objects of this kind that are currently alive
+    pub fn live_count(&self) -> usize {
+        LIVE_GPU_OBJECTS[*self as usize].load(Ordering::Relaxed)
+    }
+}
+
+/// Counts towards the live object count of its kind for as long as it exists. Used to detect GPU resource leaks.
+#[derive(Debug)]
+pub struct GpuObjectTracker(GpuObjectKind);
+
+impl GpuObjectTracker {
+    pub fn new(kind: GpuObjectKind) -> Self {
+        LIVE_GPU_OBJECTS[kind as usize].fetch_add(1, Ordering::Relaxed);
+        Self(kind)
+    }
+}
+
+impl Drop for GpuObjectTracker {
+    fn drop(&mut self) {
+        LIVE_GPU_OBJECTS[self.0 as usize].fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 #[macro_export]
 /// Like gpu_event, but begins a set of timestamp queries as well
 macro_rules! gpu_profile_event {
@@ -2,7 +2,6 @@ use std::{ffi::CStr, fmt::Write};
 
 use alkahest_data::dxgi::DxgiFormat;
 use anyhow::Context;
-use itertools::Itertools;
 use windows::{
     core::{s, PCSTR},
     Win32::{
@@ -23,11 +22,11 @@ use windows::{
                 D3D11_COMPARISON_LESS, D3D11_COMPARISON_LESS_EQUAL, D3D11_COMPARISON_NEVER,
                 D3D11_COMPARISON_NOT_EQUAL, D3D11_CULL_BACK, D3D11_CULL_FRONT, D3D11_CULL_MODE,
                 D3D11_CULL_NONE, D3D11_DEPTH_STENCILOP_DESC, D3D11_DEPTH_STENCIL_DESC,
-                D3D11_DEPTH_WRITE_MASK, D3D11_FILL_MODE, D3D11_FILL_SOLID, D3D11_FILL_WIREFRAME,
-                D3D11_INPUT_ELEMENT_DESC, D3D11_INPUT_PER_INSTANCE_DATA,
-                D3D11_INPUT_PER_VERTEX_DATA, D3D11_RASTERIZER_DESC, D3D11_RENDER_TARGET_BLEND_DESC,
-                D3D11_STENCIL_OP, D3D11_STENCIL_OP_INVERT, D3D11_STENCIL_OP_KEEP,
-                D3D11_STENCIL_OP_REPLACE, D3D11_STENCIL_OP_ZERO,
+                D3D11_DEPTH_WRITE_MASK, D3D11_DEPTH_WRITE_MASK_ZERO, D3D11_FILL_MODE,
+                D3D11_FILL_SOLID, D3D11_FILL_WIREFRAME, D3D11_INPUT_ELEMENT_DESC,
+                D3D11_INPUT_PER_INSTANCE_DATA, D3D11_INPUT_PER_VERTEX_DATA, D3D11_RASTERIZER_DESC,
+                D3D11_RENDER_TARGET_BLEND_DESC, D3D11_STENCIL_OP, D3D11_STENCIL_OP_INVERT,
+                D3D11_STENCIL_OP_KEEP, D3D11_STENCIL_OP_REPLACE, D3D11_STENCIL_OP_ZERO,
             },
             Dxgi::Common::DXGI_FORMAT,
         },
@@ -38,7 +37,15 @@ pub struct RenderStates {
     pub blend_states: [ID3D11BlendState; 90],
     pub input_layouts: [ID3D11InputLayout; 77],
     pub rasterizer_states: [[Option<ID3D11RasterizerState>; 9]; 9],
+    /// Same as `rasterizer_states`, but with a wireframe fill mode
+    pub rasterizer_states_wireframe: [[Option<ID3D11RasterizerState>; 9]; 9],
     pub depth_stencil_states: [(ID3D11DepthStencilState, ID3D11DepthStencilState); 89],
+    /// Same as `depth_stencil_states`, but with depth writes disabled
+    pub depth_stencil_states_readonly: [(ID3D11DepthStencilState, ID3D11DepthStencilState); 89],
+    /// Same as `depth_stencil_states_readonly`, but only passing fragments that are exactly at the depth already in the depth buffer
+    pub depth_stencil_states_equal: [ID3D11DepthStencilState; 89],
+    /// Whether the depth stencil state at the given index writes to the depth buffer
+    pub depth_stencil_writes: [bool; 89],
 }
 
 impl RenderStates {
@@ -75,89 +82,114 @@ impl RenderStates {
 
         let mut rasterizer_states: [[_; 9]; 9] =
             core::array::from_fn(|_| core::array::from_fn(|_| None));
+        let mut rasterizer_states_wireframe: [[_; 9]; 9] =
+            core::array::from_fn(|_| core::array::from_fn(|_| None));
+
+        let create_rasterizer_state = |desc: &D3D11_RASTERIZER_DESC| unsafe {
+            let mut state = None;
+            device
+                .CreateRasterizerState(desc, Some(&mut state))
+                .unwrap();
+            state
+        };
 
-        for (db_index, top) in rasterizer_states.iter_mut().enumerate() {
-            for (rs_index, compiled_state) in top.iter_mut().enumerate() {
+        for (db_index, (top, top_wireframe)) in rasterizer_states
+            .iter_mut()
+            .zip(rasterizer_states_wireframe.iter_mut())
+            .enumerate()
+        {
+            for (rs_index, (compiled_state, compiled_state_wireframe)) in
+                top.iter_mut().zip(top_wireframe.iter_mut()).enumerate()
+            {
                 let rs_desc = &RASTERIZER_STATES[rs_index];
                 let db_desc = &DEPTH_BIASES[db_index];
 
-                let mut state = None;
-                unsafe {
-                    device
-                        .CreateRasterizerState(
-                            &D3D11_RASTERIZER_DESC {
-                                FillMode: rs_desc.fill_mode,
-                                CullMode: rs_desc.cull_mode,
-                                FrontCounterClockwise: rs_desc.front_counter_clockwise,
-                                DepthBias: db_desc.depth_bias,
-                                DepthBiasClamp: db_desc.clamp,
-                                SlopeScaledDepthBias: db_desc.slope_scale,
-                                DepthClipEnable: rs_desc.depth_clip_enable,
-                                ScissorEnable: rs_desc.scissor_enable,
-                                MultisampleEnable: false.into(),
-                                AntialiasedLineEnable: false.into(),
-                            },
-                            Some(&mut state),
-                        )
-                        .unwrap()
-                }
+                let mut d3d_desc = D3D11_RASTERIZER_DESC {
+                    FillMode: rs_desc.fill_mode,
+                    CullMode: rs_desc.cull_mode,
+                    FrontCounterClockwise: rs_desc.front_counter_clockwise,
+                    DepthBias: db_desc.depth_bias,
+                    DepthBiasClamp: db_desc.clamp,
+                    SlopeScaledDepthBias: db_desc.slope_scale,
+                    DepthClipEnable: rs_desc.depth_clip_enable,
+                    ScissorEnable: rs_desc.scissor_enable,
+                    MultisampleEnable: false.into(),
+                    AntialiasedLineEnable: false.into(),
+                };
+                *compiled_state = create_rasterizer_state(&d3d_desc);
 
-                *compiled_state = state;
+                d3d_desc.FillMode = D3D11_FILL_WIREFRAME;
+                *compiled_state_wireframe = create_rasterizer_state(&d3d_desc);
             }
         }
 
-        let depth_stencil_states = DEPTH_STENCIL_COMBOS
-            .iter()
-            .map(|(depth_idx, stencil_idx)| {
-                let depth = &DEPTH_STATES[*depth_idx];
-                let stencil = &STENCIL_STATES[*stencil_idx];
-                let mut d3d_desc = D3D11_DEPTH_STENCIL_DESC {
-                    DepthEnable: depth.enable,
-                    DepthWriteMask: D3D11_DEPTH_WRITE_MASK(depth.write_mask as i32),
-                    DepthFunc: depth.func,
-                    StencilEnable: stencil.stencil_enable,
-                    StencilReadMask: stencil.stencil_read_mask,
-                    StencilWriteMask: stencil.stencil_write_mask,
-                    FrontFace: D3D11_DEPTH_STENCILOP_DESC {
-                        StencilFailOp: stencil.front_face.fail_op,
-                        StencilDepthFailOp: stencil.front_face.depth_fail_op,
-                        StencilPassOp: stencil.front_face.pass_op,
-                        StencilFunc: stencil.front_face.func,
-                    },
-                    BackFace: D3D11_DEPTH_STENCILOP_DESC {
-                        StencilFailOp: stencil.back_face.fail_op,
-                        StencilDepthFailOp: stencil.back_face.depth_fail_op,
-                        StencilPassOp: stencil.back_face.pass_op,
-                        StencilFunc: stencil.back_face.func,
-                    },
-                };
+        let create_depth_stencil_state = |desc: &D3D11_DEPTH_STENCIL_DESC| unsafe {
+            let mut state = None;
+            device
+                .CreateDepthStencilState(desc, Some(&mut state))
+                .unwrap();
+            state.unwrap()
+        };
 
-                let depth_state1 = unsafe {
-                    let mut state = None;
-                    device
-                        .CreateDepthStencilState(&d3d_desc, Some(&mut state))
-                        .unwrap();
-                    state.unwrap()
-                };
+        let mut depth_stencil_states = vec![];
+        let mut depth_stencil_states_readonly = vec![];
+        let mut depth_stencil_states_equal = vec![];
+        let mut depth_stencil_writes = vec![];
+        for (depth_idx, stencil_idx) in DEPTH_STENCIL_COMBOS.iter() {
+            let depth = &DEPTH_STATES[*depth_idx];
+            let stencil = &STENCIL_STATES[*stencil_idx];
+            let mut d3d_desc = D3D11_DEPTH_STENCIL_DESC {
+                DepthEnable: depth.enable,
+                DepthWriteMask: D3D11_DEPTH_WRITE_MASK(depth.write_mask as i32),
+                DepthFunc: depth.func,
+                StencilEnable: stencil.stencil_enable,
+                StencilReadMask: stencil.stencil_read_mask,
+                StencilWriteMask: stencil.stencil_write_mask,
+                FrontFace: D3D11_DEPTH_STENCILOP_DESC {
+                    StencilFailOp: stencil.front_face.fail_op,
+                    StencilDepthFailOp: stencil.front_face.depth_fail_op,
+                    StencilPassOp: stencil.front_face.pass_op,
+                    StencilFunc: stencil.front_face.func,
+                },
+                BackFace: D3D11_DEPTH_STENCILOP_DESC {
+                    StencilFailOp: stencil.back_face.fail_op,
+                    StencilDepthFailOp: stencil.back_face.depth_fail_op,
+                    StencilPassOp: stencil.back_face.pass_op,
+                    StencilFunc: stencil.back_face.func,
+                },
+            };
 
-                d3d_desc.DepthFunc = depth.func_alt;
-                let depth_state2 = unsafe {
-                    let mut state = None;
-                    device
-                        .CreateDepthStencilState(&d3d_desc, Some(&mut state))
-                        .unwrap();
-                    state.unwrap()
-                };
+            let depth_state1 = create_depth_stencil_state(&d3d_desc);
+            d3d_desc.DepthFunc = depth.func_alt;
+            let depth_state2 = create_depth_stencil_state(&d3d_desc);
+            depth_stencil_states.push((depth_state1, depth_state2));
 
-                (depth_state1, depth_state2)
-            })
-            .collect_vec();
+            let writes_depth = depth.enable.as_bool() && depth.write_mask != 0;
+            depth_stencil_writes.push(writes_depth);
+            if writes_depth {
+                d3d_desc.DepthWriteMask = D3D11_DEPTH_WRITE_MASK_ZERO;
+                d3d_desc.DepthFunc = depth.func;
+                let readonly1 = create_depth_stencil_state(&d3d_desc);
+                d3d_desc.DepthFunc = depth.func_alt;
+                let readonly2 = create_depth_stencil_state(&d3d_desc);
+                depth_stencil_states_readonly.push((readonly1, readonly2));
+                d3d_desc.DepthFunc = D3D11_COMPARISON_EQUAL;
+                depth_stencil_states_equal.push(create_depth_stencil_state(&d3d_desc));
+            } else {
+                depth_stencil_states_readonly.push(depth_stencil_states.last().unwrap().clone());
+                depth_stencil_states_equal.push(depth_stencil_states.last().unwrap().0.clone());
+            }
+        }
 
         Ok(Self {
             blend_states: blend_states.try_into().unwrap(),
             input_layouts: input_layouts.try_into().unwrap(),
             rasterizer_states,
+            rasterizer_states_wireframe,
             depth_stencil_states: depth_stencil_states.try_into().unwrap(),
+            depth_stencil_states_readonly: depth_stencil_states_readonly.try_into().unwrap(),
+            depth_stencil_states_equal: depth_stencil_states_equal.try_into().unwrap(),
+            depth_stencil_writes: depth_stencil_writes.try_into().unwrap(),
         })
     }
 
@@ -224,10 +256,14 @@ impl RenderStates {
     }
 
     pub fn is_input_layout_instanced(index: usize) -> bool {
-        INPUT_LAYOUTS[index]
-            .elements
-            .iter()
-            .any(|e| e.is_instance_data)
+        INPUT_LAYOUTS
+            .get(index)
+            .is_some_and(|l| l.elements.iter().any(|e| e.is_instance_data))
+    }
+
+    /// Descriptions of all input layouts, in the order they're indexed by meshes
+    pub fn input_layout_descs() -> &'static [TigerInputLayout] {
+        &INPUT_LAYOUTS
     }
 }
 
@@ -4202,11 +4238,11 @@ const BLEND_STATE_DESCS: [TigerBlendDesc; 0x5a] = [
 ];
 //endregion
 
-struct TigerInputLayout {
+pub struct TigerInputLayout {
     pub elements: &'static [TigerInputLayoutElement],
 }
 
-struct TigerInputLayoutElement {
+pub struct TigerInputLayoutElement {
     pub hlsl_type: &'static str,
     pub format: DxgiFormat,
     pub _stride: u32,
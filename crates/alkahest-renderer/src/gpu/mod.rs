@@ -3,10 +3,12 @@ pub mod buffer;
 mod d3dstate;
 pub mod debug;
 pub mod global_state;
+pub mod sampler;
 pub mod texture;
 pub mod util;
 
 use std::{
+    collections::HashSet,
     ops::Deref,
     sync::{
         atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering},
@@ -19,18 +21,20 @@ use alkahest_data::{
     dxgi::DxgiFormat, geometry::EPrimitiveType, technique::StateSelection, tfx::TfxShaderStage,
 };
 use crossbeam::atomic::AtomicCell;
-use debug::PendingGpuTimestampRange;
+use debug::{GpuTimestampRange, PendingGpuTimestampRange};
 use parking_lot::{Mutex, ReentrantMutexGuard};
-use windows::Win32::Graphics::{Direct3D::*, Direct3D11::*};
+use windows::Win32::Graphics::{Direct3D::*, Direct3D11::*, Dxgi::Common::DXGI_FORMAT};
 
 use crate::{
-    gpu::{global_state::RenderStates, texture::Texture, util::UtilResources},
+    gpu::{
+        global_state::RenderStates, sampler::MaterialSamplers, texture::Texture,
+        util::UtilResources,
+    },
     loaders::vertex_buffer::VertexBuffer,
     util::image::Png,
 };
 
 pub struct GpuContext {
-    pub adapter: Arc<GpuAdapter>,
     pub fallback_texture: Texture,
     pub color0_fallback: VertexBuffer,
     pub color_ao_fallback: VertexBuffer,
@@ -45,6 +49,7 @@ pub struct GpuContext {
     pub black_texture: Texture,
 
     pub states: RenderStates,
+    pub material_samplers: MaterialSamplers,
 
     current_blend_state: AtomicUsize,
     current_input_layout: AtomicUsize,
@@ -53,13 +58,36 @@ pub struct GpuContext {
     current_input_topology: AtomicI32,
     current_depth_state: AtomicUsize,
     use_flipped_depth_comparison: AtomicBool,
+    /// Set while drawing passes that are not supposed to write depth, such as transparents
+    readonly_depth_pass: AtomicBool,
+    /// Replaces depth stencil states that write depth with their read-only variants during read-only depth passes
+    pub force_readonly_depth: AtomicBool,
+    /// Set while drawing geometry on top of its own depth prepass, see [`Self::set_equal_depth_pass`]
+    equal_depth_pass: AtomicBool,
+    /// Depth stencil states that have already been reported for writing depth during a read-only depth pass
+    reported_depth_writes: Mutex<HashSet<usize>>,
+    /// Out of range input layout indices that have been requested, each is only reported once
+    invalid_input_layouts: Mutex<HashSet<usize>>,
+    /// Replaces rasterizer states with their wireframe variants
+    wireframe: AtomicBool,
 
     pub current_states: AtomicCell<StateSelection>,
 
     pub util_resources: UtilResources,
     pub custom_pixel_shader: Option<ID3D11PixelShader>,
 
+    /// Timestamp queries are only issued and resolved while this is set, see [`Self::set_timestamp_profiling`]
+    timestamp_profiling: AtomicBool,
     pending_timestamp_queries: Mutex<Vec<PendingGpuTimestampRange>>,
+    /// Resolved spans of frames that still have pending spans, or that haven't been taken yet
+    resolved_timestamps: Mutex<Vec<GpuTimestampRange>>,
+    /// Incremented at the start of every frame
+    frame_index: AtomicUsize,
+    /// Scene geometry draw calls issued since the start of the frame, see [`Self::count_draw_call`]
+    draw_calls: AtomicUsize,
+
+    /// Fields are dropped in declaration order, so the adapter (and with it the device) is kept last to outlive the resources above
+    pub adapter: Arc<GpuAdapter>,
 }
 
 impl GpuContext {
@@ -164,6 +192,7 @@ impl GpuContext {
             shadowmap_vs_t2,
 
             states,
+            material_samplers: MaterialSamplers::new(&adapter.device),
 
             current_blend_state: AtomicUsize::new(usize::MAX),
             current_input_layout: AtomicUsize::new(usize::MAX),
@@ -172,6 +201,12 @@ impl GpuContext {
             current_input_topology: AtomicI32::new(-1),
             current_depth_state: AtomicUsize::new(usize::MAX),
             use_flipped_depth_comparison: AtomicBool::new(false),
+            readonly_depth_pass: AtomicBool::new(false),
+            force_readonly_depth: AtomicBool::new(false),
+            equal_depth_pass: AtomicBool::new(false),
+            reported_depth_writes: Mutex::new(HashSet::new()),
+            invalid_input_layouts: Mutex::new(HashSet::new()),
+            wireframe: AtomicBool::new(false),
 
             current_states: AtomicCell::new(StateSelection::new(
                 Some(0),
@@ -181,7 +216,11 @@ impl GpuContext {
             )),
             custom_pixel_shader: None,
 
+            timestamp_profiling: AtomicBool::new(false),
             pending_timestamp_queries: Mutex::new(Vec::new()),
+            resolved_timestamps: Mutex::new(Vec::new()),
+            frame_index: AtomicUsize::new(0),
+            draw_calls: AtomicUsize::new(0),
         }))
     }
 
@@ -190,6 +229,32 @@ impl GpuContext {
     pub fn lock_context(&self) -> ReentrantMutexGuard<ID3D11DeviceContext> {
         self.context.lock()
     }
+
+    /// Checks whether the device supports all of the `required` capabilities for `format`
+    pub fn check_format_support(&self, format: DxgiFormat, required: D3D11_FORMAT_SUPPORT) -> bool {
+        let required = required.0 as u32;
+        unsafe { self.device.CheckFormatSupport(DXGI_FORMAT(format as i32)) }
+            .is_ok_and(|support| support & required == required)
+    }
+
+    /// Highest multisample quality level for `format` at `sample_count` samples, or `None` if that sample count is not supported
+    pub fn max_msaa_quality(&self, format: DxgiFormat, sample_count: u32) -> Option<u32> {
+        let levels = unsafe {
+            self.device
+                .CheckMultisampleQualityLevels(DXGI_FORMAT(format as i32), sample_count)
+        }
+        .ok()?;
+
+        levels.checked_sub(1)
+    }
+}
+
+/// GPU timings of the profiling spans of a single frame
+pub struct GpuFrameTimings {
+    /// See [`GpuContext::frame_index`]
+    pub frame: usize,
+    /// (label, milliseconds) pairs, in the order they were resolved
+    pub timings: Vec<(String, f32)>,
 }
 
 impl Deref for GpuContext {
@@ -201,8 +266,15 @@ impl Deref for GpuContext {
 }
 
 impl GpuContext {
+    /// Upper bound on unresolved timestamp ranges, so queries that never resolve can't pile up
+    const MAX_PENDING_TIMESTAMP_QUERIES: usize = 1024;
+
     pub fn begin_frame(&self) {
-        self.pending_timestamp_queries.lock().clear();
+        if self.timestamp_profiling.load(Ordering::Relaxed) {
+            self.resolve_timestamp_queries();
+        }
+        self.frame_index.fetch_add(1, Ordering::Relaxed);
+        self.draw_calls.store(0, Ordering::Relaxed);
         // for pending_timestamp in std::mem::take(&mut *self.pending_timestamp_queries.lock()) {
         //     let timestamp = pending_timestamp.resolve_blocking(self);
         //     if !timestamp.disjoint {
@@ -232,6 +304,110 @@ impl GpuContext {
         self.reset_states();
     }
 
+    /// Enables or disables GPU timestamp queries. Disabling drops any queries that haven't been resolved yet
+    pub fn set_timestamp_profiling(&self, enabled: bool) {
+        let was_enabled = self.timestamp_profiling.swap(enabled, Ordering::Relaxed);
+        if was_enabled && !enabled {
+            self.pending_timestamp_queries.lock().clear();
+            self.resolved_timestamps.lock().clear();
+        }
+    }
+
+    pub fn timestamp_profiling(&self) -> bool {
+        self.timestamp_profiling.load(Ordering::Relaxed)
+    }
+
+    /// Resolves the timestamp queries whose data is available without stalling.
+    /// Queries that aren't ready yet stay pending until a later frame
+    fn resolve_timestamp_queries(&self) {
+        let mut pending = self.pending_timestamp_queries.lock();
+        let mut resolved = self.resolved_timestamps.lock();
+        let mut failed = 0;
+        pending.retain(|query| match query.resolve(self) {
+            Ok(Some(timestamp)) => {
+                if !timestamp.disjoint {
+                    resolved.push(timestamp);
+                }
+                false
+            }
+            Ok(None) => true,
+            Err(e) => {
+                if failed == 0 {
+                    error!("Failed to resolve timestamp query '{}': {e}", query.label());
+                }
+                failed += 1;
+                false
+            }
+        });
+
+        if failed > 1 {
+            error!("Failed to resolve {failed} timestamp queries");
+        }
+
+        if pending.len() > Self::MAX_PENDING_TIMESTAMP_QUERIES {
+            let excess = pending.len() - Self::MAX_PENDING_TIMESTAMP_QUERIES;
+            pending.drain(..excess);
+        }
+
+        // Frames that were never taken (eg. while nothing is logging them) shouldn't pile up either
+        if resolved.len() > Self::MAX_PENDING_TIMESTAMP_QUERIES {
+            let excess = resolved.len() - Self::MAX_PENDING_TIMESTAMP_QUERIES;
+            resolved.drain(..excess);
+        }
+    }
+
+    /// Index of the current frame, which profiling spans are tagged with
+    pub fn frame_index(&self) -> usize {
+        self.frame_index.load(Ordering::Relaxed)
+    }
+
+    /// Takes the timings of every frame whose profiling spans have all been resolved, oldest first.
+    /// Frames usually complete a few frames after they were recorded, and each frame is only returned once.
+    /// Spans that were disjoint or failed to resolve are missing from their frame.
+    /// Empty unless timestamp profiling is enabled, see [`Self::set_timestamp_profiling`]
+    pub fn take_frame_timings(&self) -> Vec<GpuFrameTimings> {
+        let oldest_pending = self
+            .pending_timestamp_queries
+            .lock()
+            .iter()
+            .map(|q| q.frame())
+            .min()
+            .unwrap_or(usize::MAX);
+
+        let mut resolved = self.resolved_timestamps.lock();
+        let (complete, incomplete): (Vec<_>, Vec<_>) = std::mem::take(&mut *resolved)
+            .into_iter()
+            .partition(|t| t.frame < oldest_pending && t.frame < self.frame_index());
+        *resolved = incomplete;
+
+        let mut frames: Vec<GpuFrameTimings> = vec![];
+        for timestamp in complete {
+            let timing = (timestamp.label.clone(), timestamp.to_miliseconds_f32());
+            match frames.iter_mut().find(|f| f.frame == timestamp.frame) {
+                Some(frame) => frame.timings.push(timing),
+                None => frames.push(GpuFrameTimings {
+                    frame: timestamp.frame,
+                    timings: vec![timing],
+                }),
+            }
+        }
+        frames.sort_by_key(|f| f.frame);
+
+        frames
+    }
+
+    /// Counts a scene geometry draw call towards [`Self::draw_calls`]
+    #[inline]
+    pub fn count_draw_call(&self) {
+        self.draw_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Scene geometry draw calls issued since the start of the frame.
+    /// Fullscreen passes and debug shapes aren't counted
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls.load(Ordering::Relaxed)
+    }
+
     fn reset_states(&self) {
         // Reset current states
         self.current_blend_state
@@ -269,6 +445,38 @@ impl GpuContext {
         // cohae: Since the depth/stencil state only checks the index and not whether we changed the flipped state, we need to flush the depth state manually
         self.flush_states();
     }
+
+    /// Marks the start or end of a pass that should not write to the depth buffer
+    pub fn set_readonly_depth_pass(&self, readonly: bool) {
+        self.readonly_depth_pass.store(readonly, Ordering::Relaxed);
+        // Same as with flipped depth, the state for the current index might not be the right variant anymore
+        self.current_depth_state
+            .store(usize::MAX, Ordering::Relaxed);
+    }
+
+    /// Marks the start or end of a pass where depth has already been written by a prepass of the same geometry.
+    /// Depth stencil states that write depth are replaced with ones that only pass fragments at the same depth
+    pub fn set_equal_depth_pass(&self, equal: bool) {
+        self.equal_depth_pass.store(equal, Ordering::Relaxed);
+        self.current_depth_state
+            .store(usize::MAX, Ordering::Relaxed);
+    }
+
+    /// Marks the start or end of geometry that should be drawn as wireframe
+    pub fn set_wireframe(&self, wireframe: bool) {
+        if self.wireframe.swap(wireframe, Ordering::Relaxed) != wireframe {
+            // Re-applies the rasterizer state with the new fill mode
+            self.flush_states();
+        }
+    }
+
+    fn rasterizer_state(&self, depth_bias: usize, index: usize) -> Option<&ID3D11RasterizerState> {
+        if self.wireframe.load(Ordering::Relaxed) {
+            self.states.rasterizer_states_wireframe[depth_bias][index].as_ref()
+        } else {
+            self.states.rasterizer_states[depth_bias][index].as_ref()
+        }
+    }
 }
 
 impl GpuContext {
@@ -287,16 +495,29 @@ impl GpuContext {
 
     pub fn set_depth_stencil_state(&self, index: usize) {
         if self.current_depth_state.load(Ordering::Relaxed) != index {
-            let states = &self.states.depth_stencil_states[index];
+            let mut states = &self.states.depth_stencil_states[index];
+            if self.states.depth_stencil_writes[index]
+                && self.readonly_depth_pass.load(Ordering::Relaxed)
+            {
+                if self.force_readonly_depth.load(Ordering::Relaxed) {
+                    states = &self.states.depth_stencil_states_readonly[index];
+                } else if self.reported_depth_writes.lock().insert(index) {
+                    warn!("Depth stencil state {index} writes depth during a read-only depth pass");
+                }
+            }
+
+            let state = if self.states.depth_stencil_writes[index]
+                && self.equal_depth_pass.load(Ordering::Relaxed)
+            {
+                &self.states.depth_stencil_states_equal[index]
+            } else if self.use_flipped_depth_comparison.load(Ordering::Relaxed) {
+                &states.1
+            } else {
+                &states.0
+            };
+
             unsafe {
-                self.lock_context().OMSetDepthStencilState(
-                    if self.use_flipped_depth_comparison.load(Ordering::Relaxed) {
-                        &states.1
-                    } else {
-                        &states.0
-                    },
-                    0,
-                );
+                self.lock_context().OMSetDepthStencilState(state, 0);
             }
             self.current_depth_state.store(index, Ordering::Relaxed);
         }
@@ -308,7 +529,7 @@ impl GpuContext {
                 let depth_bias = self.current_depth_bias.load(Ordering::Relaxed);
                 if index < 9 && depth_bias < 9 {
                     self.lock_context()
-                        .RSSetState(self.states.rasterizer_states[depth_bias][index].as_ref());
+                        .RSSetState(self.rasterizer_state(depth_bias, index));
                 }
             }
             self.current_rasterizer_state
@@ -321,16 +542,27 @@ impl GpuContext {
             unsafe {
                 let rasterizer_state = self.current_rasterizer_state.load(Ordering::Relaxed);
                 if index < 9 && rasterizer_state < 9 {
-                    self.lock_context().RSSetState(
-                        self.states.rasterizer_states[index][rasterizer_state].as_ref(),
-                    );
+                    self.lock_context()
+                        .RSSetState(self.rasterizer_state(index, rasterizer_state));
                 }
             }
             self.current_depth_bias.store(index, Ordering::Relaxed);
         }
     }
 
-    pub fn set_input_layout(&self, index: usize) {
+    /// Binds the input layout at the given index.
+    /// Returns false without changing the bound layout if the index is out of range, in which case the draw should be skipped
+    pub fn set_input_layout(&self, index: usize) -> bool {
+        if index >= self.states.input_layouts.len() {
+            if self.invalid_input_layouts.lock().insert(index) {
+                error!(
+                    "Input layout {index} is out of range, only {} layouts are registered",
+                    self.states.input_layouts.len()
+                );
+            }
+            return false;
+        }
+
         if self.current_input_layout.load(Ordering::Relaxed) != index {
             unsafe {
                 self.lock_context()
@@ -338,6 +570,20 @@ impl GpuContext {
             }
             self.current_input_layout.store(index, Ordering::Relaxed);
         }
+
+        true
+    }
+
+    /// Out of range input layout indices that have been requested so far, sorted
+    pub fn invalid_input_layouts(&self) -> Vec<usize> {
+        let mut indices = self
+            .invalid_input_layouts
+            .lock()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices
     }
 
     pub fn set_input_topology(&self, topology: EPrimitiveType) {
@@ -409,9 +655,28 @@ impl GpuContext {
 unsafe impl Send for GpuContext {}
 unsafe impl Sync for GpuContext {}
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DepthMode {
+    /// Reversed-Z, where 1 is the near plane and 0 is infinitely far away
     Normal,
     /// Used for rendering shadowmaps
     Flipped,
 }
+
+impl DepthMode {
+    /// The depth that is furthest away from the camera, which depth buffers should be cleared to
+    pub fn clear_value(&self) -> f32 {
+        match self {
+            DepthMode::Normal => 0.0,
+            DepthMode::Flipped => 1.0,
+        }
+    }
+
+    /// Depth comparison that passes for geometry closer to the camera
+    pub fn comparison_func(&self) -> D3D11_COMPARISON_FUNC {
+        match self {
+            DepthMode::Normal => D3D11_COMPARISON_GREATER_EQUAL,
+            DepthMode::Flipped => D3D11_COMPARISON_LESS_EQUAL,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossbeam::atomic::AtomicCell;
+use destiny_pkg::TagHash;
+use parking_lot::RwLock;
+use rustc_hash::FxHashMap;
+use windows::Win32::Graphics::Direct3D11::*;
+
+/// Filters that sample with (bi/tri)linear minification, which are the only ones that get upgraded to anisotropic filtering.
+/// Point and comparison (shadow) samplers are left as-is
+const LINEAR_FILTERS: [D3D11_FILTER; 5] = [
+    D3D11_FILTER_MIN_LINEAR_MAG_MIP_POINT,
+    D3D11_FILTER_MIN_LINEAR_MAG_POINT_MIP_LINEAR,
+    D3D11_FILTER_MIN_MAG_LINEAR_MIP_POINT,
+    D3D11_FILTER_MIN_MAG_MIP_LINEAR,
+    D3D11_FILTER_ANISOTROPIC,
+];
+
+/// Sampler states used by material techniques.
+/// These are created from the descriptions in the game files, with the filtering settings applied on top.
+/// Changing the settings recreates all samplers and increments the generation, so users know to fetch them again
+pub struct MaterialSamplers {
+    device: ID3D11Device,
+    samplers: RwLock<FxHashMap<TagHash, (D3D11_SAMPLER_DESC, ID3D11SamplerState)>>,
+    anisotropy: AtomicCell<u32>,
+    mip_lod_bias: AtomicCell<f32>,
+    generation: AtomicUsize,
+}
+
+impl MaterialSamplers {
+    pub fn new(device: &ID3D11Device) -> Self {
+        Self {
+            device: device.clone(),
+            samplers: RwLock::new(FxHashMap::default()),
+            anisotropy: AtomicCell::new(1),
+            mip_lod_bias: AtomicCell::new(0.0),
+            generation: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    pub fn get(&self, hash: TagHash) -> Option<ID3D11SamplerState> {
+        self.samplers.read().get(&hash).map(|(_, s)| s.clone())
+    }
+
+    /// Returns the sampler for the given tag, creating it from the description returned by `load_desc` if it doesn't exist yet
+    pub fn get_or_create(
+        &self,
+        hash: TagHash,
+        load_desc: impl FnOnce() -> anyhow::Result<D3D11_SAMPLER_DESC>,
+    ) -> anyhow::Result<ID3D11SamplerState> {
+        if let Some(sampler) = self.get(hash) {
+            return Ok(sampler);
+        }
+
+        let desc = load_desc()?;
+        let sampler = self.create(&desc)?;
+        self.samplers.write().insert(hash, (desc, sampler.clone()));
+
+        Ok(sampler)
+    }
+
+    /// Sets the maximum anisotropy for material samplers. A level of 1 disables anisotropic filtering
+    pub fn set_anisotropy(&self, level: u32) {
+        let level = level.clamp(1, D3D11_REQ_MAXANISOTROPY);
+        if self.anisotropy.swap(level) != level {
+            self.recreate_all();
+        }
+    }
+
+    /// Sets the bias added to the mip level selected by material samplers. Negative values make textures sharper
+    pub fn set_mip_lod_bias(&self, bias: f32) {
+        if self.mip_lod_bias.swap(bias) != bias {
+            self.recreate_all();
+        }
+    }
+
+    fn recreate_all(&self) {
+        let mut samplers = self.samplers.write();
+        for (hash, (desc, sampler)) in samplers.iter_mut() {
+            match self.create(desc) {
+                Ok(s) => *sampler = s,
+                Err(e) => error!("Failed to recreate sampler {hash}: {e}"),
+            }
+        }
+
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn create(&self, desc: &D3D11_SAMPLER_DESC) -> anyhow::Result<ID3D11SamplerState> {
+        let mut desc = *desc;
+
+        let anisotropy = self.anisotropy.load();
+        if anisotropy > 1 && LINEAR_FILTERS.contains(&desc.Filter) {
+            desc.Filter = D3D11_FILTER_ANISOTROPIC;
+            desc.MaxAnisotropy = anisotropy;
+        }
+
+        desc.MipLODBias = (desc.MipLODBias + self.mip_lod_bias.load())
+            .clamp(D3D11_MIP_LOD_BIAS_MIN, D3D11_MIP_LOD_BIAS_MAX);
+
+        let mut sampler = None;
+        unsafe {
+            self.device.CreateSamplerState(&desc, Some(&mut sampler))?;
+        }
+
+        Ok(sampler.unwrap())
+    }
+}
@@ -15,7 +15,10 @@ use windows::Win32::Graphics::{
 };
 
 use crate::{
-    gpu::GpuContext,
+    gpu::{
+        debug::{GpuObjectKind, GpuObjectTracker},
+        GpuContext,
+    },
     util::{
         d3d::{calc_dx_subresource, D3dResource},
         image::Png,
@@ -34,6 +37,7 @@ pub struct Texture {
     pub view: ID3D11ShaderResourceView,
     pub handle: TextureHandle,
     pub format: DxgiFormat,
+    _tracker: GpuObjectTracker,
 }
 
 impl Texture {
@@ -317,6 +321,7 @@ impl Texture {
             handle: tex,
             view,
             format: texture.format,
+            _tracker: GpuObjectTracker::new(GpuObjectKind::Texture),
         })
     }
 
@@ -384,6 +389,7 @@ impl Texture {
                 handle: TextureHandle::Texture2D(tex),
                 view,
                 format,
+                _tracker: GpuObjectTracker::new(GpuObjectKind::Texture),
             })
         }
     }
@@ -450,6 +456,7 @@ impl Texture {
                 handle: TextureHandle::Texture3D(tex),
                 view,
                 format,
+                _tracker: GpuObjectTracker::new(GpuObjectKind::Texture),
             })
         }
     }
@@ -486,6 +493,36 @@ impl Texture {
     pub fn bind(&self, gctx: &GpuContext, slot: u32, stage: TfxShaderStage) {
         gctx.bind_srv(Some(self.view.clone()), slot, stage);
     }
+
+    /// Approximate amount of video memory used by the texture, including all mips and array slices
+    pub fn size_in_bytes(&self) -> usize {
+        let (width, height, depth, mip_count, array_size) = unsafe {
+            match &self.handle {
+                TextureHandle::Texture2D(tex) | TextureHandle::TextureCube(tex) => {
+                    let mut desc = D3D11_TEXTURE2D_DESC::default();
+                    tex.GetDesc(&mut desc);
+                    (desc.Width, desc.Height, 1, desc.MipLevels, desc.ArraySize)
+                }
+                TextureHandle::Texture3D(tex) => {
+                    let mut desc = D3D11_TEXTURE3D_DESC::default();
+                    tex.GetDesc(&mut desc);
+                    (desc.Width, desc.Height, desc.Depth, desc.MipLevels, 1)
+                }
+            }
+        };
+
+        let mip_chain: usize = (0..mip_count.max(1))
+            .map(|mip| {
+                let (_, slice_pitch) = self.format.calculate_pitch(
+                    (width >> mip).max(1) as usize,
+                    (height >> mip).max(1) as usize,
+                );
+                slice_pitch * (depth >> mip).max(1) as usize
+            })
+            .sum();
+
+        mip_chain * array_size as usize
+    }
 }
 
 fn dxgi_to_win(v: DxgiFormat) -> DXGI_FORMAT {
@@ -2,8 +2,8 @@ use alkahest_data::geometry::EPrimitiveType;
 use windows::Win32::Graphics::Direct3D11::{
     ID3D11ComputeShader, ID3D11Device, ID3D11PixelShader, ID3D11RenderTargetView,
     ID3D11SamplerState, ID3D11ShaderResourceView, ID3D11Texture2D, ID3D11VertexShader,
-    D3D11_COMPARISON_NEVER, D3D11_FILTER_MIN_MAG_MIP_POINT, D3D11_SAMPLER_DESC,
-    D3D11_TEXTURE_ADDRESS_CLAMP,
+    D3D11_COMPARISON_NEVER, D3D11_FILTER_MIN_MAG_MIP_LINEAR, D3D11_FILTER_MIN_MAG_MIP_POINT,
+    D3D11_SAMPLER_DESC, D3D11_TEXTURE_ADDRESS_CLAMP,
 };
 
 use crate::{gpu::GpuContext, gpu_event, include_dxbc};
@@ -14,8 +14,13 @@ pub struct UtilResources {
     pub blit_ps: ID3D11PixelShader,
     pub blit_srgb_ps: ID3D11PixelShader,
     pub blit_alphaluminance_ps: ID3D11PixelShader,
+    pub blit_sharp_ps: ID3D11PixelShader,
+    pub blit_tonemap_ps: ID3D11PixelShader,
+    /// Flat color pixel shader used to highlight water
+    pub water_tint_ps: ID3D11PixelShader,
 
     pub point_sampler: ID3D11SamplerState,
+    pub linear_sampler: ID3D11SamplerState,
 }
 
 impl UtilResources {
@@ -35,6 +40,15 @@ impl UtilResources {
         let blit_alphaluminance_ps = device
             .load_pixel_shader(include_dxbc!(ps "util/copy_with_luminance_as_alpha.hlsl"))
             .unwrap();
+        let blit_sharp_ps = device
+            .load_pixel_shader(include_dxbc!(ps "util/blit_sharp.hlsl"))
+            .unwrap();
+        let blit_tonemap_ps = device
+            .load_pixel_shader(include_dxbc!(ps "util/blit_tonemap.hlsl"))
+            .unwrap();
+        let water_tint_ps = device
+            .load_pixel_shader(include_dxbc!(ps "debug/water_tint.hlsl"))
+            .unwrap();
 
         let point_sampler = device
             .create_sampler_state(&D3D11_SAMPLER_DESC {
@@ -51,13 +65,32 @@ impl UtilResources {
             })
             .unwrap();
 
+        let linear_sampler = device
+            .create_sampler_state(&D3D11_SAMPLER_DESC {
+                Filter: D3D11_FILTER_MIN_MAG_MIP_LINEAR,
+                AddressU: D3D11_TEXTURE_ADDRESS_CLAMP,
+                AddressV: D3D11_TEXTURE_ADDRESS_CLAMP,
+                AddressW: D3D11_TEXTURE_ADDRESS_CLAMP,
+                MipLODBias: 0.0,
+                MaxAnisotropy: 1,
+                ComparisonFunc: D3D11_COMPARISON_NEVER,
+                BorderColor: [0.0, 0.0, 0.0, 0.0],
+                MinLOD: 0.0,
+                MaxLOD: f32::MAX,
+            })
+            .unwrap();
+
         Self {
             entity_vs_override,
             blit_vs,
             blit_ps,
             blit_srgb_ps,
             blit_alphaluminance_ps,
+            blit_sharp_ps,
+            blit_tonemap_ps,
+            water_tint_ps,
             point_sampler,
+            linear_sampler,
         }
     }
 }
@@ -78,6 +111,7 @@ impl GpuContext {
             } else {
                 &self.util_resources.blit_ps
             },
+            &self.util_resources.point_sampler,
         );
     }
 
@@ -91,6 +125,48 @@ impl GpuContext {
             texture_view,
             rt,
             &self.util_resources.blit_alphaluminance_ps,
+            &self.util_resources.point_sampler,
+        );
+    }
+
+    /// Blits an HDR texture to a render target with a simple tonemap, scaling it up to fill the target
+    pub fn blit_texture_tonemapped(
+        &self,
+        texture_view: &ID3D11ShaderResourceView,
+        rt: &ID3D11RenderTargetView,
+    ) {
+        gpu_event!(self, "blit_texture_tonemapped");
+        self.blit_internal(
+            texture_view,
+            rt,
+            &self.util_resources.blit_tonemap_ps,
+            &self.util_resources.linear_sampler,
+        );
+    }
+
+    /// Blits a texture to a (larger) render target using bilinear filtering, or a bicubic filter if `sharp` is set
+    pub fn upscale_texture(
+        &self,
+        texture_view: &ID3D11ShaderResourceView,
+        rt: &ID3D11RenderTargetView,
+        srgb: bool,
+        sharp: bool,
+    ) {
+        gpu_event!(self, "upscale_texture");
+        // The sharp filter doesn't do gamma conversion, so we fall back to bilinear for debug views that need it
+        let shader = if sharp && !srgb {
+            &self.util_resources.blit_sharp_ps
+        } else if srgb {
+            &self.util_resources.blit_srgb_ps
+        } else {
+            &self.util_resources.blit_ps
+        };
+
+        self.blit_internal(
+            texture_view,
+            rt,
+            shader,
+            &self.util_resources.linear_sampler,
         );
     }
 
@@ -99,6 +175,7 @@ impl GpuContext {
         texture_view: &ID3D11ShaderResourceView,
         rt: &ID3D11RenderTargetView,
         shader: &ID3D11PixelShader,
+        sampler: &ID3D11SamplerState,
     ) {
         let ctx = self.lock_context();
         unsafe {
@@ -112,7 +189,7 @@ impl GpuContext {
             self.set_input_topology(EPrimitiveType::Triangles);
             ctx.OMSetRenderTargets(Some(&[Some(rt.clone())]), None);
             ctx.OMSetDepthStencilState(None, 0);
-            ctx.PSSetSamplers(0, Some(&[Some(self.util_resources.point_sampler.clone())]));
+            ctx.PSSetSamplers(0, Some(&[Some(sampler.clone())]));
             ctx.PSSetShaderResources(0, Some(&[Some(texture_view.clone())]));
 
             ctx.Draw(3, 0);
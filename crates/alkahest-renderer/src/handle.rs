@@ -202,13 +202,33 @@ impl<T: Asset> Clone for Handle<T> {
 //     }
 // }
 
-pub trait Asset: Sized {}
+pub trait Asset: Sized {
+    /// Approximate amount of memory used by the asset, in bytes
+    fn approximate_size(&self) -> usize {
+        0
+    }
+}
 
 impl Asset for () {}
-impl Asset for Texture {}
 impl Asset for Technique {}
-impl Asset for VertexBuffer {}
-impl Asset for IndexBuffer {}
+
+impl Asset for Texture {
+    fn approximate_size(&self) -> usize {
+        self.size_in_bytes()
+    }
+}
+
+impl Asset for VertexBuffer {
+    fn approximate_size(&self) -> usize {
+        self.size as usize
+    }
+}
+
+impl Asset for IndexBuffer {
+    fn approximate_size(&self) -> usize {
+        self.length * self.format.bpp() / 8
+    }
+}
 
 struct AssetStorage<T: Asset> {
     refcount: Weak<()>,
@@ -217,6 +237,15 @@ struct AssetStorage<T: Asset> {
 
 type FastHasher = BuildHasherDefault<FxHasher>;
 
+#[derive(Default, Clone, Copy)]
+pub struct AssetRegistryStats {
+    /// Number of handles in the registry, including assets that are still loading
+    pub count: usize,
+    pub loaded: usize,
+    /// Approximate size of all loaded assets, in bytes
+    pub size: usize,
+}
+
 pub struct AssetRegistry<T: Asset> {
     handle_map: IndexMap<AssetId, AssetStorage<T>, FastHasher>,
     next_id: usize,
@@ -349,6 +378,20 @@ impl<T: Asset + 'static> AssetRegistry<T> {
             .and_then(|storage| storage.asset.clone())
     }
 
+    pub fn stats(&self) -> AssetRegistryStats {
+        let mut stats = AssetRegistryStats {
+            count: self.handle_map.len(),
+            ..Default::default()
+        };
+
+        for asset in self.handle_map.values().filter_map(|s| s.asset.as_ref()) {
+            stats.loaded += 1;
+            stats.size += asset.approximate_size();
+        }
+
+        stats
+    }
+
     pub fn remove_all_dead(&mut self) -> usize {
         let mut removed = 0;
         for idx in (0..self.handle_map.len()).rev() {
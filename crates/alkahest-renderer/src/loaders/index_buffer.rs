@@ -13,13 +13,20 @@ use windows::Win32::Graphics::{
     Dxgi::Common::DXGI_FORMAT,
 };
 
-use crate::{gpu::GpuContext, util::d3d::D3dResource};
+use crate::{
+    gpu::{
+        debug::{GpuObjectKind, GpuObjectTracker},
+        GpuContext,
+    },
+    util::d3d::D3dResource,
+};
 
 pub struct IndexBuffer {
     pub buffer: ID3D11Buffer,
     /// Amount of elements in the buffer
     pub length: usize,
     pub format: DxgiFormat,
+    _tracker: GpuObjectTracker,
 }
 
 impl IndexBuffer {
@@ -48,6 +55,7 @@ impl IndexBuffer {
             buffer,
             length: data.len(),
             format: DxgiFormat::R16_UINT,
+            _tracker: GpuObjectTracker::new(GpuObjectKind::IndexBuffer),
         })
     }
 
@@ -103,5 +111,6 @@ pub(crate) fn load_index_buffer(
         } else {
             DxgiFormat::R16_UINT
         },
+        _tracker: GpuObjectTracker::new(GpuObjectKind::IndexBuffer),
     })
 }
@@ -33,10 +33,10 @@ use crate::{
         audio::AmbientAudio,
         common::{Icon, Label, RenderCommonBundle, ResourceOrigin},
         hierarchy::{Children, Parent},
-        map::{CubemapVolume, MapAtmosphere, MapStaticAO, NodeMetadata},
+        map::{CubemapVolume, MapAtmosphere, MapLoadFailures, MapStaticAO, NodeMetadata},
         render::{
             decorators::DecoratorRenderer,
-            dynamic_geometry::DynamicModelComponent,
+            dynamic_geometry::{DynamicModelComponent, ModelPlaceholder},
             havok::HavokShapeRenderer,
             light::{LightRenderer, LightShape, ShadowMapRenderer},
             static_geometry::{StaticInstance, StaticInstances, StaticModel, StaticModelSingle},
@@ -387,6 +387,13 @@ pub async fn load_map(
         scene.entity_mut(entity).insert_one(transform);
     }
 
+    if let Some(failures) = scene.get_resource::<MapLoadFailures>() {
+        warn!(
+            "{} models failed to load for map {map_hash}",
+            failures.models.len()
+        );
+    }
+
     let mut to_update = vec![];
     for entity in scene
         .query_filtered::<Entity, With<TerrainPatches>>()
@@ -680,14 +687,17 @@ fn load_datatable_into_scene<R: Read + Seek>(
                         continue;
                     }
 
-                    let model = DynamicModelComponent::load(
+                    let Some(model) = load_dynamic_model(
+                        scene,
                         renderer,
                         &transform,
                         unk8.unk60.entity_model,
                         vec![],
                         vec![],
                         TfxFeatureRenderer::SkyTransparent,
-                    )?;
+                    ) else {
+                        continue;
+                    };
                     let transform = Transform::from_mat4(Mat4::from_cols_array(&unk8.transform));
                     spawn_data_entity(
                         scene,
@@ -721,14 +731,17 @@ fn load_datatable_into_scene<R: Read + Seek>(
                     continue;
                 }
 
-                let model = DynamicModelComponent::load(
+                let Some(model) = load_dynamic_model(
+                    scene,
                     renderer,
                     &transform,
                     d.entity_model,
                     vec![],
                     vec![],
                     TfxFeatureRenderer::Water,
-                )?;
+                ) else {
+                    continue;
+                };
                 if d.entity_model.is_some() {
                     spawn_data_entity(
                         scene,
@@ -1583,6 +1596,36 @@ fn get_entity_labels(entity: TagHash) -> Option<FxHashMap<u64, String>> {
     )
 }
 
+/// Loads a dynamic model, recording the failure in [`MapLoadFailures`] instead of aborting the map load
+fn load_dynamic_model(
+    scene: &mut Scene,
+    renderer: &Renderer,
+    transform: &Transform,
+    hash: TagHash,
+    technique_map: Vec<Unk808072c5>,
+    techniques: Vec<TagHash>,
+    feature_type: TfxFeatureRenderer,
+) -> Option<DynamicModelComponent> {
+    match DynamicModelComponent::load(
+        renderer,
+        transform,
+        hash,
+        technique_map,
+        techniques,
+        feature_type,
+    ) {
+        Ok(model) => Some(model),
+        Err(e) => {
+            error!(error=?e, model=%hash, "Failed to load dynamic model");
+            scene
+                .get_resource_or_insert_with(MapLoadFailures::default)
+                .models
+                .push(hash);
+            None
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn load_entity_into_scene(
     entity_hash: TagHash,
@@ -1651,19 +1694,26 @@ fn load_entity_into_scene(
                 let materials: Vec<TagHash> =
                     TigerReadable::read_ds_endian(&mut cur, Endian::Little)?;
 
-                let model = DynamicModelComponent::load(
+                // The entity itself is kept when its model fails to load, so its children still get loaded
+                if let Some(model) = load_dynamic_model(
+                    scene,
                     renderer,
                     &transform,
                     model_hash,
                     entity_material_map,
                     materials,
                     TfxFeatureRenderer::DynamicObjects,
-                )?;
-                scene.entity_mut(scene_entity).insert((
-                    model.model.occlusion_bounds(),
-                    model,
-                    TfxFeatureRenderer::DynamicObjects,
-                ));
+                ) {
+                    scene.entity_mut(scene_entity).insert((
+                        model.model.occlusion_bounds(),
+                        model,
+                        TfxFeatureRenderer::DynamicObjects,
+                    ));
+                } else {
+                    scene
+                        .entity_mut(scene_entity)
+                        .insert_one(ModelPlaceholder { model: model_hash });
+                }
             }
             u => {
                 debug!(
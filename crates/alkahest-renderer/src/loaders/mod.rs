@@ -1,13 +1,19 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use crossbeam::channel::{Receiver, Sender};
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender};
 use destiny_pkg::TagHash;
 use rustc_hash::FxHashSet;
 use strum::AsRefStr;
 
 use crate::{
     gpu::{texture::Texture, GpuContext},
-    handle::{AssetId, AssetIdValue, AssetRegistry, Handle, RawHandle},
+    handle::{AssetId, AssetIdValue, AssetRegistry, AssetRegistryStats, Handle, RawHandle},
     loaders::{index_buffer::IndexBuffer, vertex_buffer::VertexBuffer},
     tfx::technique::Technique,
     util::{d3d::ErrorExt, packages::TagHashExt},
@@ -30,18 +36,37 @@ pub struct AssetManager {
     pub index_buffers: AssetRegistry<IndexBuffer>,
 
     request_tx: Sender<LoadRequest>,
+    /// Kept around so workers can be added to the pool later
+    request_rx: Receiver<LoadRequest>,
+    asset_tx: Sender<LoadedAsset>,
     asset_rx: Receiver<LoadedAsset>,
-    _workers: Vec<std::thread::JoinHandle<()>>,
+    workers: Vec<std::thread::JoinHandle<()>>,
+    /// Number of workers the pool is supposed to have, excluding the ones that are retiring
+    worker_count: usize,
+    /// Number of workers that should exit once they finish their current request
+    retiring_workers: Arc<AtomicUsize>,
+    /// Amount of requests currently being processed by the workers
+    in_flight: Arc<AtomicUsize>,
 
     pending_requests: FxHashSet<AssetId>,
 }
 
 impl AssetManager {
-    pub fn new(gctx: Arc<GpuContext>) -> Self {
+    pub fn new(gctx: Arc<GpuContext>, worker_count: usize) -> Self {
         let (request_tx, request_rx) = crossbeam::channel::unbounded();
         let (asset_tx, asset_rx) = crossbeam::channel::unbounded();
 
-        let workers = spawn_load_workers(gctx.clone(), 4, request_rx, asset_tx);
+        let worker_count = worker_count.max(1);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let retiring_workers = Arc::new(AtomicUsize::new(0));
+        let workers = spawn_load_workers(
+            gctx.clone(),
+            0..worker_count,
+            request_rx.clone(),
+            asset_tx.clone(),
+            in_flight.clone(),
+            retiring_workers.clone(),
+        );
 
         Self {
             gctx,
@@ -51,15 +76,20 @@ impl AssetManager {
             vertex_buffers: AssetRegistry::new(true),
             index_buffers: AssetRegistry::new(true),
             request_tx,
+            request_rx,
+            asset_tx,
             asset_rx,
-            _workers: workers,
+            workers,
+            worker_count,
+            retiring_workers,
+            in_flight,
             pending_requests: FxHashSet::default(),
         }
     }
 
     pub fn new_disabled(gctx: Arc<GpuContext>) -> Self {
-        let (request_tx, _request_rx) = crossbeam::channel::unbounded();
-        let (_asset_tx, asset_rx) = crossbeam::channel::unbounded();
+        let (request_tx, request_rx) = crossbeam::channel::unbounded();
+        let (asset_tx, asset_rx) = crossbeam::channel::unbounded();
 
         Self {
             gctx,
@@ -69,8 +99,13 @@ impl AssetManager {
             vertex_buffers: AssetRegistry::new(false),
             index_buffers: AssetRegistry::new(false),
             request_tx,
+            request_rx,
+            asset_tx,
             asset_rx,
-            _workers: vec![],
+            workers: vec![],
+            worker_count: 0,
+            retiring_workers: Default::default(),
+            in_flight: Default::default(),
             pending_requests: FxHashSet::default(),
         }
     }
@@ -166,12 +201,7 @@ impl AssetManager {
             debug!("Polling asset manager ({} assets to process)", budget);
         }
 
-        let mut total_removed = 0;
-        total_removed += self.textures.remove_all_dead();
-        total_removed += self.techniques.remove_all_dead();
-        total_removed += self.vertex_buffers.remove_all_dead();
-        total_removed += self.index_buffers.remove_all_dead();
-
+        let total_removed = self.remove_all_dead();
         if total_removed > 0 {
             trace!("Removed {total_removed} dead assets");
         }
@@ -274,6 +304,108 @@ impl AssetManager {
     pub fn remaining_requests(&self) -> usize {
         self.pending_requests.len()
     }
+
+    /// Amount of requests that are currently being processed, as opposed to waiting in the queue
+    pub fn in_flight_requests(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.worker_count
+    }
+
+    /// Grows or shrinks the loader thread pool without waiting for it.
+    /// New workers pick up requests from the same queue, and removed workers exit after finishing their current request,
+    /// so nothing that's queued or being loaded is dropped
+    pub fn set_worker_count(&mut self, worker_count: usize) {
+        let worker_count = worker_count.max(1);
+        if self.disabled || worker_count == self.worker_count {
+            return;
+        }
+
+        profiling::scope!("AssetManager::set_worker_count");
+        // Finished workers have already left the pool, dropping their handles just detaches them
+        self.workers.retain(|w| !w.is_finished());
+
+        if worker_count > self.worker_count {
+            // Workers that haven't retired yet are kept instead of spawning new ones
+            let mut to_spawn = worker_count - self.worker_count;
+            let retiring = self
+                .retiring_workers
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |r| {
+                    Some(r - r.min(to_spawn))
+                })
+                .unwrap_or_default();
+            to_spawn -= retiring.min(to_spawn);
+
+            let first_index = self.workers.len();
+            self.workers.extend(spawn_load_workers(
+                self.gctx.clone(),
+                first_index..first_index + to_spawn,
+                self.request_rx.clone(),
+                self.asset_tx.clone(),
+                self.in_flight.clone(),
+                self.retiring_workers.clone(),
+            ));
+        } else {
+            self.retiring_workers
+                .fetch_add(self.worker_count - worker_count, Ordering::Relaxed);
+        }
+
+        self.worker_count = worker_count;
+        info!("Asset loader is now using {worker_count} threads");
+    }
+
+    fn remove_all_dead(&mut self) -> usize {
+        // Techniques hold handles to their textures, so they're removed first
+        self.techniques.remove_all_dead()
+            + self.textures.remove_all_dead()
+            + self.vertex_buffers.remove_all_dead()
+            + self.index_buffers.remove_all_dead()
+    }
+
+    /// Removes every asset that is no longer referenced by a handle.
+    /// Returns the amount of assets removed
+    pub fn purge_unused(&mut self) -> usize {
+        profiling::scope!("AssetManager::purge_unused");
+        let mut total_removed = 0;
+        // Removing an asset can release the last handle to another one (eg. a technique's textures)
+        loop {
+            let removed = self.remove_all_dead();
+            if removed == 0 {
+                break;
+            }
+            total_removed += removed;
+        }
+
+        total_removed
+    }
+
+    pub fn stats(&self) -> AssetManagerStats {
+        AssetManagerStats {
+            textures: self.textures.stats(),
+            techniques: self.techniques.stats(),
+            vertex_buffers: self.vertex_buffers.stats(),
+            index_buffers: self.index_buffers.stats(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct AssetManagerStats {
+    pub textures: AssetRegistryStats,
+    pub techniques: AssetRegistryStats,
+    pub vertex_buffers: AssetRegistryStats,
+    pub index_buffers: AssetRegistryStats,
+}
+
+impl AssetManagerStats {
+    pub fn total_size(&self) -> usize {
+        self.textures.size
+            + self.techniques.size
+            + self.vertex_buffers.size
+            + self.index_buffers.size
+    }
 }
 
 #[derive(AsRefStr)]
@@ -318,11 +450,24 @@ fn load_worker_thread(
     gctx: Arc<GpuContext>,
     rx_request: Receiver<LoadRequest>,
     tx: Sender<LoadedAsset>,
+    in_flight: Arc<AtomicUsize>,
+    retiring_workers: Arc<AtomicUsize>,
 ) -> anyhow::Result<()> {
+    /// How often idle workers check whether they should retire
+    const RETIRE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
     profiling::register_thread!();
     loop {
-        match rx_request.recv() {
+        if retiring_workers
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |r| r.checked_sub(1))
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        match rx_request.recv_timeout(RETIRE_POLL_INTERVAL) {
             Ok(request) => {
+                in_flight.fetch_add(1, Ordering::Relaxed);
                 profiling::scope!(
                     "load_worker_thread::handle_request",
                     &format!("{} {:?}", request.as_ref(), request.handle().id())
@@ -381,7 +526,9 @@ fn load_worker_thread(
                         }
                     },
                 }
+                in_flight.fetch_sub(1, Ordering::Relaxed);
             }
+            Err(RecvTimeoutError::Timeout) => {}
             Err(e) => {
                 return Err(e.into());
             }
@@ -389,24 +536,31 @@ fn load_worker_thread(
     }
 }
 
+/// Spawns a loader thread for every index in `indices`, which are only used to name the threads
 pub fn spawn_load_workers(
     gctx: Arc<GpuContext>,
-    num_workers: usize,
+    indices: std::ops::Range<usize>,
     rx_request: Receiver<LoadRequest>,
     tx: Sender<LoadedAsset>,
+    in_flight: Arc<AtomicUsize>,
+    retiring_workers: Arc<AtomicUsize>,
 ) -> Vec<std::thread::JoinHandle<()>> {
-    (0..num_workers)
+    indices
         .map(|i| {
             let gctx = gctx.clone();
             let rx_request = rx_request.clone();
             let tx = tx.clone();
+            let in_flight = in_flight.clone();
+            let retiring_workers = retiring_workers.clone();
 
             std::thread::Builder::new()
                 .name(format!("alkahest-loader-{i}"))
-                .spawn(move || match load_worker_thread(gctx, rx_request, tx) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        debug!("Loader thread exited: {:?}", e);
+                .spawn(move || {
+                    match load_worker_thread(gctx, rx_request, tx, in_flight, retiring_workers) {
+                        Ok(_) => {}
+                        Err(e) => {
+                            debug!("Loader thread exited: {:?}", e);
+                        }
                     }
                 })
                 .unwrap()
@@ -8,7 +8,7 @@ use alkahest_pm::package_manager;
 use anyhow::{ensure, Context};
 use destiny_pkg::TagHash;
 use tiger_parse::PackageManagerExt;
-use windows::Win32::Graphics::Direct3D11::ID3D11SamplerState;
+use windows::Win32::Graphics::Direct3D11::{ID3D11SamplerState, D3D11_SAMPLER_DESC};
 
 use crate::{
     gpu::{buffer::ConstantBufferCached, GpuContext},
@@ -105,6 +105,8 @@ fn load_technique_stage(
         shader: shader.clone(),
 
         samplers: vec![],
+        sampler_hashes: vec![],
+        sampler_generation: 0,
         textures: vec![],
         shader_module: ShaderModule::load(&gctx, shader.shader)
             .with_context(|| format!("Failed to load shader module {}", shader.shader))?
@@ -120,15 +122,31 @@ fn load_technique_stage(
     });
 
     for sampler in shader.constants.samplers.iter() {
-        stage
-            .samplers
-            .push(load_sampler(&gctx, sampler.hash32()).ok());
+        let hash = sampler.hash32();
+        stage.sampler_hashes.push(hash);
+        stage.samplers.push(
+            gctx.material_samplers
+                .get_or_create(hash, || load_sampler_desc(hash))
+                .ok(),
+        );
     }
+    stage.sampler_generation = gctx.material_samplers.generation();
 
     Ok(Some(stage))
 }
 
 pub fn load_sampler(gctx: &GpuContext, hash: TagHash) -> anyhow::Result<ID3D11SamplerState> {
+    let desc = load_sampler_desc(hash)?;
+
+    let mut sampler = None;
+    unsafe {
+        gctx.device.CreateSamplerState(&desc, Some(&mut sampler))?;
+    };
+
+    Ok(sampler.unwrap())
+}
+
+pub fn load_sampler_desc(hash: TagHash) -> anyhow::Result<D3D11_SAMPLER_DESC> {
     let entry = package_manager()
         .get_entry(hash)
         .context("Sampler entry not found")?;
@@ -140,12 +158,10 @@ pub fn load_sampler(gctx: &GpuContext, hash: TagHash) -> anyhow::Result<ID3D11Sa
     let sampler_data = package_manager()
         .read_tag(sampler_header_ref)
         .context("Failed to read sampler data")?;
+    ensure!(
+        sampler_data.len() >= std::mem::size_of::<D3D11_SAMPLER_DESC>(),
+        "Sampler data is too small"
+    );
 
-    let mut sampler = None;
-    unsafe {
-        gctx.device
-            .CreateSamplerState(sampler_data.as_ptr() as _, Some(&mut sampler))?;
-    };
-
-    Ok(sampler.unwrap())
+    Ok(unsafe { (sampler_data.as_ptr() as *const D3D11_SAMPLER_DESC).read_unaligned() })
 }
@@ -14,7 +14,13 @@ use windows::Win32::Graphics::{
     Dxgi::Common::{DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_FORMAT_R8_UNORM},
 };
 
-use crate::{gpu::GpuContext, util::d3d::D3dResource};
+use crate::{
+    gpu::{
+        debug::{GpuObjectKind, GpuObjectTracker},
+        GpuContext,
+    },
+    util::d3d::D3dResource,
+};
 
 pub struct VertexBuffer {
     pub buffer: ID3D11Buffer,
@@ -23,6 +29,7 @@ pub struct VertexBuffer {
     pub stride: u32,
     /// Optional SRV for the buffer. Created for buffers with stride 4
     pub srv: Option<ID3D11ShaderResourceView>,
+    _tracker: GpuObjectTracker,
 }
 
 impl VertexBuffer {
@@ -84,6 +91,7 @@ impl VertexBuffer {
             length: data.len() as u32 / stride,
             stride,
             srv,
+            _tracker: GpuObjectTracker::new(GpuObjectKind::VertexBuffer),
         })
     }
 
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use alkahest_data::{dxgi::DxgiFormat, geometry::EPrimitiveType};
+use crossbeam::atomic::AtomicCell;
+use parking_lot::Mutex;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::{
+        gbuffer::{CpuStagingRing, RenderTarget},
+        Renderer,
+    },
+};
+
+/// Eye adaptation. The average luminance of the shading result is reduced into a small buffer and read back a few frames later,
+/// after which the exposure eases towards the value that maps the average luminance to [`Self::KEY_VALUE`]
+pub struct AutoExposureRenderer {
+    luminance: RenderTarget,
+    staging: Mutex<CpuStagingRing>,
+    /// Exposure of the last frame
+    exposure: AtomicCell<f32>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+}
+
+impl AutoExposureRenderer {
+    /// Width and height of the luminance buffer, must match `postprocess/luminance.hlsl`
+    const SIZE: u32 = 64;
+    /// Middle grey, the brightness the average luminance is adjusted towards
+    const KEY_VALUE: f32 = 0.18;
+    const MIN_EXPOSURE: f32 = 0.05;
+    const MAX_EXPOSURE: f32 = 16.0;
+
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "postprocess/luminance.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/luminance.hlsl"))?;
+
+        Ok(Self {
+            luminance: RenderTarget::create(
+                (Self::SIZE, Self::SIZE),
+                DxgiFormat::R32_FLOAT,
+                gctx.clone(),
+                "auto_exposure_luminance",
+            )?,
+            staging: Mutex::new(CpuStagingRing::create(
+                (Self::SIZE, Self::SIZE),
+                DxgiFormat::R32_FLOAT,
+                gctx.clone(),
+                "auto_exposure_staging",
+                3,
+            )?),
+            exposure: AtomicCell::new(1.0),
+            shader_vs,
+            shader_ps,
+        })
+    }
+
+    /// Exposure of the last frame, without adapting it
+    pub fn current(&self) -> f32 {
+        self.exposure.load()
+    }
+
+    /// Eases the exposure towards the newest luminance readback, and returns it
+    pub fn update(&self, renderer: &Renderer) -> f32 {
+        let exposure = self.exposure.load();
+        let Some(average_log_luminance) = self.read_average_log_luminance() else {
+            return exposure;
+        };
+
+        let target = (Self::KEY_VALUE / average_log_luminance.exp2())
+            .clamp(Self::MIN_EXPOSURE, Self::MAX_EXPOSURE);
+
+        // Adapt in log space, so brightening and darkening by the same number of stops takes equally long
+        let t = 1.0
+            - (-renderer.delta_time as f32 * renderer.settings.auto_exposure_speed.max(0.0)).exp();
+        let exposure = (exposure.log2() + (target.log2() - exposure.log2()) * t).exp2();
+        self.exposure.store(exposure);
+        exposure
+    }
+
+    /// Reduces the shading result into the luminance buffer and queues it for readback
+    pub fn draw(&self, renderer: &Renderer) {
+        let data = renderer.data.lock();
+
+        self.luminance.bind();
+        unsafe {
+            let ctx = renderer.gpu.lock_context();
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.shading_result.view.clone())]));
+
+            renderer.gpu.set_blend_state(0);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            ctx.PSSetShaderResources(0, Some(&[None]));
+            ctx.OMSetRenderTargets(Some(&[]), None);
+            ctx.RSSetViewports(Some(std::slice::from_ref(
+                &data.gbuffers.shading_result.viewport(),
+            )));
+        }
+
+        self.luminance
+            .copy_to_staging(self.staging.lock().next_target());
+    }
+
+    fn read_average_log_luminance(&self) -> Option<f32> {
+        let size = Self::SIZE as usize;
+        self.staging
+            .lock()
+            .map_latest(|m| {
+                let mut total = 0.0;
+                let mut count = 0;
+                for y in 0..size {
+                    let row = unsafe {
+                        std::slice::from_raw_parts(
+                            (m.pData as *const u8).add(y * m.RowPitch as usize) as *const f32,
+                            size,
+                        )
+                    };
+
+                    for &value in row.iter().filter(|v| v.is_finite()) {
+                        total += value;
+                        count += 1;
+                    }
+                }
+
+                (count > 0).then(|| total / count as f32)
+            })
+            .flatten()
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use alkahest_data::{geometry::EPrimitiveType, tfx::TfxShaderStage};
+use glam::Vec2;
+use windows::Win32::Graphics::Direct3D11::{
+    ID3D11PixelShader, ID3D11ShaderResourceView, ID3D11VertexShader,
+};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    gpu_event, include_dxbc,
+    renderer::{gbuffer::RenderTarget, Renderer},
+};
+
+/// Glow around bright highlights. The shading result is thresholded and downsampled into the GBuffer bloom mip chain,
+/// which is then blurred back up and added to the shading result before it is tonemapped
+pub struct BloomRenderer {
+    scope: ConstantBuffer<ScopeAlkahestBloom>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_downsample_ps: ID3D11PixelShader,
+    shader_upsample_ps: ID3D11PixelShader,
+    shader_composite_ps: ID3D11PixelShader,
+}
+
+impl BloomRenderer {
+    /// Width of the soft transition around the threshold, relative to the threshold
+    const KNEE: f32 = 0.5;
+
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "postprocess/bloom_downsample.hlsl"))?;
+        let shader_downsample_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/bloom_downsample.hlsl"))?;
+        let shader_upsample_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/bloom_upsample.hlsl"))?;
+        let shader_composite_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/bloom_composite.hlsl"))?;
+
+        Ok(Self {
+            scope: ConstantBuffer::create(gctx.clone(), None)?,
+            shader_vs,
+            shader_downsample_ps,
+            shader_upsample_ps,
+            shader_composite_ps,
+        })
+    }
+
+    /// Builds the bloom mip chain from the shading result, and adds it back onto the shading result.
+    /// Runs before tonemapping, so the threshold and the composite both work on HDR color
+    pub fn draw(&self, renderer: &Renderer) {
+        let (bloom_view, shading_result) = {
+            let data = renderer.data.lock();
+            let gbuffers = &data.gbuffers;
+            let mips = &gbuffers.bloom_mips;
+
+            unsafe {
+                let ctx = renderer.gpu.lock_context();
+                ctx.RSSetState(None);
+                ctx.OMSetDepthStencilState(None, 0);
+                ctx.VSSetShader(&self.shader_vs, None);
+                ctx.PSSetSamplers(
+                    0,
+                    Some(&[Some(renderer.gpu.util_resources.linear_sampler.clone())]),
+                );
+            }
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+
+            {
+                gpu_event!(renderer.gpu, "bloom_downsample");
+                renderer.gpu.set_blend_state(0);
+                let mut source = &gbuffers.shading_result;
+                for (i, target) in mips.iter().enumerate() {
+                    self.draw_pass(renderer, source, target, &self.shader_downsample_ps, i == 0);
+                    source = target;
+                }
+            }
+
+            {
+                gpu_event!(renderer.gpu, "bloom_upsample");
+                // Premultiplied alpha blending, which is additive as the shader outputs an alpha of 0
+                renderer.gpu.set_blend_state(8);
+                for pair in mips.windows(2).rev() {
+                    self.draw_pass(
+                        renderer,
+                        &pair[1],
+                        &pair[0],
+                        &self.shader_upsample_ps,
+                        false,
+                    );
+                }
+            }
+
+            unsafe {
+                renderer
+                    .gpu
+                    .lock_context()
+                    .RSSetViewports(Some(std::slice::from_ref(
+                        &gbuffers.shading_result.viewport(),
+                    )));
+            }
+
+            // The composite can't read from the target it writes to
+            gbuffers
+                .shading_result
+                .copy_to(&gbuffers.shading_result_read);
+
+            (
+                mips[0].view.clone(),
+                (
+                    gbuffers.shading_result_read.view.clone(),
+                    gbuffers.shading_result.render_target.clone(),
+                ),
+            )
+        };
+
+        gpu_event!(renderer.gpu, "bloom_composite");
+        self.write_scope(renderer, Vec2::ZERO, false);
+        self.scope.bind(0, TfxShaderStage::Pixel);
+        let (source, target) = shading_result;
+        renderer.draw_fullscreen_effect(
+            &self.shader_vs,
+            &self.shader_composite_ps,
+            &source,
+            &target,
+            &[Some(bloom_view)],
+        );
+    }
+
+    fn draw_pass(
+        &self,
+        renderer: &Renderer,
+        source: &RenderTarget,
+        target: &RenderTarget,
+        shader_ps: &ID3D11PixelShader,
+        prefilter: bool,
+    ) {
+        let desc = source.get_desc();
+        self.write_scope(
+            renderer,
+            Vec2::new(1.0 / desc.Width as f32, 1.0 / desc.Height as f32),
+            prefilter,
+        );
+        self.scope.bind(0, TfxShaderStage::Pixel);
+
+        target.bind();
+        unsafe {
+            let ctx = renderer.gpu.lock_context();
+            ctx.PSSetShader(shader_ps, None);
+            ctx.PSSetShaderResources(0, Some(&[Some(source.view.clone())]));
+            ctx.Draw(3, 0);
+
+            // The target is the source of the next pass, so neither can stay bound
+            ctx.PSSetShaderResources(0, Some(&[None::<ID3D11ShaderResourceView>]));
+            ctx.OMSetRenderTargets(Some(&[]), None);
+        }
+    }
+
+    fn write_scope(&self, renderer: &Renderer, source_texel_size: Vec2, prefilter: bool) {
+        let threshold = renderer.settings.bloom_threshold.max(0.0);
+        self.scope
+            .write(&ScopeAlkahestBloom {
+                source_texel_size,
+                threshold,
+                knee: threshold * Self::KNEE,
+                intensity: renderer.settings.bloom_intensity,
+                prefilter: prefilter as u32,
+                _pad: Vec2::ZERO,
+            })
+            .unwrap();
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct ScopeAlkahestBloom {
+    source_texel_size: Vec2,
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+    prefilter: u32,
+    _pad: Vec2,
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use alkahest_data::tfx::TfxShaderStage;
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+};
+
+/// Shifts the red and blue channels outwards, increasing towards the edges of the screen
+pub struct ChromaticAberrationRenderer {
+    /// x: strength in pixels
+    scope: ConstantBuffer<Vec4>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+}
+
+impl ChromaticAberrationRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "postprocess/chromatic_aberration.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/chromatic_aberration.hlsl"))?;
+
+        Ok(Self {
+            scope: ConstantBuffer::create(gctx.clone(), None)?,
+            shader_vs,
+            shader_ps,
+        })
+    }
+
+    pub fn draw(&self, renderer: &Renderer) {
+        self.scope
+            .write(&Vec4::new(
+                renderer.settings.chromatic_aberration_strength,
+                0.0,
+                0.0,
+                0.0,
+            ))
+            .unwrap();
+
+        self.scope.bind(0, TfxShaderStage::Pixel);
+        renderer.draw_postprocess_effect(&self.shader_vs, &self.shader_ps, &[]);
+    }
+}
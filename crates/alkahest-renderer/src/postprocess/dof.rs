@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use alkahest_data::tfx::TfxShaderStage;
+use crossbeam::atomic::AtomicCell;
+use glam::{Vec2, Vec3};
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    gpu_event, include_dxbc,
+    renderer::Renderer,
+};
+
+/// Depth of field, implemented as a separable blur weighted by the circle of confusion of each pixel
+pub struct DofRenderer {
+    scope: ConstantBuffer<ScopeAlkahestDof>,
+    /// Focal distance of the last frame. Auto-focus eases this towards the gaze depth to avoid sudden jumps
+    focus: AtomicCell<f32>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+}
+
+impl DofRenderer {
+    /// How quickly auto-focus adjusts to a new gaze depth
+    const FOCUS_SPEED: f32 = 8.0;
+
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "postprocess/dof.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/dof.hlsl"))?;
+
+        Ok(Self {
+            scope: ConstantBuffer::create(gctx.clone(), None)?,
+            focus: AtomicCell::new(10.0),
+            shader_vs,
+            shader_ps,
+        })
+    }
+
+    /// Focal distance for the current frame, either from the settings or from the depth at the center of the screen
+    pub fn focal_distance(&self, renderer: &Renderer) -> f32 {
+        if !renderer.settings.dof_autofocus {
+            self.focus.store(renderer.settings.dof_focal_distance);
+            return renderer.settings.dof_focal_distance;
+        }
+
+        let data = renderer.data.lock();
+        let raw_depth = data.gbuffers.depth_buffer_read_center();
+        let focus = self.focus.load();
+        // Keep the current focus when looking at the sky
+        let Some(view) = data.externs.view.as_ref().filter(|_| raw_depth > 0.0) else {
+            return focus;
+        };
+
+        let (width, height) = renderer.render_resolution();
+        let target = view
+            .target_pixel_to_camera
+            .project_point3(Vec3::new(
+                (width / 2) as f32,
+                (height / 2) as f32,
+                raw_depth,
+            ))
+            .length();
+
+        let t = 1.0 - (-renderer.delta_time as f32 * Self::FOCUS_SPEED).exp();
+        let focus = focus + (target - focus) * t;
+        self.focus.store(focus);
+        focus
+    }
+
+    /// Blurs the shading result in two passes, going through `shading_result_read` in between.
+    /// Runs before tonemapping, so the blur is applied to HDR color
+    pub fn draw(&self, renderer: &Renderer) {
+        let focal_distance = self.focal_distance(renderer);
+        let (depth, shading_result, intermediate) = {
+            let data = renderer.data.lock();
+            data.gbuffers.depth.copy_depth();
+            let shading_result = &data.gbuffers.shading_result;
+            let intermediate = &data.gbuffers.shading_result_read;
+            (
+                data.gbuffers.depth.texture_copy_view.clone(),
+                (
+                    shading_result.view.clone(),
+                    shading_result.render_target.clone(),
+                ),
+                (
+                    intermediate.view.clone(),
+                    intermediate.render_target.clone(),
+                ),
+            )
+        };
+
+        for (name, direction, (source, _), (_, target)) in [
+            ("dof_horizontal", Vec2::X, &shading_result, &intermediate),
+            ("dof_vertical", Vec2::Y, &intermediate, &shading_result),
+        ] {
+            gpu_event!(renderer.gpu, name);
+            self.scope
+                .write(&ScopeAlkahestDof {
+                    focal_distance,
+                    focal_range: renderer.settings.dof_focal_range.max(0.01),
+                    blur_strength: renderer.settings.dof_blur_strength,
+                    direction,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            self.scope.bind(0, TfxShaderStage::Pixel);
+            renderer.draw_fullscreen_effect(
+                &self.shader_vs,
+                &self.shader_ps,
+                source,
+                target,
+                &[Some(depth.clone())],
+            );
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct ScopeAlkahestDof {
+    focal_distance: f32,
+    focal_range: f32,
+    /// Maximum blur radius, in pixels
+    blur_strength: f32,
+    _pad: f32,
+    direction: Vec2,
+    _pad2: Vec2,
+}
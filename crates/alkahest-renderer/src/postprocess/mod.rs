@@ -1 +1,8 @@
+pub mod auto_exposure;
+pub mod bloom;
+pub mod chromatic_aberration;
+pub mod dof;
+pub mod grain;
 pub mod ssao;
+pub mod tonemap;
+pub mod vignette;
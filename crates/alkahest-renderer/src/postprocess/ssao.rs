@@ -68,11 +68,17 @@ impl SsaoRenderer {
         };
         let externs = &mut renderer.data.lock().externs;
         {
-            let scope = self.scope.data();
-            if let Some(view) = &externs.view {
-                scope.target_pixel_to_world = view.target_pixel_to_world
-            } else {
+            let Some(view) = &externs.view else {
                 return;
+            };
+
+            // Only dirty the scope when the camera moved, so a still camera doesn't re-upload it every frame
+            if self
+                .scope
+                .data_ref()
+                .is_some_and(|s| s.target_pixel_to_world != view.target_pixel_to_world)
+            {
+                self.scope.data().target_pixel_to_world = view.target_pixel_to_world;
             }
         }
 
@@ -91,6 +97,22 @@ impl SsaoRenderer {
 
             self.noise_texture
                 .bind(&renderer.gpu, 2, TfxShaderStage::Pixel);
+
+            // Bound explicitly, s0 is otherwise left at whatever the last material technique used
+            let depth_sampler = if renderer.settings.ssao_linear_depth {
+                &renderer.gpu.util_resources.linear_sampler
+            } else {
+                &renderer.gpu.util_resources.point_sampler
+            };
+            unsafe {
+                renderer.gpu.lock_context().PSSetSamplers(
+                    0,
+                    Some(&[
+                        Some(renderer.gpu.util_resources.point_sampler.clone()),
+                        Some(depth_sampler.clone()),
+                    ]),
+                );
+            }
         } else {
             return;
         }
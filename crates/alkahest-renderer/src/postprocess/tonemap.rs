@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use alkahest_data::{geometry::EPrimitiveType, tfx::TfxShaderStage};
+use glam::Vec2;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::{Renderer, ToneMapping},
+};
+
+/// Converts the HDR shading result into the first postprocess buffer, compressing highlights instead of clipping them
+pub struct TonemapRenderer {
+    scope: ConstantBuffer<ScopeAlkahestTonemap>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+}
+
+impl TonemapRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "postprocess/tonemap.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/tonemap.hlsl"))?;
+
+        Ok(Self {
+            scope: ConstantBuffer::create(gctx.clone(), None)?,
+            shader_vs,
+            shader_ps,
+        })
+    }
+
+    /// Draws the shading result into the next postprocess buffer
+    pub fn draw(&self, renderer: &Renderer, exposure: f32) {
+        self.scope
+            .write(&ScopeAlkahestTonemap {
+                operator: renderer.settings.tone_mapping as u32,
+                exposure: exposure.max(0.0),
+                _pad: Vec2::ZERO,
+            })
+            .unwrap();
+
+        let data = renderer.data.lock();
+        let (_source, target) = data.gbuffers.get_postprocess_rt(true);
+        unsafe {
+            let ctx = renderer.gpu.lock_context();
+
+            self.scope.bind(0, TfxShaderStage::Pixel);
+            ctx.OMSetRenderTargets(Some(&[Some(target.render_target.clone())]), None);
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.shading_result.view.clone())]));
+
+            renderer.gpu.set_blend_state(0);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            // The shading result is written to again at the end of the postprocess pass
+            ctx.PSSetShaderResources(0, Some(&[None]));
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct ScopeAlkahestTonemap {
+    /// [`ToneMapping`] as an index, see `postprocess/tonemap.hlsl`
+    operator: u32,
+    exposure: f32,
+    _pad: Vec2,
+}
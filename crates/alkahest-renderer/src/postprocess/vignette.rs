@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use alkahest_data::tfx::TfxShaderStage;
+use glam::{Vec2, Vec3, Vec4};
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+};
+
+/// Darkens (or tints) the image towards the edges of the screen
+pub struct VignetteRenderer {
+    scope: ConstantBuffer<ScopeAlkahestVignette>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+}
+
+impl VignetteRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "postprocess/vignette.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "postprocess/vignette.hlsl"))?;
+
+        Ok(Self {
+            scope: ConstantBuffer::create(gctx.clone(), None)?,
+            shader_vs,
+            shader_ps,
+        })
+    }
+
+    pub fn draw(&self, renderer: &Renderer) {
+        self.scope
+            .write(&ScopeAlkahestVignette {
+                color: Vec3::from(renderer.settings.vignette_color).extend(1.0),
+                strength: renderer.settings.vignette_strength,
+                radius: renderer.settings.vignette_radius,
+                _pad: Vec2::ZERO,
+            })
+            .unwrap();
+
+        self.scope.bind(0, TfxShaderStage::Pixel);
+        renderer.draw_postprocess_effect(&self.shader_vs, &self.shader_ps, &[]);
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct ScopeAlkahestVignette {
+    color: Vec4,
+    strength: f32,
+    radius: f32,
+    _pad: Vec2,
+}
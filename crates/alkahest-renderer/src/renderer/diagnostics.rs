@@ -0,0 +1,204 @@
+use std::fmt::Display;
+
+use alkahest_data::{
+    occlusion::Aabb,
+    tfx::{TfxFeatureRenderer, TfxRenderStage},
+};
+use bevy_ecs::entity::Entity;
+use itertools::Itertools;
+
+use crate::{
+    ecs::{
+        render::{
+            decorators::DecoratorRenderer,
+            dynamic_geometry::DynamicModelComponent,
+            static_geometry::{StaticInstance, StaticInstances, StaticModelSingle},
+            terrain::TerrainPatches,
+        },
+        transform::Transform,
+        visibility::{ViewVisibility, Visibility},
+        Scene,
+    },
+    renderer::{lod, Renderer},
+};
+
+/// Stages that produce visible output in the main view
+const VISIBLE_STAGES: [TfxRenderStage; 4] = [
+    TfxRenderStage::GenerateGbuffer,
+    TfxRenderStage::Decals,
+    TfxRenderStage::DecalsAdditive,
+    TfxRenderStage::Transparents,
+];
+
+/// A condition that prevents (part of) an entity from being drawn
+pub enum DrawBlocker {
+    /// The entity is explicitly hidden
+    Hidden,
+    /// One of the entity's ancestors is hidden
+    InheritedHidden,
+    /// The entity is outside of the view frustum
+    Culled,
+    /// The feature renderer for this entity is disabled in the render settings
+    FeatureDisabled(TfxFeatureRenderer),
+    /// The selected mesh index doesn't exist in the model
+    MeshOutOfRange { index: usize, mesh_count: usize },
+    /// The selected mesh doesn't subscribe to any stage that is visible in the main view
+    NoVisibleStages,
+    /// All visible stages the selected mesh subscribes to are disabled in the render settings
+    StagesDisabled(Vec<TfxRenderStage>),
+    /// A lower detail LOD is being drawn, skipping the parts of other LODs
+    LodReduced { level: u8, skipped_parts: usize },
+    /// Only parts with the given identifier are drawn
+    IdentifierFiltered(u16),
+    /// Some of the techniques used by the entity failed to load
+    TechniquesMissing(usize),
+}
+
+impl Display for DrawBlocker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawBlocker::Hidden => write!(f, "Entity is hidden"),
+            DrawBlocker::InheritedHidden => write!(f, "A parent of this entity is hidden"),
+            DrawBlocker::Culled => write!(f, "Entity is outside of the view frustum"),
+            DrawBlocker::FeatureDisabled(feature) => {
+                write!(f, "Feature renderer {feature:?} is disabled")
+            }
+            DrawBlocker::MeshOutOfRange { index, mesh_count } => {
+                write!(
+                    f,
+                    "Selected mesh {index} is out of range, the model has {mesh_count} meshes"
+                )
+            }
+            DrawBlocker::NoVisibleStages => {
+                write!(
+                    f,
+                    "Selected mesh doesn't subscribe to any visible render stage"
+                )
+            }
+            DrawBlocker::StagesDisabled(stages) => write!(
+                f,
+                "Render stages are disabled: {}",
+                stages.iter().map(|s| format!("{s:?}")).join(", ")
+            ),
+            DrawBlocker::LodReduced {
+                level,
+                skipped_parts,
+            } => write!(
+                f,
+                "Drawing LOD {level}, {skipped_parts} parts of other LODs are skipped"
+            ),
+            DrawBlocker::IdentifierFiltered(identifier) => {
+                write!(f, "Only parts with identifier {identifier} are drawn")
+            }
+            DrawBlocker::TechniquesMissing(count) => {
+                write!(
+                    f,
+                    "{count} techniques failed to load, their parts are not drawn"
+                )
+            }
+        }
+    }
+}
+
+/// Returns the feature renderer that draws the given entity, if any
+pub fn entity_feature_renderer(scene: &Scene, entity: Entity) -> Option<TfxFeatureRenderer> {
+    let e = scene.get_entity(entity)?;
+    if let Some(dynamic) = e.get::<DynamicModelComponent>() {
+        Some(dynamic.model.feature_type)
+    } else if e.contains::<StaticModelSingle>()
+        || e.contains::<StaticInstances>()
+        || e.contains::<StaticInstance>()
+    {
+        Some(TfxFeatureRenderer::StaticObjects)
+    } else if e.contains::<TerrainPatches>() {
+        Some(TfxFeatureRenderer::TerrainPatch)
+    } else if e.contains::<DecoratorRenderer>() {
+        Some(TfxFeatureRenderer::SpeedtreeTrees)
+    } else {
+        None
+    }
+}
+
+impl Renderer {
+    /// Lists the conditions that currently prevent the given entity from being drawn in the main view.
+    /// Returns `None` if the entity isn't drawn by any of the feature renderers
+    pub fn draw_blockers(&self, scene: &Scene, entity: Entity) -> Option<Vec<DrawBlocker>> {
+        let feature = entity_feature_renderer(scene, entity)?;
+        let e = scene.get_entity(entity)?;
+        let dynamic = e.get::<DynamicModelComponent>();
+
+        let mut blockers = vec![];
+        match e.get::<Visibility>() {
+            Some(Visibility::Hidden) => blockers.push(DrawBlocker::Hidden),
+            Some(Visibility::InheritedHidden) => blockers.push(DrawBlocker::InheritedHidden),
+            _ => {
+                if e.get::<ViewVisibility>().is_some_and(|v| !v.is_visible(0)) {
+                    blockers.push(DrawBlocker::Culled);
+                }
+            }
+        }
+
+        if !self.should_render(None, Some(feature)) {
+            blockers.push(DrawBlocker::FeatureDisabled(feature));
+        }
+
+        let Some(dynamic) = dynamic else {
+            return Some(blockers);
+        };
+
+        let model = &dynamic.model;
+        let (Some(&stages), Some(mesh)) = (
+            model.mesh_stages.get(model.selected_mesh),
+            model.model.meshes.get(model.selected_mesh),
+        ) else {
+            blockers.push(DrawBlocker::MeshOutOfRange {
+                index: model.selected_mesh,
+                mesh_count: model.model.meshes.len(),
+            });
+            return Some(blockers);
+        };
+        let visible_stages = VISIBLE_STAGES
+            .into_iter()
+            .filter(|&s| stages.is_subscribed(s))
+            .collect_vec();
+        if visible_stages.is_empty() {
+            blockers.push(DrawBlocker::NoVisibleStages);
+        } else if visible_stages
+            .iter()
+            .all(|&s| !self.should_render(Some(s), None))
+        {
+            blockers.push(DrawBlocker::StagesDisabled(visible_stages));
+        }
+
+        let desired_level = self.lod_level_for_bounds(e.get::<Aabb>(), e.get::<Transform>());
+        if desired_level > 0 {
+            let parts = &mesh.parts;
+            let level = lod::select_lod_level(desired_level, parts.iter().map(|p| p.lod_category));
+            let skipped_parts = parts
+                .iter()
+                .filter(|p| p.lod_category.lod_level() != level)
+                .count();
+            if level > 0 && skipped_parts > 0 {
+                blockers.push(DrawBlocker::LodReduced {
+                    level,
+                    skipped_parts,
+                });
+            }
+        }
+
+        if dynamic.identifier != u16::MAX {
+            blockers.push(DrawBlocker::IdentifierFiltered(dynamic.identifier));
+        }
+
+        let missing_techniques = dynamic
+            .techniques()
+            .iter()
+            .filter(|t| !t.is_none() && self.get_technique_shared(t).is_none())
+            .count();
+        if missing_techniques > 0 {
+            blockers.push(DrawBlocker::TechniquesMissing(missing_techniques));
+        }
+
+        Some(blockers)
+    }
+}
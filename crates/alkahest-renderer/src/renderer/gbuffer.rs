@@ -1,16 +1,31 @@
-use std::{mem::size_of, sync::Arc};
+use std::{
+    mem::size_of,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use alkahest_data::dxgi::DxgiFormat;
 use anyhow::Context;
 use crossbeam::atomic::AtomicCell;
 use glam::Vec3;
 use windows::Win32::Graphics::{
-    Direct3D::{D3D11_SRV_DIMENSION_TEXTURE2D, D3D11_SRV_DIMENSION_TEXTURE2DARRAY},
+    Direct3D::{
+        D3D11_SRV_DIMENSION_TEXTURE2D, D3D11_SRV_DIMENSION_TEXTURE2DARRAY,
+        D3D11_SRV_DIMENSION_TEXTURE2DMS,
+    },
     Direct3D11::*,
-    Dxgi::Common::*,
+    Dxgi::{Common::*, DXGI_ERROR_WAS_STILL_DRAWING},
 };
 
-use crate::{camera::Camera, gpu::GpuContext, gpu_event, util::d3d::D3dResource};
+use crate::{
+    camera::Camera,
+    gpu::{
+        debug::{GpuObjectKind, GpuObjectTracker},
+        DepthMode, GpuContext,
+    },
+    gpu_event,
+    util::d3d::D3dResource,
+};
 
 pub struct GBuffer {
     pub rt0: RenderTarget,
@@ -26,7 +41,8 @@ pub struct GBuffer {
     pub shading_result: RenderTarget,
     pub shading_result_read: RenderTarget,
     pub depth: DepthState,
-    pub depth_staging: CpuStagingBuffer,
+    /// Depth readback, lagging a frame or two behind so reading it never has to wait for the GPU
+    pub depth_staging: CpuStagingRing,
 
     pub ssao_intermediate: RenderTarget,
     pub atmos_ss_far_lookup: RenderTarget,
@@ -38,7 +54,67 @@ pub struct GBuffer {
     pub postprocess_pong: RenderTarget,
     postprocess_pingpong: AtomicCell<PingPong>,
 
+    /// Bloom mip chain, starting at half the render resolution
+    pub bloom_mips: Vec<RenderTarget>,
+
+    /// Index of the map each pixel was drawn by, used by the map tint debug view
+    pub map_ids: RenderTarget,
+
+    /// Multisampled geometry targets, resolved into RT0-RT2 and the depth buffer after the G-buffer pass
+    pub msaa: Option<MsaaTargets>,
+
     current_size: (u32, u32),
+    /// Size requested by the last [`Self::resize`] and when it was requested, applied once resizing has settled
+    pending_size: Option<((u32, u32), Instant)>,
+}
+
+pub struct MsaaTargets {
+    pub rt0: RenderTarget,
+    pub rt1: RenderTarget,
+    pub rt2: RenderTarget,
+    pub depth: DepthState,
+}
+
+impl MsaaTargets {
+    fn create(
+        size: (u32, u32),
+        rt0_format: DxgiFormat,
+        sample_count: u32,
+        gctx: Arc<GpuContext>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            rt0: RenderTarget::create_multisampled(
+                size,
+                rt0_format,
+                sample_count,
+                gctx.clone(),
+                "RT0_MS",
+            )
+            .context("RT0_MS")?,
+            rt1: RenderTarget::create_multisampled(
+                size,
+                DxgiFormat::R10G10B10A2_UNORM,
+                sample_count,
+                gctx.clone(),
+                "RT1_MS",
+            )
+            .context("RT1_MS")?,
+            rt2: RenderTarget::create_multisampled(
+                size,
+                DxgiFormat::B8G8R8A8_UNORM,
+                sample_count,
+                gctx.clone(),
+                "RT2_MS",
+            )
+            .context("RT2_MS")?,
+            depth: DepthState::create_multisampled(gctx, size, "gbuffer_depth_ms", sample_count)
+                .context("Depth_MS")?,
+        })
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.depth.sample_count
+    }
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -62,10 +138,16 @@ impl GBuffer {
             size = (1, 1);
         }
 
+        match [8, 4, 2]
+            .into_iter()
+            .find(|&count| Self::msaa_supported(&gctx, count))
+        {
+            Some(count) => info!("G-buffer supports up to {count}x MSAA"),
+            None => info!("G-buffer does not support MSAA"),
+        }
+
         Ok(Self {
-            // rt0: RenderTarget::create(size, DxgiFormat::R11G11B10_FLOAT, gctx.clone(), "RT0")
-            //     .context("RT0")?,
-            rt0: RenderTarget::create(size, DxgiFormat::B8G8R8A8_UNORM_SRGB, gctx.clone(), "RT0")
+            rt0: RenderTarget::create(size, Self::rt0_format(false), gctx.clone(), "RT0")
                 .context("RT0")?,
             rt1: RenderTarget::create(size, DxgiFormat::R10G10B10A2_UNORM, gctx.clone(), "RT1")
                 .context("RT1")?,
@@ -118,11 +200,12 @@ impl GBuffer {
             )
             .context("Staging_Clone")?,
             depth: DepthState::create(gctx.clone(), size, "gbuffer_depth").context("Depth")?,
-            depth_staging: CpuStagingBuffer::create(
+            depth_staging: CpuStagingRing::create(
                 size,
                 DxgiFormat::R32_TYPELESS,
                 gctx.clone(),
                 "Depth_Buffer_Staging",
+                3,
             )
             .context("Depth_Buffer_Staging")?,
             ssao_intermediate: RenderTarget::create(
@@ -172,15 +255,140 @@ impl GBuffer {
             .context("postprocess_ping")?,
             postprocess_pingpong: AtomicCell::new(PingPong::Ping),
 
+            bloom_mips: (0..Self::BLOOM_MIP_COUNT)
+                .map(|i| {
+                    RenderTarget::create(
+                        Self::bloom_mip_size(size, i),
+                        DxgiFormat::R11G11B10_FLOAT,
+                        gctx.clone(),
+                        &format!("bloom_mip{i}"),
+                    )
+                    .with_context(|| format!("bloom_mip{i}"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+
+            map_ids: RenderTarget::create(size, DxgiFormat::R8_UINT, gctx.clone(), "map_ids")
+                .context("map_ids")?,
+
+            msaa: None,
+
             current_size: size,
+            pending_size: None,
         })
     }
 
-    pub fn resize(&mut self, mut new_size: (u32, u32)) -> anyhow::Result<()> {
+    pub const BLOOM_MIP_COUNT: usize = 6;
+
+    fn bloom_mip_size(size: (u32, u32), mip: usize) -> (u32, u32) {
+        ((size.0 >> (mip + 1)).max(1), (size.1 >> (mip + 1)).max(1))
+    }
+
+    /// Format of RT0 (albedo). The float format avoids banding in dark gradients
+    pub fn rt0_format(float: bool) -> DxgiFormat {
+        if float {
+            DxgiFormat::R11G11B10_FLOAT
+        } else {
+            DxgiFormat::B8G8R8A8_UNORM_SRGB
+        }
+    }
+
+    /// Recreates only RT0 with either the float or the 8-bit sRGB format
+    pub fn set_rt0_float(&mut self, float: bool) -> anyhow::Result<()> {
+        let format = Self::rt0_format(float);
+        if self.rt0.format != format {
+            self.rt0.format = format;
+            self.rt0.resize(self.current_size).context("RT0")?;
+            if let Some(msaa) = &mut self.msaa {
+                msaa.rt0.format = format;
+                msaa.rt0.resize(self.current_size).context("RT0_MS")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether every target drawn to by the G-buffer pass supports the given sample count
+    pub fn msaa_supported(gctx: &GpuContext, sample_count: u32) -> bool {
+        [
+            Self::rt0_format(false),
+            Self::rt0_format(true),
+            DxgiFormat::R10G10B10A2_UNORM,
+            DxgiFormat::B8G8R8A8_UNORM,
+            DxgiFormat::D32_FLOAT,
+        ]
+        .into_iter()
+        .all(|format| gctx.max_msaa_quality(format, sample_count).is_some())
+    }
+
+    /// Number of samples per pixel of the G-buffer pass, 1 when MSAA is disabled
+    pub fn msaa_samples(&self) -> u32 {
+        self.msaa.as_ref().map_or(1, MsaaTargets::sample_count)
+    }
+
+    /// (Re)creates the multisampled G-buffer targets, or removes them when `sample_count` is 1.
+    /// Falls back to no MSAA when the device doesn't support the requested sample count, [`Self::msaa_samples`] returns the count in use
+    pub fn set_msaa_samples(&mut self, sample_count: u32) -> anyhow::Result<()> {
+        if sample_count == self.msaa_samples() {
+            return Ok(());
+        }
+
+        let gctx = self.rt0.gctx.clone();
+        if sample_count > 1 && !Self::msaa_supported(&gctx, sample_count) {
+            warn!("{sample_count}x MSAA is not supported for the G-buffer, disabling MSAA");
+            self.msaa = None;
+            return Ok(());
+        }
+
+        self.msaa = if sample_count > 1 {
+            Some(MsaaTargets::create(
+                self.current_size,
+                self.rt0.format,
+                sample_count,
+                gctx,
+            )?)
+        } else {
+            None
+        };
+
+        Ok(())
+    }
+
+    /// How long the requested size has to stay the same before the targets are reallocated
+    const RESIZE_SETTLE_TIME: Duration = Duration::from_millis(100);
+
+    /// Requests the targets to be resized. Dragging a window edge sends a resize for every frame, so reallocation
+    /// is deferred until the size hasn't changed for [`Self::RESIZE_SETTLE_TIME`], see [`Self::apply_pending_resize`]
+    pub fn resize(&mut self, mut new_size: (u32, u32)) {
         if new_size.0 == 0 || new_size.1 == 0 {
             new_size = (1, 1);
         }
 
+        if new_size == self.current_size {
+            self.pending_size = None;
+            return;
+        }
+
+        if self.pending_size.map(|(size, _)| size) != Some(new_size) {
+            self.pending_size = Some((new_size, Instant::now()));
+        }
+    }
+
+    /// Reallocates the targets if a resize is pending and has settled. Returns the new size if the targets were resized
+    pub fn apply_pending_resize(&mut self) -> anyhow::Result<Option<(u32, u32)>> {
+        let Some((new_size, requested_at)) = self.pending_size else {
+            return Ok(None);
+        };
+
+        if requested_at.elapsed() < Self::RESIZE_SETTLE_TIME {
+            return Ok(None);
+        }
+
+        self.pending_size = None;
+        self.resize_now(new_size)?;
+        Ok(Some(new_size))
+    }
+
+    fn resize_now(&mut self, new_size: (u32, u32)) -> anyhow::Result<()> {
         self.rt0.resize(new_size).context("RT0")?;
         self.rt1.resize(new_size).context("RT1")?;
         self.rt1_read.resize(new_size).context("RT1_Clone")?;
@@ -212,14 +420,31 @@ impl GBuffer {
 
         self.postprocess_ping.resize(new_size)?;
         self.postprocess_pong.resize(new_size)?;
+        for (i, mip) in self.bloom_mips.iter_mut().enumerate() {
+            mip.resize(Self::bloom_mip_size(new_size, i))
+                .with_context(|| format!("bloom_mip{i}"))?;
+        }
+        self.map_ids.resize(new_size).context("map_ids")?;
+        if let Some(msaa) = &mut self.msaa {
+            msaa.rt0.resize(new_size).context("RT0_MS")?;
+            msaa.rt1.resize(new_size).context("RT1_MS")?;
+            msaa.rt2.resize(new_size).context("RT2_MS")?;
+            msaa.depth.resize(new_size).context("Depth_MS")?;
+        }
 
         self.current_size = new_size;
         Ok(())
     }
 
+    /// Reads the depth at the given pixel from the most recent depth readback that's available
     pub fn depth_buffer_read(&self, x: usize, y: usize) -> f32 {
+        let (width, height) = self.depth_staging.size();
+        if x >= width as usize || y >= height as usize {
+            return 0.0;
+        }
+
         self.depth_staging
-            .map(D3D11_MAP_READ, |m| unsafe {
+            .map_latest(|m| unsafe {
                 let data = m
                     .pData
                     .cast::<u8>()
@@ -230,6 +455,12 @@ impl GBuffer {
             })
             .unwrap_or(0.0)
     }
+
+    /// Size the targets are currently allocated at. Lags behind [`Self::resize`] until the pending resize is applied
+    pub fn size(&self) -> (u32, u32) {
+        self.current_size
+    }
+
     pub fn depth_buffer_read_center(&self) -> f32 {
         self.depth_buffer_read(
             (self.current_size.0 / 2) as usize,
@@ -246,6 +477,43 @@ impl GBuffer {
         (distance, pos)
     }
 
+    /// Position and normal of the surface at the center of the screen, or `None` when looking at the sky.
+    /// The normal is reconstructed from neighboring depth samples and faces the camera
+    pub fn depth_buffer_surface_center(&self, camera: &Camera) -> Option<(Vec3, Vec3)> {
+        // Sampling a few pixels apart smooths out depth precision noise
+        const OFFSET: u32 = 2;
+
+        let (width, height) = self.current_size;
+        let (x, y) = (width / 2, height / 2);
+        let position = |x: u32, y: u32| {
+            let raw_depth = self.depth_buffer_read(x as usize, y as usize);
+            // Reverse-Z, so anything at 0 is the sky
+            if raw_depth == 0.0 {
+                return None;
+            }
+
+            let ndc = Vec3::new(
+                (x as f32 + 0.5) / width as f32 * 2.0 - 1.0,
+                1.0 - (y as f32 + 0.5) / height as f32 * 2.0,
+                raw_depth,
+            );
+            Some(camera.projective_to_world.project_point3(ndc))
+        };
+
+        let center = position(x, y)?;
+        let right = position((x + OFFSET).min(width - 1), y)?;
+        let down = position(x, (y + OFFSET).min(height - 1))?;
+
+        let mut normal = (right - center).cross(down - center).normalize_or_zero();
+        if normal == Vec3::ZERO {
+            normal = -camera.forward();
+        } else if normal.dot(camera.position() - center) < 0.0 {
+            normal = -normal;
+        }
+
+        Some((center, normal))
+    }
+
     /// Returns (source, target). If `swap_after_use` is enabled, the order of the buffers will be reversed on the next call
     pub fn get_postprocess_rt(&self, swap_after_use: bool) -> (&RenderTarget, &RenderTarget) {
         let current_pingpong = self.postprocess_pingpong.load();
@@ -278,8 +546,11 @@ pub struct RenderTarget {
     pub view: ID3D11ShaderResourceView,
     pub format: DxgiFormat,
     pub name: String,
+    /// 1 for regular render targets. Multisampled targets have to be resolved before they can be read as a `Texture2D`
+    pub sample_count: u32,
 
     gctx: Arc<GpuContext>,
+    _tracker: GpuObjectTracker,
 }
 
 impl RenderTarget {
@@ -289,6 +560,17 @@ impl RenderTarget {
         gctx: Arc<GpuContext>,
         name: &str,
     ) -> anyhow::Result<Self> {
+        Self::create_multisampled(size, format, 1, gctx, name)
+    }
+
+    pub fn create_multisampled(
+        size: (u32, u32),
+        format: DxgiFormat,
+        sample_count: u32,
+        gctx: Arc<GpuContext>,
+        name: &str,
+    ) -> anyhow::Result<Self> {
+        let sample_count = sample_count.max(1);
         let size = if size.0 == 0 || size.1 == 0 {
             warn!("Zero size render target requested for {name}, using 1x1");
             (1, 1)
@@ -296,6 +578,16 @@ impl RenderTarget {
             size
         };
 
+        anyhow::ensure!(
+            gctx.check_format_support(
+                format,
+                D3D11_FORMAT_SUPPORT(
+                    D3D11_FORMAT_SUPPORT_TEXTURE2D.0 | D3D11_FORMAT_SUPPORT_RENDER_TARGET.0
+                )
+            ),
+            "Format {format:?} of render target {name} is not supported by this device"
+        );
+
         unsafe {
             let mut texture = None;
             gctx.device
@@ -307,7 +599,7 @@ impl RenderTarget {
                         ArraySize: 1,
                         Format: DXGI_FORMAT(format as i32),
                         SampleDesc: DXGI_SAMPLE_DESC {
-                            Count: 1,
+                            Count: sample_count,
                             Quality: 0,
                         },
                         Usage: D3D11_USAGE_DEFAULT,
@@ -332,16 +624,10 @@ impl RenderTarget {
             gctx.device
                 .CreateShaderResourceView(
                     &texture,
-                    Some(&D3D11_SHADER_RESOURCE_VIEW_DESC {
-                        Format: DXGI_FORMAT(format as i32),
-                        ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
-                        Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
-                            Texture2D: D3D11_TEX2D_SRV {
-                                MostDetailedMip: 0,
-                                MipLevels: 1,
-                            },
-                        },
-                    }),
+                    Some(&texture2d_srv_desc(
+                        DXGI_FORMAT(format as i32),
+                        sample_count,
+                    )),
                     Some(&mut view),
                 )
                 .context("Failed to create SRV")?;
@@ -355,7 +641,9 @@ impl RenderTarget {
                 view,
                 format,
                 name: name.to_string(),
+                sample_count,
                 gctx,
+                _tracker: GpuObjectTracker::new(GpuObjectKind::RenderTarget),
             })
         }
     }
@@ -383,10 +671,34 @@ impl RenderTarget {
     }
 
     pub fn resize(&mut self, new_size: (u32, u32)) -> anyhow::Result<()> {
-        *self = Self::create(new_size, self.format, self.gctx.clone(), &self.name)?;
+        *self = Self::create_multisampled(
+            new_size,
+            self.format,
+            self.sample_count,
+            self.gctx.clone(),
+            &self.name,
+        )?;
         Ok(())
     }
 
+    /// Resolves this multisampled render target into `dest`, which must have the same size and format
+    pub fn resolve_to(&self, dest: &RenderTarget) {
+        gpu_event!(
+            self.gctx,
+            "resolve",
+            format!("{}->{}", self.name, dest.name)
+        );
+        unsafe {
+            self.gctx.lock_context().ResolveSubresource(
+                &dest.texture,
+                0,
+                &self.texture,
+                0,
+                DXGI_FORMAT(self.format as i32),
+            )
+        }
+    }
+
     pub fn clear(&self, color: &[f32; 4]) {
         unsafe {
             self.gctx
@@ -432,7 +744,9 @@ pub struct CpuStagingBuffer {
     pub texture: ID3D11Texture2D,
     pub format: DxgiFormat,
     pub name: String,
+    pub size: (u32, u32),
     gctx: Arc<GpuContext>,
+    _tracker: GpuObjectTracker,
 }
 
 impl CpuStagingBuffer {
@@ -473,7 +787,9 @@ impl CpuStagingBuffer {
                 texture,
                 format,
                 name: name.to_string(),
+                size,
                 gctx,
+                _tracker: GpuObjectTracker::new(GpuObjectKind::StagingBuffer),
             })
         }
     }
@@ -503,6 +819,105 @@ impl CpuStagingBuffer {
             Ok(r)
         }
     }
+
+    /// Like [`Self::map`], but returns `None` instead of waiting when the GPU is still using the buffer
+    pub fn try_map<R>(
+        &self,
+        mode: D3D11_MAP,
+        f: impl FnOnce(D3D11_MAPPED_SUBRESOURCE) -> R,
+    ) -> anyhow::Result<Option<R>> {
+        unsafe {
+            let mut ptr = D3D11_MAPPED_SUBRESOURCE::default();
+            match self.gctx.lock_context().Map(
+                &self.texture,
+                0,
+                mode,
+                D3D11_MAP_FLAG_DO_NOT_WAIT.0 as u32,
+                Some(&mut ptr),
+            ) {
+                Ok(()) => {}
+                Err(e) if e.code() == DXGI_ERROR_WAS_STILL_DRAWING => return Ok(None),
+                Err(e) => return Err(e).context("Failed to map staging buffer"),
+            }
+
+            let r = f(ptr);
+
+            self.gctx.lock_context().Unmap(&self.texture, 0);
+
+            Ok(Some(r))
+        }
+    }
+}
+
+/// A ring of staging buffers for reading data back from the GPU without stalling.
+/// Every frame copies into the next buffer in the ring, and reads use the newest buffer the GPU has finished copying into
+pub struct CpuStagingRing {
+    buffers: Vec<CpuStagingBuffer>,
+    /// Index of the buffer that will be copied into next
+    next: usize,
+    /// Number of buffers that have been copied into since the ring was created or resized
+    filled: usize,
+}
+
+impl CpuStagingRing {
+    pub fn create(
+        size: (u32, u32),
+        format: DxgiFormat,
+        gctx: Arc<GpuContext>,
+        name: &str,
+        count: usize,
+    ) -> anyhow::Result<Self> {
+        let buffers = (0..count.max(1))
+            .map(|i| CpuStagingBuffer::create(size, format, gctx.clone(), &format!("{name}_{i}")))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            buffers,
+            next: 0,
+            filled: 0,
+        })
+    }
+
+    pub fn resize(&mut self, new_size: (u32, u32)) -> anyhow::Result<()> {
+        for buffer in &mut self.buffers {
+            buffer.resize(new_size)?;
+        }
+
+        self.next = 0;
+        self.filled = 0;
+        Ok(())
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.buffers[0].size
+    }
+
+    /// Returns the buffer to copy this frame's data into, and advances the ring
+    pub fn next_target(&mut self) -> &CpuStagingBuffer {
+        let index = self.next;
+        self.next = (self.next + 1) % self.buffers.len();
+        self.filled = (self.filled + 1).min(self.buffers.len());
+        &self.buffers[index]
+    }
+
+    /// Maps the newest buffer that the GPU is done with, without blocking.
+    /// Returns `None` if none of the copies have finished yet
+    pub fn map_latest<R>(&self, f: impl FnOnce(D3D11_MAPPED_SUBRESOURCE) -> R) -> Option<R> {
+        let mut f = Some(f);
+        for age in 1..=self.filled {
+            let index = (self.next + self.buffers.len() - age) % self.buffers.len();
+            match self.buffers[index].try_map(D3D11_MAP_READ, |m| f.take().unwrap()(m)) {
+                Ok(Some(r)) => return Some(r),
+                Ok(None) => continue,
+                Err(e) => {
+                    error!("Staging buffer readback failed: {e:?}");
+                    return None;
+                }
+            }
+        }
+
+        None
+    }
 }
 
 pub struct DepthState {
@@ -515,12 +930,46 @@ pub struct DepthState {
 
     pub texture_copy: ID3D11Texture2D,
     pub texture_copy_view: ID3D11ShaderResourceView,
+    pub mode: DepthMode,
+    /// 1 for regular depth buffers. The views of multisampled depth buffers are `Texture2DMS`
+    pub sample_count: u32,
     gctx: Arc<GpuContext>,
     name: String,
+    _tracker: GpuObjectTracker,
 }
 
 impl DepthState {
     pub fn create(gctx: Arc<GpuContext>, size: (u32, u32), name: &str) -> anyhow::Result<Self> {
+        Self::create_with_mode(gctx, size, name, DepthMode::Normal)
+    }
+
+    /// Creates a depth buffer whose depth stencil states compare according to `mode`
+    pub fn create_with_mode(
+        gctx: Arc<GpuContext>,
+        size: (u32, u32),
+        name: &str,
+        mode: DepthMode,
+    ) -> anyhow::Result<Self> {
+        Self::create_inner(gctx, size, name, mode, 1)
+    }
+
+    pub fn create_multisampled(
+        gctx: Arc<GpuContext>,
+        size: (u32, u32),
+        name: &str,
+        sample_count: u32,
+    ) -> anyhow::Result<Self> {
+        Self::create_inner(gctx, size, name, DepthMode::Normal, sample_count)
+    }
+
+    fn create_inner(
+        gctx: Arc<GpuContext>,
+        size: (u32, u32),
+        name: &str,
+        mode: DepthMode,
+        sample_count: u32,
+    ) -> anyhow::Result<Self> {
+        let sample_count = sample_count.max(1);
         let size = if size.0 == 0 || size.1 == 0 {
             warn!("Zero size depth state requested, using 1x1");
             (4, 4)
@@ -539,7 +988,7 @@ impl DepthState {
                         ArraySize: 1,
                         Format: DXGI_FORMAT_R32_TYPELESS,
                         SampleDesc: DXGI_SAMPLE_DESC {
-                            Count: 1,
+                            Count: sample_count,
                             Quality: 0,
                         },
                         Usage: D3D11_USAGE_DEFAULT,
@@ -563,7 +1012,7 @@ impl DepthState {
                     &D3D11_DEPTH_STENCIL_DESC {
                         DepthEnable: true.into(),
                         DepthWriteMask: D3D11_DEPTH_WRITE_MASK_ALL,
-                        DepthFunc: D3D11_COMPARISON_GREATER_EQUAL,
+                        DepthFunc: mode.comparison_func(),
                         StencilEnable: false.into(),
                         StencilReadMask: 0xff,
                         StencilWriteMask: 0xff,
@@ -593,7 +1042,7 @@ impl DepthState {
                     &D3D11_DEPTH_STENCIL_DESC {
                         DepthEnable: true.into(),
                         DepthWriteMask: D3D11_DEPTH_WRITE_MASK_ZERO,
-                        DepthFunc: D3D11_COMPARISON_GREATER_EQUAL,
+                        DepthFunc: mode.comparison_func(),
                         StencilEnable: false.into(),
                         StencilReadMask: 0xff,
                         StencilWriteMask: 0xff,
@@ -621,13 +1070,26 @@ impl DepthState {
             gctx.device
                 .CreateDepthStencilView(
                     &texture,
-                    Some(&D3D11_DEPTH_STENCIL_VIEW_DESC {
-                        Format: DXGI_FORMAT_D32_FLOAT,
-                        ViewDimension: D3D11_DSV_DIMENSION_TEXTURE2D,
-                        Flags: 0,
-                        Anonymous: D3D11_DEPTH_STENCIL_VIEW_DESC_0 {
-                            Texture2D: { D3D11_TEX2D_DSV { MipSlice: 0 } },
-                        },
+                    Some(&if sample_count > 1 {
+                        D3D11_DEPTH_STENCIL_VIEW_DESC {
+                            Format: DXGI_FORMAT_D32_FLOAT,
+                            ViewDimension: D3D11_DSV_DIMENSION_TEXTURE2DMS,
+                            Flags: 0,
+                            Anonymous: D3D11_DEPTH_STENCIL_VIEW_DESC_0 {
+                                Texture2DMS: D3D11_TEX2DMS_DSV {
+                                    UnusedField_NothingToDefine: 0,
+                                },
+                            },
+                        }
+                    } else {
+                        D3D11_DEPTH_STENCIL_VIEW_DESC {
+                            Format: DXGI_FORMAT_D32_FLOAT,
+                            ViewDimension: D3D11_DSV_DIMENSION_TEXTURE2D,
+                            Flags: 0,
+                            Anonymous: D3D11_DEPTH_STENCIL_VIEW_DESC_0 {
+                                Texture2D: { D3D11_TEX2D_DSV { MipSlice: 0 } },
+                            },
+                        }
                     }),
                     Some(&mut view),
                 )
@@ -640,16 +1102,7 @@ impl DepthState {
         unsafe {
             gctx.device.CreateShaderResourceView(
                 &texture,
-                Some(&D3D11_SHADER_RESOURCE_VIEW_DESC {
-                    Format: DXGI_FORMAT_R32_FLOAT,
-                    ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
-                    Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
-                        Texture2D: D3D11_TEX2D_SRV {
-                            MostDetailedMip: 0,
-                            MipLevels: 1,
-                        },
-                    },
-                }),
+                Some(&texture2d_srv_desc(DXGI_FORMAT_R32_FLOAT, sample_count)),
                 Some(&mut texture_view),
             )?
         };
@@ -667,7 +1120,7 @@ impl DepthState {
                         ArraySize: 1,
                         Format: DXGI_FORMAT_R32_TYPELESS,
                         SampleDesc: DXGI_SAMPLE_DESC {
-                            Count: 1,
+                            Count: sample_count,
                             Quality: 0,
                         },
                         Usage: D3D11_USAGE_DEFAULT,
@@ -686,16 +1139,7 @@ impl DepthState {
         unsafe {
             gctx.device.CreateShaderResourceView(
                 &texture_copy,
-                Some(&D3D11_SHADER_RESOURCE_VIEW_DESC {
-                    Format: DXGI_FORMAT_R32_FLOAT,
-                    ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
-                    Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
-                        Texture2D: D3D11_TEX2D_SRV {
-                            MostDetailedMip: 0,
-                            MipLevels: 1,
-                        },
-                    },
-                }),
+                Some(&texture2d_srv_desc(DXGI_FORMAT_R32_FLOAT, sample_count)),
                 Some(&mut texture_copy_view),
             )?
         };
@@ -709,8 +1153,11 @@ impl DepthState {
             texture_view,
             texture_copy,
             texture_copy_view,
+            mode,
+            sample_count,
             gctx,
             name: name.to_string(),
+            _tracker: GpuObjectTracker::new(GpuObjectKind::DepthBuffer),
         })
     }
 
@@ -732,7 +1179,13 @@ impl DepthState {
     }
 
     pub fn resize(&mut self, new_size: (u32, u32)) -> anyhow::Result<()> {
-        *self = Self::create(self.gctx.clone(), new_size, &self.name)?;
+        *self = Self::create_inner(
+            self.gctx.clone(),
+            new_size,
+            &self.name,
+            self.mode,
+            self.sample_count,
+        )?;
         Ok(())
     }
 
@@ -746,6 +1199,11 @@ impl DepthState {
             )
         }
     }
+
+    /// Clears depth to the far plane of the mode this buffer was created with, and stencil to 0
+    pub fn clear_default(&self) {
+        self.clear(self.mode.clear_value(), 0);
+    }
 }
 
 #[derive(Debug)]
@@ -755,6 +1213,7 @@ pub struct ShadowDepthMap {
     pub views: Vec<ID3D11DepthStencilView>,
     pub texture_view: ID3D11ShaderResourceView,
     pub layers: usize,
+    _tracker: GpuObjectTracker,
 }
 
 impl ShadowDepthMap {
@@ -873,6 +1332,7 @@ impl ShadowDepthMap {
             views,
             texture_view,
             layers,
+            _tracker: GpuObjectTracker::new(GpuObjectKind::DepthBuffer),
         })
     }
 
@@ -881,3 +1341,29 @@ impl ShadowDepthMap {
         Ok(())
     }
 }
+
+/// Shader resource view description for a single-mip 2D texture, using the multisampled dimension when `sample_count` > 1
+fn texture2d_srv_desc(format: DXGI_FORMAT, sample_count: u32) -> D3D11_SHADER_RESOURCE_VIEW_DESC {
+    if sample_count > 1 {
+        D3D11_SHADER_RESOURCE_VIEW_DESC {
+            Format: format,
+            ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2DMS,
+            Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture2DMS: D3D11_TEX2DMS_SRV {
+                    UnusedField_NothingToDefine: 0,
+                },
+            },
+        }
+    } else {
+        D3D11_SHADER_RESOURCE_VIEW_DESC {
+            Format: format,
+            ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
+            Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture2D: D3D11_TEX2D_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: 1,
+                },
+            },
+        }
+    }
+}
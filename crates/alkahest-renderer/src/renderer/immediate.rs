@@ -5,7 +5,7 @@ use genmesh::{
     generators::{IndexedPolygon, SharedVertex},
     Triangulate,
 };
-use glam::{Mat4, Vec3, Vec4};
+use glam::{Mat4, Quat, Vec3, Vec4};
 use parking_lot::Mutex;
 
 use crate::{
@@ -56,6 +56,10 @@ pub struct ImmediateRenderer {
     vb_sphere: VertexBuffer,
     ib_sphere: IndexBuffer,
 
+    /// Cone with a radius of 1, from the base at z=-1 to the tip at z=1
+    vb_cone: VertexBuffer,
+    ib_cone: IndexBuffer,
+
     shader_simple: ShaderProgram,
     shader_line: ShaderProgram,
 
@@ -89,6 +93,27 @@ impl ImmediateRenderer {
             std::mem::size_of::<[f32; 4]>() as u32,
         )?;
 
+        let mesh_cone = genmesh::generators::Cone::new(16);
+        let vertices: Vec<[f32; 4]> = mesh_cone
+            .shared_vertex_iter()
+            .map(|v| {
+                let v = <[f32; 3]>::from(v.pos);
+                [v[0], v[1], v[2], 1.0]
+            })
+            .collect();
+
+        let mut indices = vec![];
+        for i in mesh_cone.indexed_polygon_iter().triangulate() {
+            indices.extend_from_slice(&[i.x as u16, i.y as u16, i.z as u16]);
+        }
+
+        let ib_cone = IndexBuffer::load_u16(&gpu, &indices)?;
+        let vb_cone = VertexBuffer::load_data(
+            &gpu.device,
+            bytemuck::cast_slice(&vertices),
+            std::mem::size_of::<[f32; 4]>() as u32,
+        )?;
+
         let mesh = genmesh::generators::Cube::new();
         let vertices: Vec<[f32; 4]> = mesh
             .shared_vertex_iter()
@@ -125,6 +150,8 @@ impl ImmediateRenderer {
             ib_cube_outline,
             vb_sphere,
             ib_sphere,
+            vb_cone,
+            ib_cone,
             shader_simple: ShaderProgram::load(
                 &gpu,
                 include_dxbc!(vs "debug/simple.hlsl"),
@@ -148,6 +175,46 @@ impl ImmediateRenderer {
         self.line_2color(start, end, color, color, width);
     }
 
+    /// Line with a cone-shaped arrowhead at `end`
+    pub fn arrow<C: Into<Color> + Copy>(&self, start: Vec3, end: Vec3, color: C, width: f32) {
+        let direction = (end - start).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return;
+        }
+
+        // The head grows with the arrow, but is capped so long arrows don't end in a huge cone
+        let length = start.distance(end);
+        let head_length = (length * 0.2).min(1.0);
+        let head_radius = head_length * 0.35;
+        let head_base = end - direction * head_length;
+
+        self.line(start, head_base, color, width);
+        self.cone(
+            Mat4::from_scale_rotation_translation(
+                Vec3::new(head_radius, head_radius, head_length * 0.5),
+                Quat::from_rotation_arc(Vec3::Z, direction),
+                (head_base + end) * 0.5,
+            ),
+            color,
+        );
+    }
+
+    /// Arrow of the given length pointing from `origin` along `direction`, eg. for normals or velocities
+    pub fn arrow_direction<C: Into<Color> + Copy>(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        length: f32,
+        color: C,
+    ) {
+        self.arrow(
+            origin,
+            origin + direction.normalize_or_zero() * length,
+            color,
+            2.0,
+        );
+    }
+
     pub fn cross<C: Into<Color> + Copy>(&self, point: Vec3, length: f32, color: C) {
         let color = color.into();
         let half_length = length / 2.0;
@@ -311,6 +378,56 @@ impl ImmediateRenderer {
         }
     }
 
+    /// Solid cone with a radius of 1, from the base at z=-1 to the tip at z=1 in local space
+    pub fn cone<C: Into<Color>>(&self, transform: impl Into<Mat4>, color: C) {
+        gpu_event!(self.gpu, "imm_cone");
+        let color = color.into();
+        self.shader_simple.bind(&self.gpu);
+
+        self.vb_cone.bind_single(&self.gpu, 0);
+        self.ib_cone.bind(&self.gpu);
+
+        self.cb_debug_shape
+            .write(&ScopeAlkDebugShape {
+                local_to_world: transform.into(),
+                color: color.to_vec4(),
+            })
+            .unwrap();
+
+        self.cb_debug_shape.bind(0, TfxShaderStage::Vertex);
+        self.cb_debug_shape.bind(0, TfxShaderStage::Pixel);
+
+        self.gpu.set_input_layout(0);
+        self.gpu.set_input_topology(EPrimitiveType::Triangles);
+        if color.is_opaque() {
+            self.gpu.set_blend_state(0);
+        } else {
+            self.gpu.set_blend_state(12);
+        }
+
+        unsafe {
+            self.gpu
+                .lock_context()
+                .DrawIndexed(self.ib_cone.length as u32, 0, 0);
+        }
+    }
+
+    /// Outlines a frustum from its corners.
+    /// Bit 0 of the corner index selects the X side, bit 1 the Y side and bit 2 the plane (near or far),
+    /// matching [`ShadowMapRenderer::frustum_corners`](crate::ecs::render::light::ShadowMapRenderer::frustum_corners)
+    pub fn frustum<C: Into<Color> + Copy>(&self, corners: &[Vec3; 8], color: C, width: f32) {
+        for (a, b) in FRUSTUM_EDGES {
+            self.line(corners[a], corners[b], color, width);
+        }
+    }
+
+    /// Same as [`Self::frustum`], but draws nothing unless exactly 8 corners are given
+    pub fn frustum_slice<C: Into<Color> + Copy>(&self, corners: &[Vec3], color: C, width: f32) {
+        if let Ok(corners) = <&[Vec3; 8]>::try_from(corners) {
+            self.frustum(corners, color, width);
+        }
+    }
+
     pub fn cube_outline_aabb<C: Into<Color>>(&self, aabb: &Aabb, color: C) {
         let center = aabb.center();
         let extents = aabb.extents();
@@ -391,6 +508,22 @@ impl ImmediateRenderer {
     }
 }
 
+/// Pairs of corner indices that form the edges of a frustum, see [`ImmediateRenderer::frustum`]
+const FRUSTUM_EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (1, 3),
+    (3, 2),
+    (2, 0),
+    (4, 5),
+    (5, 7),
+    (7, 6),
+    (6, 4),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
 pub enum LabelAlign {
     Min,
     Center,
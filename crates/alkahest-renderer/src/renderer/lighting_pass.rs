@@ -9,7 +9,7 @@ use crate::{
 };
 
 impl Renderer {
-    pub fn draw_lighting_pass(&self, scene: &mut Scene) {
+    pub fn draw_lighting_pass(&self, scenes: &mut [&mut Scene]) {
         gpu_profile_event!(self.gpu, "lighting_pass");
 
         unsafe {
@@ -73,7 +73,9 @@ impl Renderer {
 
                 {
                     gpu_profile_event!(self.gpu, "deferred_lights");
-                    draw_light_system(self, scene)
+                    for scene in scenes.iter_mut() {
+                        draw_light_system(self, scene);
+                    }
                 }
 
                 if self.settings.feature_cubemaps {
@@ -89,7 +91,9 @@ impl Renderer {
                     }
 
                     gpu_event!(self.gpu, "cubemaps");
-                    draw_cubemap_system(self, scene);
+                    for scene in scenes.iter_mut() {
+                        draw_cubemap_system(self, scene);
+                    }
                 }
             }
         }
@@ -0,0 +1,72 @@
+use alkahest_data::{geometry::ELodCategory, occlusion::Aabb};
+
+use crate::{
+    ecs::{culling::Sphere, transform::Transform},
+    renderer::Renderer,
+    util::Hocus,
+};
+
+/// Distances (in meters) at which LOD 1, 2 and 3 are selected, before applying the LOD bias
+const LOD_DISTANCES: [f32; 3] = [40.0, 120.0, 300.0];
+
+impl Renderer {
+    /// Runs `f` with the desired LOD level set based on the distance from the camera to the given bounds
+    pub fn with_lod(&self, bounds: Option<&Aabb>, transform: Option<&Transform>, f: impl FnOnce()) {
+        self.with_lod_level(self.lod_level_for_bounds(bounds, transform), f);
+    }
+
+    /// Runs `f` with the given desired LOD level
+    pub fn with_lod_level(&self, level: u8, f: impl FnOnce()) {
+        self.pocus().active_lod_level = level;
+        f();
+        self.pocus().active_lod_level = 0;
+    }
+
+    pub fn lod_level_for_bounds(&self, bounds: Option<&Aabb>, transform: Option<&Transform>) -> u8 {
+        let Some(bounds) = bounds else {
+            return 0;
+        };
+
+        if self.settings.force_lod0 {
+            return 0;
+        }
+
+        let mut sphere = Sphere {
+            center: bounds.center(),
+            radius: bounds.radius(),
+        };
+
+        if let Some(transform) = transform {
+            sphere = sphere.transform(transform.local_to_world());
+        }
+
+        let distance = (sphere.center.distance(self.lod_origin) - sphere.radius).max(0.0);
+        let bias = self.settings.lod_bias.max(0.01);
+        LOD_DISTANCES
+            .iter()
+            .filter(|&&d| distance > d * bias)
+            .count() as u8
+    }
+
+    /// Picks the LOD level to draw from the levels available in a mesh.
+    /// This is the least detailed level that is not below the desired level, so meshes without the desired level don't disappear
+    pub fn select_lod_level(&self, available: impl IntoIterator<Item = ELodCategory>) -> u8 {
+        select_lod_level(self.active_lod_level, available)
+    }
+}
+
+pub(super) fn select_lod_level(
+    desired: u8,
+    available: impl IntoIterator<Item = ELodCategory>,
+) -> u8 {
+    let mut lowest = None;
+    let mut selected = None;
+    for level in available.into_iter().map(|l| l.lod_level()) {
+        lowest = Some(lowest.map_or(level, |l: u8| l.min(level)));
+        if level <= desired {
+            selected = Some(selected.map_or(level, |s: u8| s.max(level)));
+        }
+    }
+
+    selected.or(lowest).unwrap_or(0)
+}
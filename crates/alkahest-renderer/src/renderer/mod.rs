@@ -1,13 +1,17 @@
 mod cubemaps;
+mod diagnostics;
+pub use diagnostics::{entity_feature_renderer, DrawBlocker};
 pub mod gbuffer;
 mod immediate;
 use crossbeam::atomic::AtomicCell;
-use glam::{Mat4, Quat};
+use glam::{Mat4, Quat, Vec3};
 pub use immediate::{ImmediateLabel, LabelAlign};
 mod lighting_pass;
+mod lod;
 mod opaque_pass;
 mod pickbuffer;
 mod postprocess;
+mod screenshot;
 pub mod shader;
 mod shadows;
 pub use shadows::{ShadowPcfSamples, ShadowQuality};
@@ -34,12 +38,16 @@ use bevy_ecs::system::{Resource, RunSystemOnce};
 use bitflags::bitflags;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use strum::{EnumCount, EnumIter};
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
 use windows::Win32::Graphics::Direct3D11::D3D11_VIEWPORT;
 
 use crate::{
+    camera::{Camera, CameraProjection},
     ecs::{
-        render::{havok::draw_debugshapes_system, light::ShadowGenerationMode},
+        render::{
+            dynamic_geometry::draw_model_placeholders_system, havok::draw_debugshapes_system,
+            light::ShadowGenerationMode,
+        },
         resources::SelectedEntity,
         tags::NodeFilterSet,
         transform::Transform,
@@ -51,13 +59,24 @@ use crate::{
     gpu_event, gpu_profile_event,
     handle::Handle,
     loaders::AssetManager,
-    postprocess::ssao::SsaoRenderer,
+    postprocess::{
+        auto_exposure::AutoExposureRenderer, bloom::BloomRenderer,
+        chromatic_aberration::ChromaticAberrationRenderer, dof::DofRenderer, grain::GrainRenderer,
+        ssao::SsaoRenderer, tonemap::TonemapRenderer, vignette::VignetteRenderer,
+    },
     renderer::{
-        cubemaps::CubemapRenderer, gbuffer::GBuffer, immediate::ImmediateRenderer,
+        cubemaps::CubemapRenderer,
+        gbuffer::{GBuffer, RenderTarget},
+        immediate::ImmediateRenderer,
         pickbuffer::Pickbuffer,
     },
     resources::AppResources,
-    shader::matcap::MatcapRenderer,
+    shader::{
+        background::BackgroundRenderer, feature_color::FeatureColorRenderer, grid::GridRenderer,
+        icon_billboard::IconBillboardRenderer, map_tint::MapTintRenderer, matcap::MatcapRenderer,
+        msaa_resolve::MsaaResolveRenderer, near_fade::NearFadeRenderer,
+        shadow_coverage::ShadowCoverageRenderer, shadow_split::ShadowSplitRenderer,
+    },
     tfx::{
         externs::{self, ExternStorage, Frame},
         globals::RenderGlobals,
@@ -95,17 +114,43 @@ pub struct Renderer {
     pub settings: RendererSettings,
 
     pub ssao: SsaoRenderer,
+    auto_exposure: AutoExposureRenderer,
+    tonemap: TonemapRenderer,
+    bloom: BloomRenderer,
+    dof: DofRenderer,
+    chromatic_aberration: ChromaticAberrationRenderer,
+    vignette: VignetteRenderer,
+    grain: GrainRenderer,
     matcap: MatcapRenderer,
+    background: BackgroundRenderer,
+    near_fade: NearFadeRenderer,
+    msaa_resolve: MsaaResolveRenderer,
+    shadow_coverage: ShadowCoverageRenderer,
+    shadow_split: ShadowSplitRenderer,
+    grid: GridRenderer,
+    map_tint: MapTintRenderer,
+    feature_color: FeatureColorRenderer,
+    pub icon_billboards: IconBillboardRenderer,
     pub immediate: ImmediateRenderer,
     cubemap_renderer: CubemapRenderer,
     pub pickbuffer: Pickbuffer,
 
     pub time: AtomicCell<Time>,
+    /// Size of the output window. The GBuffer is scaled from this by [`RendererSettings::render_scale`]
+    window_size: AtomicCell<(u32, u32)>,
+    /// Size the GBuffer is currently allocated at, which lags behind the window while it's being resized
+    render_resolution: AtomicCell<(u32, u32)>,
     last_frame: Instant,
     pub delta_time: f64,
     pub frame_index: AtomicUsize,
 
     pub active_view: usize,
+    /// Whether the active view is a camera view, as opposed to a shadow map
+    pub is_camera_view: bool,
+    /// Desired LOD level for the object that's currently being drawn, see [`Self::with_lod`]
+    pub active_lod_level: u8,
+    /// Position LOD distances are measured from, usually the main camera
+    pub lod_origin: Vec3,
     // Hacky way to obtain these filters for now
     pub lastfilters: NodeFilterSet,
     pub active_shadow_generation_mode: ShadowGenerationMode,
@@ -118,26 +163,59 @@ pub struct RendererData {
 }
 
 impl Renderer {
+    /// `asset_load_threads` should match the configured [`RendererSettings::asset_load_threads`], so the
+    /// asset loader doesn't have to be restarted when the settings are applied
     pub fn create(
         gpu: Arc<GpuContext>,
         window_size: (u32, u32),
+        asset_load_threads: usize,
         disable_asset_loading: bool,
     ) -> anyhow::Result<RendererShared> {
         let render_globals =
             RenderGlobals::load(gpu.clone()).expect("Failed to load render globals");
+        let gbuffers = GBuffer::create(window_size, gpu.clone())?;
+        let render_resolution = gbuffers.size();
 
         Ok(RendererShared(Arc::new(Self {
             data: Mutex::new(RendererData {
                 asset_manager: if disable_asset_loading {
                     AssetManager::new_disabled(gpu.clone())
                 } else {
-                    AssetManager::new(gpu.clone())
+                    AssetManager::new(gpu.clone(), asset_load_threads)
                 },
-                gbuffers: GBuffer::create(window_size, gpu.clone())?,
+                gbuffers,
                 externs: ExternStorage::default(),
             }),
             ssao: SsaoRenderer::new(gpu.clone()).context("failed to create SsaoRenderer")?,
+            auto_exposure: AutoExposureRenderer::new(gpu.clone())
+                .context("failed to create AutoExposureRenderer")?,
+            tonemap: TonemapRenderer::new(gpu.clone())
+                .context("failed to create TonemapRenderer")?,
+            bloom: BloomRenderer::new(gpu.clone()).context("failed to create BloomRenderer")?,
+            dof: DofRenderer::new(gpu.clone()).context("failed to create DofRenderer")?,
+            chromatic_aberration: ChromaticAberrationRenderer::new(gpu.clone())
+                .context("failed to create ChromaticAberrationRenderer")?,
+            vignette: VignetteRenderer::new(gpu.clone())
+                .context("failed to create VignetteRenderer")?,
+            grain: GrainRenderer::new(gpu.clone()).context("failed to create GrainRenderer")?,
             matcap: MatcapRenderer::new(gpu.clone()).context("failed to create MatcapRenderer")?,
+            background: BackgroundRenderer::new(gpu.clone())
+                .context("failed to create BackgroundRenderer")?,
+            near_fade: NearFadeRenderer::new(gpu.clone())
+                .context("failed to create NearFadeRenderer")?,
+            msaa_resolve: MsaaResolveRenderer::new(gpu.clone())
+                .context("failed to create MsaaResolveRenderer")?,
+            shadow_coverage: ShadowCoverageRenderer::new(gpu.clone())
+                .context("failed to create ShadowCoverageRenderer")?,
+            shadow_split: ShadowSplitRenderer::new(gpu.clone())
+                .context("failed to create ShadowSplitRenderer")?,
+            grid: GridRenderer::new(gpu.clone()).context("failed to create GridRenderer")?,
+            map_tint: MapTintRenderer::new(gpu.clone())
+                .context("failed to create MapTintRenderer")?,
+            feature_color: FeatureColorRenderer::new(gpu.clone())
+                .context("failed to create FeatureColorRenderer")?,
+            icon_billboards: IconBillboardRenderer::new(gpu.clone())
+                .context("failed to create IconBillboardRenderer")?,
             immediate: ImmediateRenderer::new(gpu.clone())
                 .context("failed to create ImmediateRenderer")?,
             cubemap_renderer: CubemapRenderer::new(gpu.clone())
@@ -146,14 +224,22 @@ impl Renderer {
                 .context("failed to create Pickbuffer")?,
             gpu,
             render_globals,
-            settings: RendererSettings::default(),
+            settings: RendererSettings {
+                asset_load_threads,
+                ..Default::default()
+            },
             time: AtomicCell::new(Time::now()),
+            window_size: AtomicCell::new(window_size),
+            render_resolution: AtomicCell::new(render_resolution),
             last_frame: Instant::now(),
             delta_time: 0.0,
             frame_index: AtomicUsize::default(),
             active_shadow_generation_mode: ShadowGenerationMode::StationaryOnly,
             lastfilters: NodeFilterSet::default(),
             active_view: 0,
+            is_camera_view: false,
+            active_lod_level: 0,
+            lod_origin: Vec3::ZERO,
         })))
     }
 
@@ -162,43 +248,98 @@ impl Renderer {
         data.asset_manager.techniques.get_shared(handle)
     }
 
-    pub fn render_world(&self, view: &impl View, scene: &mut Scene, resources: &AppResources) {
+    /// Renders the given scenes together. The first scene is the primary one, which provides the atmosphere,
+    /// selection and overlays, the others only contribute their geometry and lights
+    pub fn render_world(
+        &self,
+        camera: &Camera,
+        scenes: &mut [&mut Scene],
+        resources: &AppResources,
+    ) {
+        self.pocus().delta_time = self.last_frame.elapsed().as_secs_f64();
+        self.pocus().last_frame = Instant::now();
+
+        self.apply_pending_resize();
+        self.render_world_internal(camera, scenes, resources, None);
+
+        {
+            let mut data = self.data.lock();
+            let gbuffers = &mut data.gbuffers;
+            gbuffers
+                .depth
+                .copy_to_staging(gbuffers.depth_staging.next_target());
+        }
+
+        self.frame_index.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Renders the world from a secondary view (eg. the minimap) into the given render target.
+    /// Does not advance frame timing or handle selection requests, so this should be called before the main [`Self::render_world`].
+    pub fn render_world_to(
+        &self,
+        camera: &Camera,
+        scenes: &mut [&mut Scene],
+        resources: &AppResources,
+        target: &RenderTarget,
+    ) {
+        self.render_world_internal(camera, scenes, resources, Some(target));
+    }
+
+    fn render_world_internal(
+        &self,
+        camera: &Camera,
+        scenes: &mut [&mut Scene],
+        resources: &AppResources,
+        target: Option<&RenderTarget>,
+    ) {
+        if scenes.is_empty() {
+            return;
+        }
+
         self.pocus().lastfilters = resources.get::<NodeFilterSet>().clone();
 
         // Make sure immediate labels have been drained completely
         let _ = self.immediate.drain_labels();
 
-        self.begin_world_frame(scene);
-
-        let frustum = view.frustum();
-        scene.run_system_once_with(frustum, calculate_view_visibility_system);
+        self.begin_world_frame(scenes[0]);
 
-        self.update_shadow_maps(scene);
+        let frustum = camera.frustum();
+        for scene in scenes.iter_mut() {
+            scene.run_system_once_with(frustum, calculate_view_visibility_system);
+            // Shadow maps don't depend on the view, secondary views reuse the ones from the main view
+            if target.is_none() {
+                self.update_shadow_maps(scene);
+            }
+        }
 
         {
             gpu_profile_event!(self.gpu, "view_0");
-            self.bind_view(view, 0);
+            self.bind_view(camera, 0, true);
 
-            self.draw_atmosphere(scene);
+            self.draw_atmosphere(scenes[0]);
             // if self.render_settings.depth_prepass {
             //     self.draw_depth_prepass(scene);
             // }
-            self.draw_opaque_pass(scene);
-            self.draw_lighting_pass(scene);
-            self.draw_shading_pass(scene);
-            self.draw_transparents_pass(scene);
+            self.draw_opaque_pass(scenes);
+            self.draw_lighting_pass(scenes);
+            self.draw_shading_pass(scenes[0]);
+            self.draw_transparents_pass(scenes);
 
-            self.draw_postprocessing_pass(scene);
+            // Secondary views (eg. the minimap) don't share the focus of the main view
+            self.draw_postprocessing_pass(scenes[0], target.is_none());
 
-            if self.pickbuffer.selection_request.load().is_some() {
-                self.draw_pickbuffer(scene, resources.get::<SelectedEntity>().selected());
+            // Entities can only be picked from the primary scene
+            if target.is_none() && self.pickbuffer.is_requested() {
+                self.draw_pickbuffer(scenes[0], resources.get::<SelectedEntity>().selected());
             }
         }
 
         if self.settings.debug_view.is_gamma_converter() {
-            self.draw_view_overlay(scene, resources);
+            self.draw_view_overlay(scenes[0], resources, camera);
         }
 
+        let srgb = self.shading_result_is_linear();
+
         unsafe {
             {
                 let mut data = self.data.lock();
@@ -220,10 +361,18 @@ impl Renderer {
             }
 
             gpu_profile_event!(self.gpu, "final_or_debug_view");
+            // The game's film curve is a tone mapping operator as well, so it's left out when another operator is applied during postprocessing
+            let debug_view = if self.settings.debug_view == RenderDebugView::None
+                && self.settings.tone_mapping != ToneMapping::None
+            {
+                RenderDebugView::NoFilmCurve
+            } else {
+                self.settings.debug_view
+            };
             let pipeline = self
                 .render_globals
                 .pipelines
-                .get_debug_view_pipeline(self.settings.debug_view);
+                .get_debug_view_pipeline(debug_view);
 
             self.gpu
                 .current_states
@@ -231,31 +380,126 @@ impl Renderer {
             self.execute_global_pipeline(pipeline, "final_or_debug_view");
         }
 
+        if let Some(color) = self.settings.background_color {
+            gpu_event!(self.gpu, "background");
+            self.background.draw(self, color, srgb);
+        }
+
+        if self.settings.debug_view.is_atmosphere_lookup() {
+            self.draw_atmosphere_lookup_view();
+        }
+
+        if self.settings.draw_shadow_coverage {
+            gpu_event!(self.gpu, "shadow_coverage");
+            self.shadow_coverage.draw(self);
+        }
+
+        if self.settings.draw_shadow_split {
+            gpu_event!(self.gpu, "shadow_split");
+            self.shadow_split.draw(self, scenes[0]);
+        }
+
+        if self.settings.draw_grid {
+            gpu_event!(self.gpu, "grid");
+            self.grid.draw(self, self.settings.grid_spacing);
+        }
+
+        if self.settings.draw_map_tint {
+            gpu_event!(self.gpu, "map_tint");
+            self.map_tint.draw(self, scenes);
+        }
+
         if !self.settings.debug_view.is_gamma_converter() {
-            self.draw_view_overlay(scene, resources);
+            self.draw_view_overlay(scenes[0], resources, camera);
         }
 
-        self.gpu.blit_texture(
-            &self.data.lock().gbuffers.shading_result.view,
-            self.gpu.swapchain_target.read().as_ref().unwrap(),
-            // final_combine and final_combine_no_film_curve already apply gamma correction
-            !matches!(
-                self.settings.debug_view,
-                RenderDebugView::None | RenderDebugView::NoFilmCurve
-            ),
-        );
+        // Billboards are submitted by the GUI for the main view only
+        if target.is_none() {
+            gpu_event!(self.gpu, "icon_billboards");
+            let shading_result = self
+                .data
+                .lock()
+                .gbuffers
+                .shading_result
+                .render_target
+                .clone();
+            self.icon_billboards.draw(self, &shading_result, srgb);
+        }
 
-        {
-            let data = self.data.lock();
-            data.gbuffers
-                .depth
-                .copy_to_staging(&data.gbuffers.depth_staging);
+        if let Some(target) = target {
+            unsafe {
+                self.gpu
+                    .lock_context()
+                    .RSSetViewports(Some(std::slice::from_ref(&target.viewport())));
+            }
+
+            self.gpu.upscale_texture(
+                &self.data.lock().gbuffers.shading_result.view,
+                &target.render_target,
+                srgb,
+                false,
+            );
+            return;
         }
 
-        self.frame_index.fetch_add(1, Ordering::Relaxed);
+        // The GBuffer might be smaller than the swapchain when render scale is < 1.0, so we need to upscale to the whole window
+        let (window_width, window_height) = self.gpu.swapchain_resolution.load();
+        unsafe {
+            self.gpu
+                .lock_context()
+                .RSSetViewports(Some(&[D3D11_VIEWPORT {
+                    TopLeftX: 0.0,
+                    TopLeftY: 0.0,
+                    Width: window_width as f32,
+                    Height: window_height as f32,
+                    MinDepth: 0.0,
+                    MaxDepth: 1.0,
+                }]));
+        }
+
+        if self.render_resolution() == (window_width, window_height) {
+            self.gpu.blit_texture(
+                &self.data.lock().gbuffers.shading_result.view,
+                self.gpu.swapchain_target.read().as_ref().unwrap(),
+                srgb,
+            );
+        } else {
+            self.gpu.upscale_texture(
+                &self.data.lock().gbuffers.shading_result.view,
+                self.gpu.swapchain_target.read().as_ref().unwrap(),
+                srgb,
+                self.settings.upscale_filter == UpscaleFilter::Sharp,
+            );
+        }
+    }
+
+    /// Whether the shading result still needs to be converted to sRGB for display
+    fn shading_result_is_linear(&self) -> bool {
+        // final_combine and final_combine_no_film_curve already apply gamma correction
+        !matches!(
+            self.settings.debug_view,
+            RenderDebugView::None | RenderDebugView::NoFilmCurve
+        )
+    }
+
+    /// Replaces the shading result with a scaled up preview of the selected atmosphere lookup
+    fn draw_atmosphere_lookup_view(&self) {
+        gpu_profile_event!(self.gpu, "atmosphere_lookup_view");
+        let data = self.data.lock();
+        let lookup = match self.settings.debug_view {
+            RenderDebugView::AtmosphereFarLookup => &data.gbuffers.atmos_ss_far_lookup,
+            RenderDebugView::AtmosphereNearLookup => &data.gbuffers.atmos_ss_near_lookup,
+            RenderDebugView::AtmosphereDepthAngleDensity => {
+                &data.gbuffers.depth_angle_density_lookup
+            }
+            _ => return,
+        };
+
+        self.gpu
+            .blit_texture_tonemapped(&lookup.view, &data.gbuffers.shading_result.render_target);
     }
 
-    fn draw_view_overlay(&self, scene: &mut Scene, resources: &AppResources) {
+    fn draw_view_overlay(&self, scene: &mut Scene, resources: &AppResources, camera: &Camera) {
         gpu_profile_event!(self.gpu, "view_overlay");
 
         self.gpu
@@ -280,6 +524,12 @@ impl Renderer {
             resources.get::<RendererShared>().clone(),
             draw_utilities_system,
         );
+        if self.settings.draw_model_placeholders {
+            scene.run_system_once_with(
+                resources.get::<RendererShared>().clone(),
+                draw_model_placeholders_system,
+            );
+        }
         // scene.run_system_once_with(resources.get::<RendererShared>().clone(), draw_aabb_system);
 
         if let Some(selected) = resources.get::<SelectedEntity>().selected() {
@@ -317,14 +567,70 @@ impl Renderer {
             }
         }
 
+        if self.settings.draw_light_direction {
+            self.draw_light_direction(camera);
+        }
+
+        if self.settings.draw_shadow_frusta {
+            self.draw_shadow_frusta(scene);
+        }
+
         self.gpu.restore_state(&dxstate);
     }
 
-    fn bind_view(&self, view: &impl View, index: usize) {
+    /// Draws an arrow in the bottom right corner of the view, pointing in the direction of the global light
+    fn draw_light_direction(&self, camera: &Camera) {
+        const DISTANCE: f32 = 2.0;
+
+        let Some(direction) = self.global_light_direction() else {
+            return;
+        };
+
+        // Anchored to a point on the screen, so the arrow stays in place when zooming
+        let corner = camera
+            .projective_to_world
+            .project_point3(Vec3::new(0.75, -0.65, 1.0));
+        let (anchor, length) =
+            if let CameraProjection::OrthographicSized { size } = camera.projection {
+                let forward = camera.forward().normalize();
+                let on_camera_plane = corner - forward * forward.dot(corner - camera.position());
+                (on_camera_plane + forward * DISTANCE, size * 0.25)
+            } else {
+                (
+                    camera.position() + (corner - camera.position()).normalize() * DISTANCE,
+                    DISTANCE * (camera.zoomed_fov().to_radians() * 0.5).tan() * 0.25,
+                )
+            };
+
+        self.immediate.arrow(
+            anchor - direction * length * 0.5,
+            anchor + direction * length * 0.5,
+            Color::from_rgb(1.0, 0.8, 0.2),
+            2.0,
+        );
+    }
+
+    /// Direction the global light shines in, if there is one
+    fn global_light_direction(&self) -> Option<Vec3> {
+        self.data
+            .lock()
+            .externs
+            .global_lighting
+            .as_ref()
+            .map(|g| g.unk50.truncate().normalize_or_zero())
+            .filter(|d| *d != Vec3::ZERO)
+    }
+
+    /// Binds a view to draw from. `index` is the visibility slot of the view, which for shadow maps is the index of their entity
+    fn bind_view(&self, view: &impl View, index: usize, is_camera_view: bool) {
         *self.active_view.pocus() = index;
+        *self.is_camera_view.pocus() = is_camera_view;
         self.data.lock().externs.view = Some({
             let mut e = externs::View::default();
             view.update_extern(&mut e);
+            if is_camera_view {
+                self.pocus().lod_origin = e.position.truncate();
+            }
             e
         });
 
@@ -350,9 +656,6 @@ impl Renderer {
     }
 
     fn begin_world_frame(&self, _scene: &Scene) {
-        self.pocus().delta_time = self.last_frame.elapsed().as_secs_f64();
-        self.pocus().last_frame = Instant::now();
-
         {
             let externs = &mut self.data.lock().externs;
             externs.frame = Frame {
@@ -431,20 +734,94 @@ impl Renderer {
     }
 
     pub fn set_render_settings(&self, settings: RendererSettings) {
+        let scale_changed = settings.render_scale != self.settings.render_scale;
+        let rt0_changed = settings.rt0_float != self.settings.rt0_float;
+        let msaa_changed = settings.msaa_samples != self.settings.msaa_samples;
+        let load_threads_changed = settings.asset_load_threads != self.settings.asset_load_threads;
         self.pocus().settings = settings;
+
+        if load_threads_changed {
+            self.data
+                .lock()
+                .asset_manager
+                .set_worker_count(self.settings.asset_load_threads);
+        }
+
+        // Failing to recreate a target reverts the setting, the previous targets are kept around
+        if rt0_changed {
+            let gbuffers = &mut self.data.lock().gbuffers;
+            if let Err(e) = gbuffers.set_rt0_float(self.settings.rt0_float) {
+                error!("Failed to recreate RT0: {e:?}");
+                self.pocus().settings.rt0_float = !self.settings.rt0_float;
+                if let Err(e) = gbuffers.set_rt0_float(self.settings.rt0_float) {
+                    error!("Failed to restore RT0: {e:?}");
+                }
+            }
+        }
+
+        if msaa_changed {
+            let gbuffers = &mut self.data.lock().gbuffers;
+            if let Err(e) = gbuffers.set_msaa_samples(self.settings.msaa_samples) {
+                error!("Failed to recreate MSAA targets: {e:?}");
+            }
+            // Unsupported sample counts fall back to no MSAA, the settings reflect what's actually in use
+            self.pocus().settings.msaa_samples = gbuffers.msaa_samples();
+        }
+
+        if scale_changed {
+            let (width, height) = self.window_size.load();
+            self.resize_buffers(width, height);
+        }
+
+        self.gpu
+            .material_samplers
+            .set_anisotropy(self.settings.anisotropy.level());
+        self.gpu
+            .material_samplers
+            .set_mip_lod_bias(self.settings.mip_lod_bias);
     }
 
+    /// Resizes the render buffers for the given window size.
+    /// The buffers are reallocated by a later frame, once the size has stopped changing
     pub fn resize_buffers(&self, width: u32, height: u32) {
-        self.data
+        self.window_size.store((width, height));
+        let render_resolution = self.target_render_resolution();
+
+        self.data.lock().gbuffers.resize(render_resolution);
+    }
+
+    fn apply_pending_resize(&self) {
+        let new_size = self
+            .data
             .lock()
             .gbuffers
-            .resize((width, height))
+            .apply_pending_resize()
             .expect("Failed to resize GBuffer");
 
-        self.pocus()
-            .pickbuffer
-            .resize((width, height))
-            .expect("Failed to resize Pickbuffer");
+        // The pickbuffer outline reads the GBuffer depth, so both are resized together
+        if let Some(new_size) = new_size {
+            self.render_resolution.store(new_size);
+            self.pocus()
+                .pickbuffer
+                .resize(new_size)
+                .expect("Failed to resize Pickbuffer");
+        }
+    }
+
+    /// The resolution the world is rendered at, before being upscaled to the window.
+    /// This is the size the GBuffer is allocated at, so it only follows the window once a resize has been applied
+    pub fn render_resolution(&self) -> (u32, u32) {
+        self.render_resolution.load()
+    }
+
+    /// The resolution the GBuffer is resized to for the current window size and render scale
+    fn target_render_resolution(&self) -> (u32, u32) {
+        let (width, height) = self.window_size.load();
+        let scale = self.settings.render_scale.clamp(0.25, 1.0);
+        (
+            ((width as f32 * scale).ceil() as u32).max(1),
+            ((height as f32 * scale).ceil() as u32).max(1),
+        )
     }
 
     /// Checks if we should render the given stage and feature, based on render settings
@@ -502,17 +879,217 @@ fn default_true() -> bool {
 fn default_false() -> bool {
     false
 }
+fn default_render_scale() -> f32 {
+    1.0
+}
+fn default_lod_bias() -> f32 {
+    1.0
+}
+fn default_asset_load_threads() -> usize {
+    // Leave some room for the main and render threads
+    std::thread::available_parallelism()
+        .map_or(4, |n| n.get().saturating_sub(2))
+        .clamp(1, 16)
+}
+
+fn default_near_fade_distance() -> f32 {
+    0.5
+}
+
+fn default_max_frame_latency() -> u32 {
+    3
+}
+
+fn default_msaa_samples() -> u32 {
+    1
+}
+
+fn default_grid_spacing() -> f32 {
+    1.0
+}
+
+fn default_exposure() -> f32 {
+    1.0
+}
+
+fn default_auto_exposure_speed() -> f32 {
+    1.5
+}
+
+fn default_bloom_intensity() -> f32 {
+    0.3
+}
+fn default_bloom_threshold() -> f32 {
+    1.0
+}
+
+fn default_dof_focal_distance() -> f32 {
+    10.0
+}
+fn default_dof_focal_range() -> f32 {
+    5.0
+}
+fn default_dof_blur_strength() -> f32 {
+    8.0
+}
+
+fn default_vignette_strength() -> f32 {
+    0.5
+}
+fn default_vignette_radius() -> f32 {
+    0.5
+}
+fn default_chromatic_aberration_strength() -> f32 {
+    2.0
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RendererSettings {
     pub vsync: bool,
+    /// Maximum number of frames the CPU may queue up ahead of the GPU (1-3). 3 is the DXGI default.
+    ///
+    /// Lower values make the camera more responsive at a slight throughput cost. This matters most with vsync on, as
+    /// queued frames then each wait for a vertical blank before they're shown.
+    #[serde(default = "default_max_frame_latency")]
+    pub max_frame_latency: u32,
+    /// Wait for the swapchain at the start of every frame, so input is sampled right before rendering. Applied on restart.
+    #[serde(default)]
+    pub waitable_swapchain: bool,
     pub ssao: bool,
+    /// Sample depth with linear filtering in the SSAO pass instead of point sampling, to show the smearing across depth edges
+    #[serde(skip)]
+    pub ssao_linear_depth: bool,
     #[serde(skip)]
     pub matcap: bool,
+    /// Draw scene geometry as wireframe
+    #[serde(skip)]
+    pub wireframe: bool,
     #[serde(skip, default = "default_true")]
     pub draw_selection_outline: bool,
+    /// Draw an arrow showing the direction of the global light
+    #[serde(skip)]
+    pub draw_light_direction: bool,
+    /// Tint geometry by the shadow cascade that would sample it
+    #[serde(skip)]
+    pub draw_shadow_coverage: bool,
+    /// Tint shadows by whether they come from the cached stationary pass or the per-frame moving pass
+    #[serde(skip)]
+    pub draw_shadow_split: bool,
+    /// Outline the frustum of every shadow-casting light
+    #[serde(skip)]
+    pub draw_shadow_frusta: bool,
+    /// Tint geometry by the map it belongs to, and outline the seams between maps
+    #[serde(skip)]
+    pub draw_map_tint: bool,
+    /// Draw a grid on the ground plane, with a major line every 10 minor lines
+    #[serde(skip)]
+    pub draw_grid: bool,
+    /// Distance between minor grid lines, in meters
+    #[serde(default = "default_grid_spacing")]
+    pub grid_spacing: f32,
+    /// Draw a placeholder cube for entities whose model failed to load
+    #[serde(default = "default_true")]
+    pub draw_model_placeholders: bool,
     pub shadow_quality: ShadowQuality,
+    /// Number of PCF samples used to filter shadows, `None` uses the default for the shadow quality
+    #[serde(default)]
+    pub shadow_pcf_samples: Option<ShadowPcfSamples>,
     pub shadow_updates_per_frame: usize,
+    /// Scale of the render resolution relative to the window size (0.25-1.0)
+    #[serde(default = "default_render_scale")]
+    pub render_scale: f32,
+    /// Filter used to upscale the final image when render scale is below 1.0
+    #[serde(default)]
+    pub upscale_filter: UpscaleFilter,
+    /// Multiplier for the distances at which lower detail LODs are used
+    #[serde(default = "default_lod_bias")]
+    pub lod_bias: f32,
+    /// Number of threads used to load and parse assets
+    #[serde(default = "default_asset_load_threads")]
+    pub asset_load_threads: usize,
+    /// Always render the highest detail LOD, regardless of distance
+    #[serde(skip, default = "default_false")]
+    pub force_lod0: bool,
+    /// Anisotropic filtering level for material textures
+    #[serde(default)]
+    pub anisotropy: Anisotropy,
+    /// Bias added to the mip level of material textures. Negative values make textures sharper
+    #[serde(default)]
+    pub mip_lod_bias: f32,
+    /// Store albedo (RT0) as R11G11B10 float instead of 8-bit sRGB, reducing banding
+    #[serde(default)]
+    pub rt0_float: bool,
+    /// Samples per pixel for the G-buffer pass (1, 2, 4 or 8). The G-buffer is resolved right after geometry is drawn
+    #[serde(default = "default_msaa_samples")]
+    pub msaa_samples: u32,
+    /// Disable depth writes for all techniques drawn in the transparent passes
+    #[serde(skip)]
+    pub force_transparent_depth_readonly: bool,
+    /// Overrides whether skinned dynamic models are drawn with `entity_vs_override`, `None` detects it per part
+    #[serde(skip)]
+    pub force_skinning_vs: Option<bool>,
+    /// Dither out geometry close to the camera instead of clipping it against the near plane
+    #[serde(default)]
+    pub near_fade: bool,
+    /// Distance from the camera at which geometry starts fading out
+    #[serde(default = "default_near_fade_distance")]
+    pub near_fade_distance: f32,
+    /// Curve used to bring the HDR shading result into displayable range before postprocessing.
+    /// Any operator other than [`ToneMapping::None`] replaces the game's film curve, which is then skipped by using `final_combine_no_film_curve`
+    #[serde(default)]
+    pub tone_mapping: ToneMapping,
+    /// Multiplier applied to the shading result before tone mapping, on top of auto-exposure
+    #[serde(default = "default_exposure")]
+    pub exposure: f32,
+    /// Adapt the exposure to the average brightness of the scene
+    #[serde(default)]
+    pub auto_exposure: bool,
+    /// How quickly auto-exposure adapts, higher is faster
+    #[serde(default = "default_auto_exposure_speed")]
+    pub auto_exposure_speed: f32,
+    /// Glow around highlights that are brighter than [`Self::bloom_threshold`]
+    #[serde(default)]
+    pub bloom: bool,
+    #[serde(default = "default_bloom_intensity")]
+    pub bloom_intensity: f32,
+    /// Brightness above which pixels start to bloom
+    #[serde(default = "default_bloom_threshold")]
+    pub bloom_threshold: f32,
+    /// Depth of field, blurs everything outside of the focal range
+    #[serde(default)]
+    pub dof: bool,
+    /// Focus on whatever is at the center of the screen instead of [`Self::dof_focal_distance`]
+    #[serde(default = "default_true")]
+    pub dof_autofocus: bool,
+    #[serde(default = "default_dof_focal_distance")]
+    pub dof_focal_distance: f32,
+    /// Depth range around the focal distance that stays sharp
+    #[serde(default = "default_dof_focal_range")]
+    pub dof_focal_range: f32,
+    /// Maximum blur radius, in pixels
+    #[serde(default = "default_dof_blur_strength")]
+    pub dof_blur_strength: f32,
+    #[serde(default)]
+    pub vignette: bool,
+    #[serde(default = "default_vignette_strength")]
+    pub vignette_strength: f32,
+    /// Distance from the center of the screen at which the vignette starts, where 1 is the corners
+    #[serde(default = "default_vignette_radius")]
+    pub vignette_radius: f32,
+    /// Linear RGB color the edges are tinted towards
+    #[serde(default)]
+    pub vignette_color: [f32; 3],
+    #[serde(default)]
+    pub chromatic_aberration: bool,
+    /// Offset of the red and blue channels at the edges of the screen, in pixels
+    #[serde(default = "default_chromatic_aberration_strength")]
+    pub chromatic_aberration_strength: f32,
+    /// Film grain intensity, 0 disables the effect
+    #[serde(default)]
+    pub grain: f32,
+    /// Use the same grain pattern every frame, for reproducible captures
+    #[serde(default)]
+    pub grain_frozen: bool,
 
     #[serde(skip, default = "RenderFeatureVisibility::all")]
     pub feature_statics: RenderFeatureVisibility,
@@ -526,6 +1103,9 @@ pub struct RendererSettings {
     pub feature_decorators: RenderFeatureVisibility,
     #[serde(skip, default = "RenderFeatureVisibility::all")]
     pub feature_water: RenderFeatureVisibility,
+    /// Draw water in a flat color, to tell it apart from other transparents
+    #[serde(skip)]
+    pub water_tint: bool,
     pub feature_atmosphere: bool,
     pub feature_cubemaps: bool,
     pub feature_global_lighting: bool,
@@ -541,6 +1121,13 @@ pub struct RendererSettings {
     #[serde(skip, default = "default_false")]
     pub fxaa_noise: bool,
 
+    /// Solid color drawn behind all geometry, see [`Self::hide_environment`]
+    #[serde(skip)]
+    pub background_color: Option<Color>,
+    /// Settings from before the environment was hidden, restored by [`Self::restore_environment`]
+    #[serde(skip)]
+    hidden_environment: Option<EnvironmentSnapshot>,
+
     // #[serde(skip, default = "default_true")]
     // pub depth_prepass: bool,
     #[serde(skip)]
@@ -551,11 +1138,57 @@ impl Default for RendererSettings {
     fn default() -> Self {
         Self {
             vsync: true,
+            max_frame_latency: default_max_frame_latency(),
+            waitable_swapchain: false,
             ssao: true,
+            ssao_linear_depth: false,
             matcap: false,
+            wireframe: false,
             draw_selection_outline: true,
+            draw_light_direction: false,
+            draw_shadow_coverage: false,
+            draw_shadow_split: false,
+            draw_shadow_frusta: false,
+            draw_map_tint: false,
+            draw_grid: false,
+            grid_spacing: default_grid_spacing(),
+            draw_model_placeholders: true,
             shadow_quality: ShadowQuality::Medium,
+            shadow_pcf_samples: None,
             shadow_updates_per_frame: 2,
+            render_scale: 1.0,
+            upscale_filter: UpscaleFilter::default(),
+            lod_bias: 1.0,
+            asset_load_threads: default_asset_load_threads(),
+            force_lod0: false,
+            anisotropy: Anisotropy::default(),
+            mip_lod_bias: 0.0,
+            rt0_float: false,
+            msaa_samples: default_msaa_samples(),
+            force_transparent_depth_readonly: false,
+            force_skinning_vs: None,
+            near_fade: false,
+            near_fade_distance: default_near_fade_distance(),
+            tone_mapping: ToneMapping::default(),
+            exposure: default_exposure(),
+            auto_exposure: false,
+            auto_exposure_speed: default_auto_exposure_speed(),
+            bloom: false,
+            bloom_intensity: default_bloom_intensity(),
+            bloom_threshold: default_bloom_threshold(),
+            dof: false,
+            dof_autofocus: true,
+            dof_focal_distance: default_dof_focal_distance(),
+            dof_focal_range: default_dof_focal_range(),
+            dof_blur_strength: default_dof_blur_strength(),
+            vignette: false,
+            vignette_strength: default_vignette_strength(),
+            vignette_radius: default_vignette_radius(),
+            vignette_color: [0.0; 3],
+            chromatic_aberration: false,
+            chromatic_aberration_strength: default_chromatic_aberration_strength(),
+            grain: 0.0,
+            grain_frozen: false,
 
             feature_statics: RenderFeatureVisibility::all(),
             feature_terrain: RenderFeatureVisibility::all(),
@@ -563,6 +1196,7 @@ impl Default for RendererSettings {
             feature_sky: RenderFeatureVisibility::all(),
             feature_decorators: RenderFeatureVisibility::all(),
             feature_water: RenderFeatureVisibility::all(),
+            water_tint: false,
             feature_atmosphere: false,
             feature_cubemaps: false,
             feature_global_lighting: false,
@@ -574,12 +1208,237 @@ impl Default for RendererSettings {
 
             fxaa_noise: false,
 
+            background_color: None,
+            hidden_environment: None,
+
             // depth_prepass: true,
             debug_view: RenderDebugView::None,
         }
     }
 }
 
+impl RendererSettings {
+    /// Sets the quality-related fields to the values of the given preset. Any other settings are left untouched.
+    pub fn apply_preset(&mut self, preset: RenderPreset) {
+        let (ssao, shadow_quality, shadow_updates_per_frame, atmosphere, fxaa, render_scale) =
+            preset.values();
+
+        self.ssao = ssao;
+        self.shadow_quality = shadow_quality;
+        self.shadow_updates_per_frame = shadow_updates_per_frame;
+        self.feature_atmosphere = atmosphere;
+        self.feature_fxaa = fxaa;
+        self.render_scale = render_scale;
+    }
+
+    /// Feature renderers that have their own visibility setting
+    pub const TOGGLEABLE_FEATURES: [TfxFeatureRenderer; 6] = [
+        TfxFeatureRenderer::StaticObjects,
+        TfxFeatureRenderer::TerrainPatch,
+        TfxFeatureRenderer::DynamicObjects,
+        TfxFeatureRenderer::SkyTransparent,
+        TfxFeatureRenderer::SpeedtreeTrees,
+        TfxFeatureRenderer::Water,
+    ];
+
+    /// Hides every feature in [`Self::TOGGLEABLE_FEATURES`] except for the given one, which is made fully visible
+    pub fn isolate_feature(&mut self, feature: TfxFeatureRenderer) {
+        for (f, visibility) in [
+            (TfxFeatureRenderer::StaticObjects, &mut self.feature_statics),
+            (TfxFeatureRenderer::TerrainPatch, &mut self.feature_terrain),
+            (
+                TfxFeatureRenderer::DynamicObjects,
+                &mut self.feature_dynamics,
+            ),
+            (TfxFeatureRenderer::SkyTransparent, &mut self.feature_sky),
+            (
+                TfxFeatureRenderer::SpeedtreeTrees,
+                &mut self.feature_decorators,
+            ),
+            (TfxFeatureRenderer::Water, &mut self.feature_water),
+        ] {
+            *visibility = if f == feature {
+                RenderFeatureVisibility::all()
+            } else {
+                RenderFeatureVisibility::empty()
+            };
+        }
+    }
+
+    pub fn is_environment_hidden(&self) -> bool {
+        self.hidden_environment.is_some()
+    }
+
+    /// Disables sky objects and the atmosphere and replaces the background with a solid color.
+    /// The previous settings are kept so they can be restored with [`Self::restore_environment`]
+    pub fn hide_environment(&mut self, color: Color) {
+        if self.hidden_environment.is_none() {
+            self.hidden_environment = Some(EnvironmentSnapshot {
+                feature_sky: self.feature_sky,
+                feature_atmosphere: self.feature_atmosphere,
+                background_color: self.background_color,
+            });
+        }
+
+        self.feature_sky.remove(RenderFeatureVisibility::VISIBLE);
+        self.feature_atmosphere = false;
+        self.background_color = Some(color);
+    }
+
+    pub fn restore_environment(&mut self) {
+        if let Some(snapshot) = self.hidden_environment.take() {
+            self.feature_sky = snapshot.feature_sky;
+            self.feature_atmosphere = snapshot.feature_atmosphere;
+            self.background_color = snapshot.background_color;
+        }
+    }
+
+    pub fn apply_lighting_setup(&mut self, setup: LightingSetup) {
+        let (matcap, global_lighting, cubemaps) = setup.values();
+
+        self.matcap = matcap;
+        self.feature_global_lighting = global_lighting;
+        self.feature_cubemaps = cubemaps;
+    }
+
+    /// Returns the lighting setup that matches the current settings, or `None` if they have been changed manually
+    pub fn current_lighting_setup(&self) -> Option<LightingSetup> {
+        LightingSetup::iter().find(|s| {
+            s.values()
+                == (
+                    self.matcap,
+                    self.feature_global_lighting,
+                    self.feature_cubemaps,
+                )
+        })
+    }
+
+    /// Returns the preset that matches the current settings, or `None` if any of the preset fields have been changed manually
+    pub fn current_preset(&self) -> Option<RenderPreset> {
+        RenderPreset::iter().find(|p| {
+            p.values()
+                == (
+                    self.ssao,
+                    self.shadow_quality,
+                    self.shadow_updates_per_frame,
+                    self.feature_atmosphere,
+                    self.feature_fxaa,
+                    self.render_scale,
+                )
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct EnvironmentSnapshot {
+    feature_sky: RenderFeatureVisibility,
+    feature_atmosphere: bool,
+    background_color: Option<Color>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, EnumIter, strum::Display)]
+pub enum RenderPreset {
+    Low,
+    Medium,
+    High,
+    Ultra,
+}
+
+impl RenderPreset {
+    /// (ssao, shadow quality, shadow updates per frame, atmosphere, fxaa, render scale)
+    fn values(&self) -> (bool, ShadowQuality, usize, bool, bool, f32) {
+        match self {
+            RenderPreset::Low => (false, ShadowQuality::Off, 1, false, false, 0.5),
+            RenderPreset::Medium => (true, ShadowQuality::Low, 1, false, true, 0.75),
+            RenderPreset::High => (true, ShadowQuality::Medium, 2, false, true, 1.0),
+            RenderPreset::Ultra => (true, ShadowQuality::Highest, 4, true, true, 1.0),
+        }
+    }
+}
+
+/// Standard lighting configurations, used for previewing materials
+#[derive(Copy, Clone, Debug, PartialEq, EnumIter, strum::Display)]
+pub enum LightingSetup {
+    /// Full map lighting, including global lighting and cubemaps
+    Map,
+    /// Only the local lights placed in the map
+    LocalLights,
+    /// Fixed matcap lighting, independent of the map
+    Matcap,
+}
+
+impl LightingSetup {
+    /// (matcap, global lighting, cubemaps)
+    fn values(&self) -> (bool, bool, bool) {
+        match self {
+            LightingSetup::Map => (false, true, true),
+            LightingSetup::LocalLights => (false, false, false),
+            LightingSetup::Matcap => (true, false, false),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        LightingSetup::iter()
+            .cycle()
+            .skip_while(|s| s != self)
+            .nth(1)
+            .unwrap()
+    }
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, EnumIter, strum::Display,
+)]
+pub enum UpscaleFilter {
+    #[default]
+    Bilinear,
+    /// Bicubic (Catmull-Rom) filter, keeps edges sharper than bilinear
+    Sharp,
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, EnumIter, strum::Display,
+)]
+#[repr(u32)]
+pub enum ToneMapping {
+    /// Only the game's film curve, clipping anything it leaves above 1
+    None = 0,
+    Reinhard = 1,
+    #[default]
+    #[strum(to_string = "ACES")]
+    Aces = 2,
+    #[strum(to_string = "Uncharted 2")]
+    Uncharted2 = 3,
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, EnumIter, strum::Display,
+)]
+pub enum Anisotropy {
+    #[default]
+    Off,
+    #[strum(to_string = "2x")]
+    X2,
+    #[strum(to_string = "4x")]
+    X4,
+    #[strum(to_string = "8x")]
+    X8,
+    #[strum(to_string = "16x")]
+    X16,
+}
+
+impl Anisotropy {
+    pub fn level(&self) -> u32 {
+        match self {
+            Anisotropy::Off => 1,
+            Anisotropy::X2 => 2,
+            Anisotropy::X4 => 4,
+            Anisotropy::X8 => 8,
+            Anisotropy::X16 => 16,
+        }
+    }
+}
+
 bitflags! {
     #[derive(Serialize, Deserialize, Clone, Copy)]
     pub struct RenderFeatureVisibility : u8 {
@@ -646,6 +1505,13 @@ pub enum RenderDebugView {
     ValidSmoothnessHeatmap,
     ValidSourceColorBrightness,
     ValidSourceColorSaturation,
+
+    AtmosphereFarLookup,
+    AtmosphereNearLookup,
+    AtmosphereDepthAngleDensity,
+
+    /// Flat color per feature renderer, see [`FeatureColorRenderer`]
+    FeatureColors,
 }
 
 impl RenderDebugView {
@@ -653,6 +1519,16 @@ impl RenderDebugView {
     pub fn is_gamma_converter(&self) -> bool {
         matches!(self, Self::None | Self::NoFilmCurve)
     }
+
+    /// Is this view a preview of one of the atmosphere lookup textures, rather than a TFX debug pipeline?
+    pub fn is_atmosphere_lookup(&self) -> bool {
+        matches!(
+            self,
+            Self::AtmosphereFarLookup
+                | Self::AtmosphereNearLookup
+                | Self::AtmosphereDepthAngleDensity
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
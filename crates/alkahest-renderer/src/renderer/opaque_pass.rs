@@ -28,7 +28,7 @@ impl Renderer {
     //     self.run_renderstage_systems(scene, TfxRenderStage::DepthPrepass);
     // }
 
-    pub fn draw_opaque_pass(&self, scene: &mut Scene) {
+    pub fn draw_opaque_pass(&self, scenes: &mut [&mut Scene]) {
         gpu_profile_event!(self.gpu, "generate_gbuffer");
 
         self.gpu
@@ -37,28 +37,55 @@ impl Renderer {
 
         unsafe {
             let gbuffers = &self.data.lock().gbuffers;
+            // With MSAA, geometry is drawn to the multisampled targets and resolved afterwards
+            let (rt0, rt1, rt2, depth) = match &gbuffers.msaa {
+                Some(msaa) => (&msaa.rt0, &msaa.rt1, &msaa.rt2, &msaa.depth),
+                None => (&gbuffers.rt0, &gbuffers.rt1, &gbuffers.rt2, &gbuffers.depth),
+            };
             self.gpu.lock_context().OMSetRenderTargets(
                 Some(&[
-                    Some(gbuffers.rt0.render_target.clone()),
-                    Some(gbuffers.rt1.render_target.clone()),
-                    Some(gbuffers.rt2.render_target.clone()),
+                    Some(rt0.render_target.clone()),
+                    Some(rt1.render_target.clone()),
+                    Some(rt2.render_target.clone()),
                 ]),
-                &gbuffers.depth.view,
+                &depth.view,
             );
             self.gpu
                 .lock_context()
                 .RSSetViewports(Some(std::slice::from_ref(&gbuffers.rt0.viewport())));
 
-            gbuffers.rt0.clear(&[0.0, 0.0, 0.0, 0.0]);
-            gbuffers.rt1.clear(&[0.0, 0.0, 0.0, 0.0]);
-            gbuffers.rt2.clear(&[1.0, 0.5, 1.0, 1.0]);
+            rt0.clear(&[0.0, 0.0, 0.0, 0.0]);
+            rt1.clear(&[0.0, 0.0, 0.0, 0.0]);
+            rt2.clear(&[1.0, 0.5, 1.0, 1.0]);
             // if !self.render_settings.depth_prepass {
-            gbuffers.depth.clear(0.0, 0);
+            depth.clear_default();
             // }
+            if gbuffers.msaa.is_some() {
+                // The resolve overwrites all depth, but stencil is only ever cleared here
+                gbuffers.depth.clear_default();
+            }
+        }
+
+        // Near fade discards fragments in a depth prepass, the GBuffer pass then only draws what's left in the depth buffer
+        if self.settings.near_fade {
+            gpu_event!(self.gpu, "near_fade_depth");
+            self.near_fade
+                .draw_depth(self, scenes, self.settings.near_fade_distance);
+            self.gpu.set_equal_depth_pass(true);
         }
 
         // Draw opaque pass
-        self.run_renderstage_systems(scene, TfxRenderStage::GenerateGbuffer);
+        for scene in scenes.iter_mut() {
+            self.run_renderstage_systems(scene, TfxRenderStage::GenerateGbuffer);
+        }
+        self.gpu.set_equal_depth_pass(false);
+
+        {
+            let data = self.data.lock();
+            if let Some(msaa) = &data.gbuffers.msaa {
+                self.msaa_resolve.resolve(self, &data.gbuffers, msaa);
+            }
+        }
 
         {
             let mut data = self.data.lock();
@@ -89,6 +116,8 @@ impl Renderer {
         self.gpu
             .current_states
             .store(StateSelection::new(Some(8), Some(15), Some(2), Some(1)));
-        self.run_renderstage_systems(scene, TfxRenderStage::Decals);
+        for scene in scenes.iter_mut() {
+            self.run_renderstage_systems(scene, TfxRenderStage::Decals);
+        }
     }
 }
@@ -1,5 +1,8 @@
 use std::{
+    fs::File,
+    io::BufWriter,
     mem::size_of,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -15,6 +18,7 @@ use alkahest_data::{
 use anyhow::Context;
 use bevy_ecs::entity::Entity;
 use crossbeam::atomic::AtomicCell;
+use png::{BitDepth, ColorType};
 use windows::Win32::{
     Foundation::RECT,
     Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader, D3D11_MAP_READ},
@@ -28,7 +32,7 @@ use crate::{
         gbuffer::{CpuStagingBuffer, DepthState, RenderTarget},
         Renderer,
     },
-    util::Hocus,
+    util::{color::Hsv, image::Png, Hocus},
 };
 
 impl Renderer {
@@ -50,13 +54,20 @@ impl Renderer {
         *self.gpu.custom_pixel_shader.pocus() = None;
         self.pickbuffer.end(&self.gpu);
         self.gpu.restore_state(&dxstate);
+
+        if let Some(path) = self.pickbuffer.pocus().debug_export_request.take() {
+            match self.pickbuffer.export_debug_png(&path) {
+                Ok(()) => info!("Saved pickbuffer to {}", path.display()),
+                Err(e) => error!("Failed to export pickbuffer: {e:?}"),
+            }
+        }
     }
 
     // TODO(cohae): move rendering logic to Pickbuffer (where possible)
     pub(super) fn draw_outline(&self, scene: &mut Scene, selected: Entity, time_since_select: f32) {
         gpu_event!(self.gpu, "selection_outline");
 
-        self.pickbuffer.outline_depth.clear(0.0, 0);
+        self.pickbuffer.outline_depth.clear_default();
 
         unsafe {
             let dxstate = self.gpu.backup_state();
@@ -68,6 +79,7 @@ impl Renderer {
             self.gpu
                 .lock_context()
                 .OMSetDepthStencilState(Some(&self.pickbuffer.outline_depth.state), 0);
+            self.pickbuffer.pocus().is_drawing_outline = true;
             draw_entity(
                 scene,
                 selected,
@@ -75,6 +87,7 @@ impl Renderer {
                 Some(&self.pickbuffer.static_instance_cb),
                 TfxRenderStage::GenerateGbuffer,
             );
+            self.pickbuffer.pocus().is_drawing_outline = false;
 
             // Draw the outline itself
 
@@ -114,9 +127,13 @@ impl Renderer {
 pub struct Pickbuffer {
     /// Are we currently drawing the pickbuffer?
     pub is_drawing_selection: bool,
+    /// Are we currently drawing the selected entity into the outline depth buffer?
+    pub is_drawing_outline: bool,
 
     pub(super) selection_request: AtomicCell<Option<(u32, u32)>>,
     selection_ready: AtomicBool,
+    /// Path to export the next (full screen) pickbuffer to
+    pub(super) debug_export_request: Option<PathBuf>,
 
     pub outline_depth: DepthState,
     pub pick_buffer: RenderTarget,
@@ -158,8 +175,10 @@ impl Pickbuffer {
 
         Ok(Self {
             is_drawing_selection: false,
+            is_drawing_outline: false,
             selection_request: AtomicCell::new(None),
             selection_ready: AtomicBool::new(false),
+            debug_export_request: None,
             outline_depth: DepthState::create(gctx.clone(), window_size, "pickbuffer_depth")
                 .context("Outline Depth")?,
             pick_buffer: RenderTarget::create(
@@ -213,6 +232,17 @@ impl Pickbuffer {
         self.selection_ready.store(false, Ordering::Relaxed);
     }
 
+    /// Draws the pickbuffer for the whole screen next frame, and saves it with [`Self::export_debug_png`].
+    /// The selected entity is left out, just like it is when picking
+    pub fn request_debug_export(&self, path: impl Into<PathBuf>) {
+        self.pocus().debug_export_request = Some(path.into());
+    }
+
+    /// Should the pickbuffer be drawn this frame?
+    pub(super) fn is_requested(&self) -> bool {
+        self.selection_request.load().is_some() || self.debug_export_request.is_some()
+    }
+
     pub fn cancel_request(&self) {
         self.pocus().selection_request.store(None);
     }
@@ -240,8 +270,12 @@ impl Pickbuffer {
             gpu.current_states
                 .store(StateSelection::new(Some(0), Some(2), Some(2), Some(0)));
 
-            // Limit the draw area to as small as possible
-            if let Some((x, y)) = self.selection_request.load() {
+            // Limit the draw area to as small as possible, unless the whole buffer is being exported
+            if let Some((x, y)) = self
+                .selection_request
+                .load()
+                .filter(|_| self.debug_export_request.is_none())
+            {
                 gpu.lock_context().RSSetScissorRects(Some(&[RECT {
                     left: x as i32 - 1,
                     top: y as i32 - 1,
@@ -281,7 +315,7 @@ impl Pickbuffer {
     }
 
     pub fn clear(&self, gpu: &GpuContext) {
-        self.outline_depth.clear(0.0, 0);
+        self.outline_depth.clear_default();
 
         unsafe {
             gpu.lock_context()
@@ -298,7 +332,62 @@ impl Pickbuffer {
         }
     }
 
+    /// Saves the last drawn pickbuffer to a PNG, giving every entity its own color. Empty pixels are black.
+    ///
+    /// Stalls until the GPU has finished drawing the pickbuffer
+    pub fn export_debug_png(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let desc = self.pick_buffer.get_desc();
+        let (width, height) = (desc.Width as usize, desc.Height as usize);
+
+        let rgba = self.pick_buffer_staging.map(D3D11_MAP_READ, |m| {
+            let mut rgba = Vec::with_capacity(width * height * 4);
+            // Rows can be padded, so RowPitch may be larger than width * 4
+            for y in 0..height {
+                let row = unsafe {
+                    std::slice::from_raw_parts(
+                        (m.pData as *const u8).add(y * m.RowPitch as usize) as *const u32,
+                        width,
+                    )
+                };
+
+                for &id in row {
+                    rgba.extend_from_slice(&Self::debug_color(id));
+                }
+            }
+
+            rgba
+        })?;
+
+        let png = Png {
+            data: rgba.into(),
+            dimensions: [width, height],
+            color_type: ColorType::Rgba,
+            bit_depth: BitDepth::Eight,
+        };
+
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create pickbuffer file {}", path.display()))?;
+        png.write(BufWriter::new(file))
+    }
+
+    /// Color for an entity index in the debug export. Consecutive indices are spread over the hue circle using the golden ratio, so neighbouring entities are easy to tell apart.
+    fn debug_color(id: u32) -> [u8; 4] {
+        if id == u32::MAX {
+            return [0, 0, 0, u8::MAX];
+        }
+
+        let hue = (id as f32 * 0.618_034).fract();
+        let value = if id % 2 == 0 { 1.0 } else { 0.7 };
+        ecolor::Color32::from(*Hsv::new(hue, 0.8, value)).to_array()
+    }
+
     pub fn get(&self, x: usize, y: usize) -> u32 {
+        let (width, height) = self.pick_buffer_staging.size;
+        if x >= width as usize || y >= height as usize {
+            return u32::MAX;
+        }
+
         self.pick_buffer_staging
             .map(D3D11_MAP_READ, |m| unsafe {
                 let data = m
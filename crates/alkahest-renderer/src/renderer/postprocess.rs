@@ -1,24 +1,48 @@
-use alkahest_data::technique::StateSelection;
+use alkahest_data::{geometry::EPrimitiveType, technique::StateSelection};
+use windows::Win32::Graphics::Direct3D11::{
+    ID3D11PixelShader, ID3D11RenderTargetView, ID3D11ShaderResourceView, ID3D11VertexShader,
+};
 
 use crate::{ecs::Scene, gpu_event, renderer::Renderer, tfx::externs};
 
 impl Renderer {
-    pub fn draw_postprocessing_pass(&self, _scene: &mut Scene) {
+    /// Effects that depend on the main camera (eg. auto-focus) are skipped when `main_view` is false
+    pub fn draw_postprocessing_pass(&self, _scene: &mut Scene, main_view: bool) {
         gpu_event!(self.gpu, "postprocess");
         unsafe {
             self.gpu.lock_context().OMSetRenderTargets(Some(&[]), None);
             self.gpu.lock_context().PSSetShaderResources(0, Some(&[]));
         }
 
+        let mut exposure = self.settings.exposure;
+        if self.settings.auto_exposure {
+            // Secondary views use the exposure the main view adapted to
+            exposure *= if main_view {
+                gpu_event!(self.gpu, "auto_exposure");
+                let adapted = self.auto_exposure.update(self);
+                self.auto_exposure.draw(self);
+                adapted
+            } else {
+                self.auto_exposure.current()
+            };
+        }
+
+        // Blurs the HDR shading result, before it's brought into the postprocess buffers
+        if self.settings.dof && main_view {
+            gpu_event!(self.gpu, "dof");
+            self.dof.draw(self);
+        }
+
+        if self.settings.bloom {
+            gpu_event!(self.gpu, "bloom");
+            self.bloom.draw(self);
+        }
+
         {
-            let data = &mut self.data.lock();
             // Ping, Pong
-            // Blits shading_result to pong
-            let (_source, target) = data.gbuffers.get_postprocess_rt(true);
-            self.gpu.blit_texture_alphaluminance(
-                &data.gbuffers.shading_result.view,
-                &target.render_target,
-            );
+            // Tonemaps shading_result to pong
+            gpu_event!(self.gpu, "tonemap");
+            self.tonemap.draw(self, exposure);
         }
 
         if self.settings.feature_fxaa {
@@ -52,6 +76,21 @@ impl Renderer {
             self.execute_global_pipeline(pipeline, "fxaa(_noise)");
         }
 
+        if self.settings.chromatic_aberration {
+            gpu_event!(self.gpu, "chromatic_aberration");
+            self.chromatic_aberration.draw(self);
+        }
+
+        if self.settings.vignette {
+            gpu_event!(self.gpu, "vignette");
+            self.vignette.draw(self);
+        }
+
+        if self.settings.grain > 0.0 {
+            gpu_event!(self.gpu, "grain");
+            self.grain.draw(self);
+        }
+
         {
             unsafe {
                 self.gpu.lock_context().OMSetRenderTargets(Some(&[]), None);
@@ -67,4 +106,62 @@ impl Renderer {
             );
         }
     }
+
+    /// Draws a fullscreen effect from the current postprocess buffer into the next one.
+    /// The source buffer is bound to t0, followed by `inputs`. Constant buffers have to be bound by the caller
+    pub(crate) fn draw_postprocess_effect(
+        &self,
+        shader_vs: &ID3D11VertexShader,
+        shader_ps: &ID3D11PixelShader,
+        inputs: &[Option<ID3D11ShaderResourceView>],
+    ) {
+        let (source, target) = {
+            let data = self.data.lock();
+            let (source, target) = data.gbuffers.get_postprocess_rt(true);
+            (source.view.clone(), target.render_target.clone())
+        };
+
+        self.draw_fullscreen_effect(shader_vs, shader_ps, &source, &target, inputs);
+    }
+
+    /// Draws a fullscreen effect from `source` into `target`.
+    /// The source is bound to t0, followed by `inputs`. Constant buffers have to be bound by the caller
+    pub(crate) fn draw_fullscreen_effect(
+        &self,
+        shader_vs: &ID3D11VertexShader,
+        shader_ps: &ID3D11PixelShader,
+        source: &ID3D11ShaderResourceView,
+        target: &ID3D11RenderTargetView,
+        inputs: &[Option<ID3D11ShaderResourceView>],
+    ) {
+        let mut resources = vec![Some(source.clone())];
+        resources.extend_from_slice(inputs);
+
+        unsafe {
+            self.gpu
+                .lock_context()
+                .OMSetRenderTargets(Some(&[Some(target.clone())]), None);
+            self.gpu
+                .lock_context()
+                .PSSetShaderResources(0, Some(resources.as_slice()));
+
+            self.gpu.set_blend_state(0);
+            self.gpu.lock_context().RSSetState(None);
+            self.gpu.set_input_topology(EPrimitiveType::Triangles);
+            self.gpu.lock_context().OMSetDepthStencilState(None, 0);
+            self.gpu.lock_context().VSSetShader(shader_vs, None);
+            self.gpu.lock_context().PSSetShader(shader_ps, None);
+
+            self.gpu.lock_context().Draw(3, 0);
+
+            // The target is the source of the next effect, so it can't stay bound
+            resources.fill(None);
+            self.gpu
+                .lock_context()
+                .PSSetShaderResources(0, Some(resources.as_slice()));
+            self.gpu.lock_context().OMSetRenderTargets(Some(&[]), None);
+        }
+
+        self.gpu.flush_states();
+    }
 }
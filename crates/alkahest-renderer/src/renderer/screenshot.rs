@@ -0,0 +1,155 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use alkahest_data::dxgi::DxgiFormat;
+use anyhow::Context;
+use png::{BitDepth, ColorType};
+use windows::Win32::Graphics::Direct3D11::D3D11_MAP_READ;
+
+use crate::{
+    gpu_event,
+    renderer::{gbuffer::CpuStagingBuffer, Renderer},
+    util::image::Png,
+};
+
+impl Renderer {
+    /// Saves the last rendered frame (before upscaling and UI) to a PNG file
+    ///
+    /// The shading result has no alpha channel, so when the background color is (partially) transparent, the alpha of
+    /// pixels without geometry is reconstructed from the depth buffer, the same way the background pass finds them.
+    ///
+    /// Stalls until the GPU has finished the current frame
+    pub fn capture_screenshot(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        gpu_event!(self.gpu, "capture_screenshot");
+        let data = self.data.lock();
+        let shading_result = &data.gbuffers.shading_result;
+        anyhow::ensure!(
+            shading_result.format == DxgiFormat::R11G11B10_FLOAT,
+            "Unsupported shading result format {:?}",
+            shading_result.format
+        );
+
+        let desc = shading_result.get_desc();
+        let (width, height) = (desc.Width as usize, desc.Height as usize);
+        let staging = CpuStagingBuffer::create(
+            (desc.Width, desc.Height),
+            shading_result.format,
+            self.gpu.clone(),
+            "Screenshot_Staging",
+        )?;
+        shading_result.copy_to_staging(&staging);
+
+        let background_alpha = self
+            .settings
+            .background_color
+            .map(|c| c.a())
+            .filter(|&a| a < 1.0);
+        let depth_staging = if background_alpha.is_some() {
+            let depth_staging = CpuStagingBuffer::create(
+                (desc.Width, desc.Height),
+                DxgiFormat::R32_TYPELESS,
+                self.gpu.clone(),
+                "Screenshot_Depth_Staging",
+            )?;
+            data.gbuffers.depth.copy_to_staging(&depth_staging);
+            Some(depth_staging)
+        } else {
+            None
+        };
+        drop(data);
+
+        // Reverse-Z, so anything at 0 is empty space
+        let empty_pixels = depth_staging
+            .map(|depth_staging| {
+                depth_staging.map(D3D11_MAP_READ, |m| {
+                    let mut empty = Vec::with_capacity(width * height);
+                    for y in 0..height {
+                        let row = unsafe {
+                            std::slice::from_raw_parts(
+                                (m.pData as *const u8).add(y * m.RowPitch as usize) as *const f32,
+                                width,
+                            )
+                        };
+                        empty.extend(row.iter().map(|&depth| depth == 0.0));
+                    }
+
+                    empty
+                })
+            })
+            .transpose()?;
+        let background_alpha =
+            (background_alpha.unwrap_or(1.0).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        let srgb = self.shading_result_is_linear();
+        let rgba = staging.map(D3D11_MAP_READ, |m| {
+            let mut rgba = Vec::with_capacity(width * height * 4);
+            // Rows can be padded, so RowPitch may be larger than width * 4
+            for y in 0..height {
+                let row = unsafe {
+                    std::slice::from_raw_parts(
+                        (m.pData as *const u8).add(y * m.RowPitch as usize) as *const u32,
+                        width,
+                    )
+                };
+
+                for (x, &packed) in row.iter().enumerate() {
+                    let [r, g, b] = unpack_r11g11b10(packed);
+                    for c in [r, g, b] {
+                        let c = if srgb { linear_to_srgb(c) } else { c };
+                        rgba.push((c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                    }
+
+                    let is_background = empty_pixels
+                        .as_ref()
+                        .is_some_and(|empty| empty[y * width + x]);
+                    rgba.push(if is_background {
+                        background_alpha
+                    } else {
+                        u8::MAX
+                    });
+                }
+            }
+
+            rgba
+        })?;
+
+        let png = Png {
+            data: rgba.into(),
+            dimensions: [width, height],
+            color_type: ColorType::Rgba,
+            bit_depth: BitDepth::Eight,
+        };
+
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create screenshot file {}", path.display()))?;
+        png.write(BufWriter::new(file))
+    }
+}
+
+fn unpack_r11g11b10(packed: u32) -> [f32; 3] {
+    [
+        unpack_small_float(packed & 0x7ff, 6),
+        unpack_small_float((packed >> 11) & 0x7ff, 6),
+        unpack_small_float(packed >> 22, 5),
+    ]
+}
+
+/// Decodes an unsigned float with a 5 bit exponent, as used by R11G11B10_FLOAT
+fn unpack_small_float(bits: u32, mantissa_bits: u32) -> f32 {
+    let exponent = bits >> mantissa_bits;
+    let mantissa = (bits & ((1 << mantissa_bits) - 1)) as f32 / (1 << mantissa_bits) as f32;
+    match exponent {
+        0 => mantissa * 2f32.powi(-14),
+        // Infinity and NaN, neither of which should end up in a screenshot
+        31 => 1.0,
+        e => (1.0 + mantissa) * 2f32.powi(e as i32 - 15),
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -11,13 +11,14 @@ use crate::{
     ecs::{
         render::light::{ShadowGenerationMode, ShadowMapRenderer},
         transform::Transform,
-        visibility::{ViewVisibility, VisibilityHelper},
+        visibility::{ViewVisibility, Visibility, VisibilityHelper},
         Scene,
     },
     gpu::DepthMode,
     gpu_event, gpu_profile_event,
     renderer::Renderer,
-    util::{black_magic::EntityRefDarkMagic, Hocus},
+    util::{black_magic::EntityRefDarkMagic, color::Hsv, Hocus},
+    Color,
 };
 
 impl Renderer {
@@ -26,6 +27,14 @@ impl Renderer {
             return;
         }
 
+        // Shadow maps from before the quality (or their resolution override) was changed are recreated at the new resolution before anything is drawn into them
+        for mut shadow in scene.query::<&mut ShadowMapRenderer>().iter_mut(scene) {
+            let resolution = shadow.target_resolution(self.settings.shadow_quality);
+            if shadow.resolution() != resolution {
+                shadow.resize(&self.gpu, resolution);
+            }
+        }
+
         self.gpu.set_depth_mode(DepthMode::Flipped);
 
         gpu_profile_event!(self.gpu, "update_shadow_maps");
@@ -61,7 +70,7 @@ impl Renderer {
                 .shadowmap_vs_t2
                 .bind(&self.gpu, 2, TfxShaderStage::Vertex);
 
-            self.bind_view(&*shadow, e.index() as usize);
+            self.bind_view(&*shadow, e.index() as usize, false);
 
             if shadow.stationary_needs_update {
                 self.pocus().active_shadow_generation_mode = ShadowGenerationMode::StationaryOnly;
@@ -81,6 +90,23 @@ impl Renderer {
 
         self.gpu.set_depth_mode(DepthMode::Normal);
     }
+
+    /// Outlines the frustum of every shadow map, each light in its own color
+    pub(super) fn draw_shadow_frusta(&self, scene: &mut Scene) {
+        for (e, shadow, visibility) in scene
+            .query::<(Entity, &ShadowMapRenderer, Option<&Visibility>)>()
+            .iter(scene)
+        {
+            if !visibility.is_visible(0) {
+                continue;
+            }
+
+            // Golden ratio hue steps keep the colors of lights with nearby indices apart
+            let color = Color::from(*Hsv::new((e.index() as f32 * 0.618_034).fract(), 0.8, 1.0));
+            self.immediate
+                .frustum(&shadow.frustum_corners(), color, 1.5);
+        }
+    }
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, PartialEq, strum::EnumIter, strum::Display)]
@@ -116,8 +142,19 @@ impl ShadowQuality {
     }
 }
 
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, strum::EnumIter)]
 pub enum ShadowPcfSamples {
     Samples13 = 0,
     Samples17 = 1,
     Samples21 = 2,
 }
+
+impl ShadowPcfSamples {
+    pub fn count(&self) -> usize {
+        match self {
+            ShadowPcfSamples::Samples13 => 13,
+            ShadowPcfSamples::Samples17 => 17,
+            ShadowPcfSamples::Samples21 => 21,
+        }
+    }
+}
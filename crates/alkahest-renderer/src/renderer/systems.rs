@@ -1,4 +1,4 @@
-use alkahest_data::tfx::TfxRenderStage;
+use alkahest_data::tfx::{TfxFeatureRenderer, TfxRenderStage};
 
 use crate::{
     ecs::{
@@ -10,19 +10,54 @@ use crate::{
         Scene,
     },
     gpu_event,
-    renderer::Renderer,
+    renderer::{RenderDebugView, Renderer},
     shader::shader_ball::draw_shaderball_system,
 };
 
 impl Renderer {
-    pub(super) fn run_renderstage_systems(&self, scene: &mut Scene, stage: TfxRenderStage) {
+    pub(crate) fn run_renderstage_systems(&self, scene: &mut Scene, stage: TfxRenderStage) {
         gpu_event!(self.gpu, stage.as_str());
 
-        draw_terrain_patches_system(self, scene, stage);
-        draw_shaderball_system(self, scene, stage);
+        // Shadow maps and the pickbuffer still need filled geometry, so only the main view is drawn as wireframe
+        let wireframe = self.settings.wireframe
+            && self.is_camera_view
+            && !self.pickbuffer.is_drawing_selection
+            && !self.pickbuffer.is_drawing_outline;
+        self.gpu.set_wireframe(wireframe);
 
-        draw_sky_objects_system(self, scene, stage);
-        draw_static_instances_system(self, scene, stage);
+        self.with_feature_color(stage, TfxFeatureRenderer::TerrainPatch, || {
+            draw_terrain_patches_system(self, scene, stage)
+        });
+        // The material ball isn't drawn by a game feature renderer, so it falls under "Other"
+        self.with_feature_color(stage, TfxFeatureRenderer::EditorMesh, || {
+            draw_shaderball_system(self, scene, stage)
+        });
+
+        self.with_feature_color(stage, TfxFeatureRenderer::SkyTransparent, || {
+            draw_sky_objects_system(self, scene, stage)
+        });
+        self.with_feature_color(stage, TfxFeatureRenderer::StaticObjects, || {
+            draw_static_instances_system(self, scene, stage)
+        });
         draw_dynamic_model_system(self, scene, stage);
+
+        self.gpu.set_wireframe(false);
+    }
+
+    /// Draws everything in `f` in the flat color of the given feature when the feature colors debug view is active.
+    /// Only the GBuffer is affected, as the debug view doesn't show anything else
+    pub(crate) fn with_feature_color(
+        &self,
+        stage: TfxRenderStage,
+        feature: TfxFeatureRenderer,
+        f: impl FnOnce(),
+    ) {
+        if self.settings.debug_view == RenderDebugView::FeatureColors
+            && stage == TfxRenderStage::GenerateGbuffer
+        {
+            self.feature_color.with_feature(self, feature, f);
+        } else {
+            f();
+        }
     }
 }
@@ -1,3 +1,5 @@
+use std::sync::atomic::Ordering;
+
 use alkahest_data::{
     technique::StateSelection,
     tfx::{TfxRenderStage, TfxShaderStage},
@@ -14,7 +16,7 @@ use crate::{
 };
 
 impl Renderer {
-    pub fn draw_transparents_pass(&self, scene: &mut Scene) {
+    pub fn draw_transparents_pass(&self, scenes: &mut [&mut Scene]) {
         gpu_profile_event!(self.gpu, "transparents_pass");
 
         {
@@ -113,11 +115,20 @@ impl Renderer {
             }
         }
 
+        // Techniques bind their own depth stencil states, which can still have depth writes enabled
+        self.gpu.force_readonly_depth.store(
+            self.settings.force_transparent_depth_readonly,
+            Ordering::Relaxed,
+        );
+        self.gpu.set_readonly_depth_pass(true);
+
         self.gpu
             .current_states
             .store(StateSelection::new(Some(8), Some(15), Some(2), Some(1)));
 
-        self.run_renderstage_systems(scene, TfxRenderStage::DecalsAdditive);
+        for scene in scenes.iter_mut() {
+            self.run_renderstage_systems(scene, TfxRenderStage::DecalsAdditive);
+        }
 
         {
             let gbuffers = &self.data.lock().gbuffers;
@@ -131,7 +142,11 @@ impl Renderer {
             .store(StateSelection::new(Some(8), Some(15), Some(2), Some(1)));
         self.render_globals.scopes.transparent.bind(self).unwrap();
 
-        self.run_renderstage_systems(scene, TfxRenderStage::Transparents);
+        for scene in scenes.iter_mut() {
+            self.run_renderstage_systems(scene, TfxRenderStage::Transparents);
+        }
+
+        self.gpu.set_readonly_depth_pass(false);
 
         // draw_utilities(self, scene);
     }
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use alkahest_data::{geometry::EPrimitiveType, tfx::TfxShaderStage};
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+    Color, ColorExt,
+};
+
+/// Fills every pixel that has no geometry with a solid color
+pub struct BackgroundRenderer {
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+
+    color_cb: ConstantBuffer<Vec4>,
+}
+
+impl BackgroundRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let color_cb = ConstantBuffer::create(gctx.clone(), None)?;
+
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/background.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/background.hlsl"))?;
+
+        Ok(Self {
+            shader_vs,
+            shader_ps,
+            color_cb,
+        })
+    }
+
+    /// Draws the background onto the currently bound render target. The depth buffer must not be bound as a render target.
+    /// If `linear` is false, the color is gamma-encoded to match the output of the final combine pass
+    pub fn draw(&self, renderer: &Renderer, color: Color, linear: bool) {
+        let color = if linear {
+            color.to_vec4()
+        } else {
+            Vec4::from(ecolor::Color32::from(color).to_normalized_gamma_f32())
+        };
+        self.color_cb.write(&color).unwrap();
+
+        unsafe {
+            let data = renderer.data.lock();
+            let ctx = renderer.gpu.lock_context();
+
+            self.color_cb.bind(0, TfxShaderStage::Pixel);
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.depth.texture_view.clone())]));
+
+            renderer.gpu.set_blend_state(0);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            // Unbind the depth buffer so it can be used as a depth target again
+            ctx.PSSetShaderResources(0, Some(&[None]));
+        }
+    }
+}
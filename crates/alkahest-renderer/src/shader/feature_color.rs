@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use alkahest_data::tfx::{TfxFeatureRenderer, TfxShaderStage};
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::ID3D11PixelShader;
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+    util::Hocus,
+    Color, ColorExt,
+};
+
+/// Replaces the materials of geometry with a flat color per feature renderer, for [`RenderDebugView::FeatureColors`](crate::renderer::RenderDebugView::FeatureColors)
+pub struct FeatureColorRenderer {
+    shader_ps: ID3D11PixelShader,
+    color_cb: ConstantBuffer<Vec4>,
+}
+
+impl FeatureColorRenderer {
+    /// Features with their own color, along with their name for the legend. Anything else is drawn in [`Self::OTHER_COLOR`]
+    pub const LEGEND: [(TfxFeatureRenderer, &'static str); 5] = [
+        (TfxFeatureRenderer::StaticObjects, "Statics"),
+        (TfxFeatureRenderer::DynamicObjects, "Dynamics"),
+        (TfxFeatureRenderer::TerrainPatch, "Terrain"),
+        (TfxFeatureRenderer::SpeedtreeTrees, "Decorators"),
+        (TfxFeatureRenderer::SkyTransparent, "Sky Objects"),
+    ];
+
+    pub const OTHER_COLOR: Color = Color::from_rgb(0.5, 0.5, 0.5);
+
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "debug/feature_color.hlsl"))?;
+
+        Ok(Self {
+            shader_ps,
+            color_cb: ConstantBuffer::create(gctx.clone(), None)?,
+        })
+    }
+
+    pub fn color(feature: TfxFeatureRenderer) -> Color {
+        match feature {
+            TfxFeatureRenderer::StaticObjects => Color::from_rgb(0.15, 0.45, 1.0),
+            TfxFeatureRenderer::RigidObject | TfxFeatureRenderer::DynamicObjects => {
+                Color::from_rgb(1.0, 0.45, 0.1)
+            }
+            TfxFeatureRenderer::TerrainPatch => Color::from_rgb(0.3, 0.75, 0.2),
+            TfxFeatureRenderer::SpeedtreeTrees => Color::from_rgb(0.85, 0.2, 0.75),
+            TfxFeatureRenderer::SkyTransparent => Color::from_rgb(0.35, 0.85, 0.95),
+            _ => Self::OTHER_COLOR,
+        }
+    }
+
+    /// Draws everything in `f` with the color of the given feature. Leaves the pixel shader alone if another pass
+    /// (eg. picking) has already replaced it
+    pub fn with_feature(&self, renderer: &Renderer, feature: TfxFeatureRenderer, f: impl FnOnce()) {
+        if renderer.gpu.custom_pixel_shader.is_some() {
+            f();
+            return;
+        }
+
+        self.color_cb
+            .write(&Self::color(feature).to_vec4())
+            .unwrap();
+        self.color_cb.bind(7, TfxShaderStage::Pixel);
+        *renderer.gpu.custom_pixel_shader.pocus() = Some(self.shader_ps.clone());
+        f();
+        *renderer.gpu.custom_pixel_shader.pocus() = None;
+    }
+}
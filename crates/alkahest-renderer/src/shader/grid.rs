@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use alkahest_data::{geometry::EPrimitiveType, tfx::TfxShaderStage};
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+};
+
+/// Draws a grid on the ground plane (Z=0) that fades out with distance, occluded by the scene
+pub struct GridRenderer {
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+
+    scope: ConstantBuffer<ScopeAlkahestGrid>,
+}
+
+impl GridRenderer {
+    /// Every n-th minor line is drawn as a major line
+    pub const MAJOR_LINE_INTERVAL: f32 = 10.0;
+
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let scope = ConstantBuffer::create(gctx.clone(), None)?;
+
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/grid.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/grid.hlsl"))?;
+
+        Ok(Self {
+            shader_vs,
+            shader_ps,
+            scope,
+        })
+    }
+
+    /// Draws the grid onto the currently bound render target, with lines every `spacing` meters.
+    /// The depth buffer must not be bound as a render target
+    pub fn draw(&self, renderer: &Renderer, spacing: f32) {
+        let minor_spacing = spacing.max(0.01);
+        self.scope
+            .write(&ScopeAlkahestGrid {
+                minor_spacing,
+                major_spacing: minor_spacing * Self::MAJOR_LINE_INTERVAL,
+                // Far enough to show a few major cells, without the lines turning into a haze at grazing angles
+                fade_distance: minor_spacing * 150.0,
+                opacity: 0.6,
+                minor_color: Vec4::new(0.6, 0.6, 0.6, 0.5),
+                major_color: Vec4::new(0.85, 0.85, 0.85, 1.0),
+            })
+            .unwrap();
+
+        unsafe {
+            let data = renderer.data.lock();
+            let ctx = renderer.gpu.lock_context();
+
+            self.scope.bind(0, TfxShaderStage::Pixel);
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.depth.texture_view.clone())]));
+
+            // Alpha blending
+            renderer.gpu.set_blend_state(12);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            // Unbind the depth buffer so it can be used as a depth target again
+            ctx.PSSetShaderResources(0, Some(&[None]));
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct ScopeAlkahestGrid {
+    minor_spacing: f32,
+    major_spacing: f32,
+    fade_distance: f32,
+    opacity: f32,
+    minor_color: Vec4,
+    major_color: Vec4,
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use ab_glyph::{Font, FontRef, PxScale};
+use alkahest_data::{dxgi::DxgiFormat, geometry::EPrimitiveType, tfx::TfxShaderStage};
+use anyhow::Context;
+use ecolor::Color32;
+use glam::{Vec3, Vec4};
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use windows::Win32::Graphics::Direct3D11::*;
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, texture::Texture, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+};
+
+const MAX_ICONS_PER_DRAW: usize = 256;
+
+/// An icon drawn as a camera facing quad at a point in the world
+#[derive(Clone)]
+pub struct IconBillboard {
+    pub position: Vec3,
+    pub icon: char,
+    pub color: Color32,
+    /// Size on screen, in pixels
+    pub size: f32,
+}
+
+/// Draws icon glyphs as depth-tested billboards, so they are hidden behind geometry like the rest of the scene.
+/// Glyphs are rasterized into an atlas from the icon font the first time they are used
+pub struct IconBillboardRenderer {
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+    /// Depth tested against the GBuffer depth, without writing to it
+    depth_state: ID3D11DepthStencilState,
+
+    icons_cb: ConstantBuffer<ScopeAlkahestIconBillboards>,
+    params_cb: ConstantBuffer<ScopeAlkahestIconBillboardParams>,
+
+    atlas: Mutex<GlyphAtlas>,
+    /// Billboards submitted for the next frame
+    queue: Mutex<Vec<IconBillboard>>,
+    gctx: Arc<GpuContext>,
+}
+
+impl IconBillboardRenderer {
+    /// Distance (in meters) icons are pulled towards the camera before depth testing
+    const DEPTH_OFFSET: f32 = 0.25;
+
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/icon_billboard.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/icon_billboard.hlsl"))?;
+
+        let mut depth_state = None;
+        unsafe {
+            gctx.device
+                .CreateDepthStencilState(
+                    &D3D11_DEPTH_STENCIL_DESC {
+                        DepthEnable: true.into(),
+                        DepthWriteMask: D3D11_DEPTH_WRITE_MASK_ZERO,
+                        // Reverse-Z
+                        DepthFunc: D3D11_COMPARISON_GREATER_EQUAL,
+                        StencilEnable: false.into(),
+                        ..Default::default()
+                    },
+                    Some(&mut depth_state),
+                )
+                .context("Failed to create icon billboard depth state")?
+        };
+
+        Ok(Self {
+            shader_vs,
+            shader_ps,
+            depth_state: depth_state.unwrap(),
+            icons_cb: ConstantBuffer::create(gctx.clone(), None)?,
+            params_cb: ConstantBuffer::create(gctx.clone(), None)?,
+            atlas: Mutex::new(GlyphAtlas::new()),
+            queue: Mutex::new(vec![]),
+            gctx,
+        })
+    }
+
+    /// Sets the font that icon glyphs are rasterized from
+    pub fn set_font(&self, data: &'static [u8]) -> anyhow::Result<()> {
+        let mut atlas = GlyphAtlas::new();
+        atlas.font = Some(FontRef::try_from_slice(data).context("Failed to parse icon font")?);
+        *self.atlas.lock() = atlas;
+        Ok(())
+    }
+
+    /// Queues billboards to be drawn by the next frame. Replaces anything that was submitted before
+    pub fn submit(&self, billboards: Vec<IconBillboard>) {
+        *self.queue.lock() = billboards;
+    }
+
+    /// Draws the submitted billboards onto `target`, depth tested against the GBuffer depth
+    pub fn draw(&self, renderer: &Renderer, target: &ID3D11RenderTargetView, linear_output: bool) {
+        let billboards = std::mem::take(&mut *self.queue.lock());
+        if billboards.is_empty() {
+            return;
+        }
+
+        let mut atlas = self.atlas.lock();
+        let instances = billboards
+            .iter()
+            .filter_map(|billboard| {
+                Some(IconInstance {
+                    position_size: billboard.position.extend(billboard.size),
+                    color: Vec4::from_array(billboard.color.to_normalized_gamma_f32()),
+                    uv_rect: atlas.glyph_uv(billboard.icon)?,
+                })
+            })
+            .collect::<Vec<_>>();
+        let Some(atlas_texture) = atlas.texture(&self.gctx) else {
+            return;
+        };
+        drop(atlas);
+
+        self.params_cb
+            .write(&ScopeAlkahestIconBillboardParams {
+                depth_offset: Self::DEPTH_OFFSET,
+                linear_output: if linear_output { 1.0 } else { 0.0 },
+                ..Default::default()
+            })
+            .unwrap();
+
+        let dxstate = renderer.gpu.backup_state();
+        unsafe {
+            let data = renderer.data.lock();
+            let ctx = renderer.gpu.lock_context();
+
+            ctx.OMSetRenderTargets(Some(&[Some(target.clone())]), &data.gbuffers.depth.view);
+            ctx.PSSetShaderResources(0, Some(&[Some(atlas_texture.view.clone())]));
+            ctx.PSSetSamplers(
+                0,
+                Some(&[Some(renderer.gpu.util_resources.linear_sampler.clone())]),
+            );
+
+            // Alpha blending
+            renderer.gpu.set_blend_state(12);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(Some(&self.depth_state), 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+            self.params_cb.bind(1, TfxShaderStage::Vertex);
+            self.params_cb.bind(1, TfxShaderStage::Pixel);
+
+            for chunk in instances.chunks(MAX_ICONS_PER_DRAW) {
+                let mut scope = ScopeAlkahestIconBillboards {
+                    icons: [IconInstance::default(); MAX_ICONS_PER_DRAW],
+                };
+                scope.icons[..chunk.len()].copy_from_slice(chunk);
+                self.icons_cb.write(&scope).unwrap();
+                self.icons_cb.bind(0, TfxShaderStage::Vertex);
+
+                ctx.Draw(chunk.len() as u32 * 6, 0);
+            }
+
+            ctx.PSSetShaderResources(0, Some(&[None]));
+        }
+        renderer.gpu.restore_state(&dxstate);
+    }
+}
+
+/// Single channel coverage atlas of the icon glyphs, laid out in a grid of equally sized cells
+struct GlyphAtlas {
+    font: Option<FontRef<'static>>,
+    pixels: Vec<u8>,
+    /// UV rectangle of each rasterized glyph, `None` for glyphs that aren't in the font
+    glyphs: FxHashMap<char, Option<Vec4>>,
+    texture: Option<Arc<Texture>>,
+    dirty: bool,
+}
+
+impl GlyphAtlas {
+    const SIZE: usize = 1024;
+    const CELL_SIZE: usize = 64;
+    const CELLS_PER_ROW: usize = Self::SIZE / Self::CELL_SIZE;
+    /// Glyphs are rasterized slightly smaller than a cell, so linear filtering doesn't bleed into neighbouring cells
+    const GLYPH_SIZE: f32 = 56.0;
+
+    fn new() -> Self {
+        Self {
+            font: None,
+            pixels: vec![0; Self::SIZE * Self::SIZE],
+            glyphs: FxHashMap::default(),
+            texture: None,
+            dirty: true,
+        }
+    }
+
+    /// UV rectangle of the given glyph, rasterizing it into the atlas if it's not in there yet
+    fn glyph_uv(&mut self, c: char) -> Option<Vec4> {
+        if let Some(uv) = self.glyphs.get(&c) {
+            return *uv;
+        }
+
+        let uv = self.rasterize(c);
+        self.glyphs.insert(c, uv);
+        uv
+    }
+
+    fn rasterize(&mut self, c: char) -> Option<Vec4> {
+        let font = self.font.as_ref()?;
+        let cell = self.glyphs.values().filter(|uv| uv.is_some()).count();
+        if cell >= Self::CELLS_PER_ROW * Self::CELLS_PER_ROW {
+            warn!("Icon atlas is full, can't add glyph U+{:04X}", c as u32);
+            return None;
+        }
+
+        let glyph_id = font.glyph_id(c);
+        if glyph_id.0 == 0 {
+            return None;
+        }
+
+        let outline = font.outline_glyph(glyph_id.with_scale(PxScale::from(Self::GLYPH_SIZE)))?;
+        let bounds = outline.px_bounds();
+        let cell_x = (cell % Self::CELLS_PER_ROW) * Self::CELL_SIZE;
+        let cell_y = (cell / Self::CELLS_PER_ROW) * Self::CELL_SIZE;
+        let offset_x = ((Self::CELL_SIZE as f32 - bounds.width()) / 2.0).max(0.0) as usize;
+        let offset_y = ((Self::CELL_SIZE as f32 - bounds.height()) / 2.0).max(0.0) as usize;
+
+        outline.draw(|x, y, coverage| {
+            let (x, y) = (offset_x + x as usize, offset_y + y as usize);
+            if x < Self::CELL_SIZE && y < Self::CELL_SIZE {
+                self.pixels[(cell_y + y) * Self::SIZE + cell_x + x] =
+                    (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        });
+        self.dirty = true;
+
+        let size = Self::SIZE as f32;
+        Some(Vec4::new(
+            cell_x as f32 / size,
+            cell_y as f32 / size,
+            (cell_x + Self::CELL_SIZE) as f32 / size,
+            (cell_y + Self::CELL_SIZE) as f32 / size,
+        ))
+    }
+
+    /// The atlas texture, recreated when glyphs have been added since it was last uploaded
+    fn texture(&mut self, gctx: &GpuContext) -> Option<Arc<Texture>> {
+        if self.dirty || self.texture.is_none() {
+            match Texture::load_2d_raw(
+                &gctx.device,
+                Self::SIZE as u32,
+                Self::SIZE as u32,
+                &self.pixels,
+                DxgiFormat::R8_UNORM,
+                Some("Icon_Atlas"),
+            ) {
+                Ok(texture) => self.texture = Some(Arc::new(texture)),
+                Err(e) => error!("Failed to upload icon atlas: {e:?}"),
+            }
+            self.dirty = false;
+        }
+
+        self.texture.clone()
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct IconInstance {
+    /// xyz: world position, w: size in pixels
+    position_size: Vec4,
+    color: Vec4,
+    /// Atlas UV rectangle (min xy, max xy)
+    uv_rect: Vec4,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct ScopeAlkahestIconBillboards {
+    icons: [IconInstance; MAX_ICONS_PER_DRAW],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct ScopeAlkahestIconBillboardParams {
+    depth_offset: f32,
+    linear_output: f32,
+    _pad: [f32; 2],
+}
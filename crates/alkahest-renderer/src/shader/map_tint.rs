@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use alkahest_data::{
+    geometry::EPrimitiveType,
+    technique::StateSelection,
+    tfx::{TfxRenderStage, TfxShaderStage},
+};
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    ecs::Scene,
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    gpu_event, include_dxbc,
+    renderer::Renderer,
+    util::{color::Hsv, Hocus},
+    Color, ColorExt,
+};
+
+const MAX_MAPS: usize = 8;
+
+/// Tints geometry by the map it belongs to and outlines the seams between maps, to check how map tiles line up
+pub struct MapTintRenderer {
+    id_ps: ID3D11PixelShader,
+    id_cb: ConstantBuffer<u32>,
+
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+    scope: ConstantBuffer<ScopeAlkahestMapTint>,
+}
+
+impl MapTintRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let id_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/map_tint_id.hlsl"))?;
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/map_tint.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/map_tint.hlsl"))?;
+
+        Ok(Self {
+            id_ps,
+            id_cb: ConstantBuffer::create(gctx.clone(), None)?,
+            shader_vs,
+            shader_ps,
+            scope: ConstantBuffer::create(gctx.clone(), None)?,
+        })
+    }
+
+    /// Color used for the map at the given index in the list of rendered scenes
+    pub fn color(index: usize) -> Color {
+        Color::from(*Hsv::new(
+            (index % MAX_MAPS) as f32 / MAX_MAPS as f32,
+            0.8,
+            1.0,
+        ))
+    }
+
+    /// Draws the map tint onto the currently bound render target.
+    /// Expects the depth buffer to contain the geometry of all the given scenes
+    pub fn draw(&self, renderer: &Renderer, scenes: &mut [&mut Scene]) {
+        let dxstate = renderer.gpu.backup_state();
+
+        {
+            gpu_event!(renderer.gpu, "map_ids");
+            let data = renderer.data.lock();
+            data.gbuffers.map_ids.clear(&[0.0, 0.0, 0.0, 0.0]);
+            unsafe {
+                renderer.gpu.lock_context().OMSetRenderTargets(
+                    Some(&[Some(data.gbuffers.map_ids.render_target.clone())]),
+                    &data.gbuffers.depth.view,
+                );
+            }
+        }
+
+        // Only geometry that made it into the depth buffer should be tagged
+        renderer
+            .gpu
+            .current_states
+            .store(StateSelection::new(Some(0), Some(15), Some(2), Some(0)));
+        renderer.gpu.flush_states();
+        *renderer.gpu.custom_pixel_shader.pocus() = Some(self.id_ps.clone());
+        for (i, scene) in scenes.iter_mut().enumerate() {
+            self.id_cb.write(&(i as u32 + 1)).unwrap();
+            self.id_cb.bind(7, TfxShaderStage::Pixel);
+            renderer.run_renderstage_systems(scene, TfxRenderStage::GenerateGbuffer);
+        }
+        *renderer.gpu.custom_pixel_shader.pocus() = None;
+        renderer.gpu.restore_state(&dxstate);
+
+        let mut scope = ScopeAlkahestMapTint {
+            opacity: 0.4,
+            ..Default::default()
+        };
+        for (i, color) in scope.colors.iter_mut().enumerate() {
+            *color = Self::color(i).to_vec4();
+        }
+        self.scope.write(&scope).unwrap();
+
+        unsafe {
+            let data = renderer.data.lock();
+            let ctx = renderer.gpu.lock_context();
+
+            self.scope.bind(0, TfxShaderStage::Pixel);
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.map_ids.view.clone())]));
+
+            // Alpha blending
+            renderer.gpu.set_blend_state(12);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            ctx.PSSetShaderResources(0, Some(&[None]));
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct ScopeAlkahestMapTint {
+    opacity: f32,
+    _pad: [f32; 3],
+    colors: [Vec4; MAX_MAPS],
+}
@@ -1,5 +1,14 @@
+pub mod background;
+pub mod feature_color;
+pub mod grid;
+pub mod icon_billboard;
+pub mod map_tint;
 pub mod matcap;
+pub mod msaa_resolve;
+pub mod near_fade;
 pub mod shader_ball;
+pub mod shadow_coverage;
+pub mod shadow_split;
 
 /// Relative to the assets directory
 /// Example: include_dxbc!(vs "shaders/test.hlsl") => 'assets/shaders/test.hlsl.vs.dxbc'
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use alkahest_data::geometry::EPrimitiveType;
+use anyhow::Context;
+use windows::Win32::Graphics::Direct3D11::*;
+
+use crate::{
+    gpu::{util::DxDeviceExt, GpuContext},
+    gpu_event, include_dxbc,
+    renderer::{
+        gbuffer::{GBuffer, MsaaTargets},
+        Renderer,
+    },
+};
+
+/// Resolves the multisampled G-buffer targets into the regular ones
+pub struct MsaaResolveRenderer {
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+    /// Depth is overwritten unconditionally
+    depth_state: ID3D11DepthStencilState,
+}
+
+impl MsaaResolveRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/msaa_resolve.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/msaa_resolve.hlsl"))?;
+
+        let mut depth_state = None;
+        unsafe {
+            gctx.device
+                .CreateDepthStencilState(
+                    &D3D11_DEPTH_STENCIL_DESC {
+                        DepthEnable: true.into(),
+                        DepthWriteMask: D3D11_DEPTH_WRITE_MASK_ALL,
+                        DepthFunc: D3D11_COMPARISON_ALWAYS,
+                        StencilEnable: false.into(),
+                        ..Default::default()
+                    },
+                    Some(&mut depth_state),
+                )
+                .context("Failed to create MSAA resolve depth state")?
+        };
+
+        Ok(Self {
+            shader_vs,
+            shader_ps,
+            depth_state: depth_state.unwrap(),
+        })
+    }
+
+    /// Resolves RT0-RT2 and the depth buffer, and binds the resolved targets.
+    /// RT0 and RT2 are averaged by the hardware resolve. Depth can't be averaged and keeps the closest sample instead,
+    /// and RT1 takes the first sample as its encoded normals can't be averaged either.
+    ///
+    /// Stencil is not carried over. The G-buffer depth is `D32_FLOAT`, which has no stencil plane, so the stencil
+    /// writes of G-buffer techniques are dropped with or without MSAA. A stencil format would need the stencil
+    /// resolved here as well
+    pub fn resolve(&self, renderer: &Renderer, gbuffers: &GBuffer, msaa: &MsaaTargets) {
+        gpu_event!(renderer.gpu, "msaa_resolve");
+        msaa.rt0.resolve_to(&gbuffers.rt0);
+        msaa.rt2.resolve_to(&gbuffers.rt2);
+
+        unsafe {
+            let ctx = renderer.gpu.lock_context();
+            ctx.OMSetRenderTargets(
+                Some(&[Some(gbuffers.rt1.render_target.clone())]),
+                &gbuffers.depth.view,
+            );
+            ctx.PSSetShaderResources(
+                0,
+                Some(&[
+                    Some(msaa.depth.texture_view.clone()),
+                    Some(msaa.rt1.view.clone()),
+                ]),
+            );
+
+            renderer.gpu.set_blend_state(0);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(Some(&self.depth_state), 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            ctx.PSSetShaderResources(0, Some(&[None, None]));
+            ctx.OMSetRenderTargets(
+                Some(&[
+                    Some(gbuffers.rt0.render_target.clone()),
+                    Some(gbuffers.rt1.render_target.clone()),
+                    Some(gbuffers.rt2.render_target.clone()),
+                ]),
+                &gbuffers.depth.view,
+            );
+        }
+
+        // Restore the states of the pass we're drawing in
+        renderer.gpu.flush_states();
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use alkahest_data::tfx::{TfxRenderStage, TfxShaderStage};
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::*;
+
+use crate::{
+    ecs::Scene,
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+    util::Hocus,
+};
+
+/// Dithers out geometry that's closer to the camera than a given distance, instead of letting it clip hard against the near plane
+pub struct NearFadeRenderer {
+    /// Replaces the pixel shader of the geometry during the depth prepass
+    depth_ps: ID3D11PixelShader,
+
+    fade_cb: ConstantBuffer<Vec4>,
+}
+
+impl NearFadeRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let fade_cb = ConstantBuffer::create(gctx.clone(), None)?;
+
+        let depth_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/near_fade.hlsl"))?;
+
+        Ok(Self { depth_ps, fade_cb })
+    }
+
+    /// Draws the depth of the GBuffer geometry into the currently bound depth buffer, leaving out the fragments that are faded out.
+    /// The GBuffer pass itself should be drawn with [`GpuContext::set_equal_depth_pass`] afterwards, so only the fragments
+    /// that made it into the depth buffer end up in the GBuffer
+    pub fn draw_depth(&self, renderer: &Renderer, scenes: &mut [&mut Scene], fade_distance: f32) {
+        self.fade_cb
+            .write(&Vec4::new(fade_distance.max(0.01), 0.0, 0.0, 0.0))
+            .unwrap();
+
+        let dxstate = renderer.gpu.backup_state();
+        unsafe {
+            renderer
+                .gpu
+                .lock_context()
+                .OMSetRenderTargets(None, dxstate.depth_stencil_view.as_ref());
+        }
+
+        self.fade_cb.bind(7, TfxShaderStage::Pixel);
+        renderer.gpu.bind_pixel_shader(&self.depth_ps);
+        *renderer.gpu.custom_pixel_shader.pocus() = Some(self.depth_ps.clone());
+        for scene in scenes.iter_mut() {
+            renderer.run_renderstage_systems(scene, TfxRenderStage::GenerateGbuffer);
+        }
+        *renderer.gpu.custom_pixel_shader.pocus() = None;
+
+        renderer.gpu.restore_state(&dxstate);
+        // The geometry might have left other states behind
+        renderer.gpu.flush_states();
+    }
+}
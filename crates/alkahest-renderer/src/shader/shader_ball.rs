@@ -9,15 +9,19 @@ use glam::{Mat4, Vec3, Vec4};
 use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
 
 use crate::{
+    camera::Camera,
     ecs::{
         transform::Transform,
         visibility::{ViewVisibility, VisibilityHelper},
         Scene,
     },
     gpu::{buffer::ConstantBuffer, util::DxDeviceExt},
-    gpu_event, include_dxbc,
+    gpu_event,
+    handle::Handle,
+    include_dxbc,
     loaders::vertex_buffer::VertexBuffer,
     renderer::Renderer,
+    tfx::technique::Technique,
 };
 
 #[repr(C)]
@@ -35,6 +39,8 @@ pub struct ShaderBallRenderer {
     // index_buffer: IndexBuffer,
     vshader: ID3D11VertexShader,
     pshader: ID3D11PixelShader,
+    /// Used instead of `vshader` when drawing with a game technique
+    technique_vshader: ID3D11VertexShader,
 
     cbuffer: ConstantBuffer<ShaderBallCbuffer>,
 }
@@ -73,6 +79,10 @@ impl ShaderBallRenderer {
             .gpu
             .device
             .load_pixel_shader(include_dxbc!(ps "misc/gbuffer_test.hlsl"))?;
+        let technique_vshader = renderer
+            .gpu
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/shader_ball_entity.hlsl"))?;
 
         Ok(Self {
             vertex_buffer: VertexBuffer::load_data(
@@ -82,6 +92,7 @@ impl ShaderBallRenderer {
             )?,
             vshader,
             pshader,
+            technique_vshader,
             cbuffer: ConstantBuffer::create(renderer.gpu.clone(), None)?,
         })
     }
@@ -97,9 +108,17 @@ pub struct ShaderBallComponent {
     pub metalness: f32,
     pub emission: f32,
     pub transmission: f32,
+
+    /// Game technique to draw the GBuffer pass with, instead of the material parameters above
+    pub technique: Option<Handle<Technique>>,
 }
 
 impl ShaderBallComponent {
+    /// Distance from the camera at which [`Self::position_in_front_of`] places the ball
+    const CAMERA_DISTANCE: f32 = 4.0;
+    /// Half the height of the ball model, which has its origin at the base
+    const HALF_HEIGHT: f32 = 1.35;
+
     pub fn new(renderer: &Renderer) -> anyhow::Result<Self> {
         Ok(Self {
             renderer: ShaderBallRenderer::new(renderer)?,
@@ -109,9 +128,15 @@ impl ShaderBallComponent {
             metalness: 0.0,
             emission: 0.0,
             transmission: 0.0,
+            technique: None,
         })
     }
 
+    /// Returns a position that puts the ball centered in view of the given camera
+    pub fn position_in_front_of(camera: &Camera) -> Vec3 {
+        camera.position() + camera.forward() * Self::CAMERA_DISTANCE - Vec3::Z * Self::HALF_HEIGHT
+    }
+
     pub fn draw(&self, renderer: &Renderer, transform: &Transform, render_stage: TfxRenderStage) {
         if !matches!(
             render_stage,
@@ -135,24 +160,42 @@ impl ShaderBallComponent {
             })
             .unwrap();
 
+        let technique = self
+            .technique
+            .as_ref()
+            .filter(|_| render_stage == TfxRenderStage::GenerateGbuffer)
+            .and_then(|t| renderer.get_technique_shared(t));
+
+        // The technique provides the pixel shader, its resources and the states. Only the vertex shader is
+        // replaced, as the ball mesh doesn't have the vertex inputs the game shaders expect
+        let vshader = if let Some(technique) = &technique {
+            if let Err(e) = technique.bind(renderer) {
+                error!("Failed to bind shader ball technique: {e:?}");
+                return;
+            }
+            &self.renderer.technique_vshader
+        } else {
+            renderer.gpu.set_blend_state(0);
+            renderer.gpu.set_depth_stencil_state(2);
+            renderer.gpu.set_rasterizer_state(2);
+            renderer.gpu.set_depth_bias(0);
+            &self.renderer.vshader
+        };
+
         renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
         renderer.gpu.set_input_layout(12);
         self.renderer.vertex_buffer.bind_single(&renderer.gpu, 0);
         self.renderer.cbuffer.bind(0, TfxShaderStage::Vertex);
-        self.renderer.cbuffer.bind(0, TfxShaderStage::Pixel);
-
-        renderer.gpu.set_blend_state(0);
-        renderer.gpu.set_depth_stencil_state(2);
-        renderer.gpu.set_rasterizer_state(2);
-        renderer.gpu.set_depth_bias(0);
+        if technique.is_none() {
+            self.renderer.cbuffer.bind(0, TfxShaderStage::Pixel);
+        }
 
         unsafe {
-            renderer
-                .gpu
-                .lock_context()
-                .VSSetShader(&self.renderer.vshader, None);
+            renderer.gpu.lock_context().VSSetShader(vshader, None);
 
-            if render_stage == TfxRenderStage::GenerateGbuffer {
+            if technique.is_some() {
+                // Already bound by the technique
+            } else if render_stage == TfxRenderStage::GenerateGbuffer {
                 renderer.gpu.bind_pixel_shader(&self.renderer.pshader);
             } else {
                 renderer.gpu.bind_pixel_shader(None);
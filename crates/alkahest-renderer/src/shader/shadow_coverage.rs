@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use alkahest_data::{geometry::EPrimitiveType, tfx::TfxShaderStage};
+use glam::Vec4;
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::{Renderer, ShadowQuality},
+    util::color::Hsv,
+    Color, ColorExt,
+};
+
+const MAX_CASCADES: usize = 4;
+/// Blend between logarithmic (1.0) and uniform (0.0) split distances
+const SPLIT_LAMBDA: f32 = 0.75;
+const SPLIT_NEAR: f32 = 0.5;
+const SPLIT_FAR: f32 = 250.0;
+
+/// Tints geometry by the shadow cascade that would sample it, based on its view depth
+pub struct ShadowCoverageRenderer {
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+
+    scope: ConstantBuffer<ScopeAlkahestShadowCoverage>,
+}
+
+impl ShadowCoverageRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let scope = ConstantBuffer::create(gctx.clone(), None)?;
+
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/shadow_coverage.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/shadow_coverage.hlsl"))?;
+
+        Ok(Self {
+            shader_vs,
+            shader_ps,
+            scope,
+        })
+    }
+
+    /// Color used for the cascade at the given index
+    pub fn color(index: usize) -> Color {
+        Color::from(*Hsv::new(index as f32 / MAX_CASCADES as f32, 0.8, 1.0))
+    }
+
+    pub fn cascade_count(quality: ShadowQuality) -> usize {
+        match quality {
+            ShadowQuality::Off => 0,
+            ShadowQuality::Lowest | ShadowQuality::Low => 2,
+            ShadowQuality::Medium => 3,
+            ShadowQuality::High | ShadowQuality::Highest => 4,
+        }
+    }
+
+    /// View depth at which each cascade ends, using the practical split scheme.
+    /// The renderer has no cascaded sun shadows of its own, so these are derived from the shadow quality alone
+    pub fn cascade_splits(count: usize) -> [f32; MAX_CASCADES] {
+        let mut splits = [0.0; MAX_CASCADES];
+        for (i, split) in splits.iter_mut().enumerate().take(count) {
+            let p = (i + 1) as f32 / count as f32;
+            let logarithmic = SPLIT_NEAR * (SPLIT_FAR / SPLIT_NEAR).powf(p);
+            let uniform = SPLIT_NEAR + (SPLIT_FAR - SPLIT_NEAR) * p;
+            *split = SPLIT_LAMBDA * logarithmic + (1.0 - SPLIT_LAMBDA) * uniform;
+        }
+
+        splits
+    }
+
+    /// Draws the cascade coverage onto the currently bound render target.
+    /// The depth buffer must not be bound as a render target
+    pub fn draw(&self, renderer: &Renderer) {
+        let cascade_count = Self::cascade_count(renderer.settings.shadow_quality);
+        if cascade_count == 0 {
+            return;
+        }
+
+        let mut scope = ScopeAlkahestShadowCoverage {
+            cascade_count: cascade_count as u32,
+            opacity: 0.35,
+            splits: Vec4::from_array(Self::cascade_splits(cascade_count)),
+            ..Default::default()
+        };
+        for (i, color) in scope.colors.iter_mut().enumerate() {
+            *color = Self::color(i).to_vec4();
+        }
+
+        self.scope.write(&scope).unwrap();
+
+        unsafe {
+            let data = renderer.data.lock();
+            let ctx = renderer.gpu.lock_context();
+
+            self.scope.bind(0, TfxShaderStage::Pixel);
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.depth.texture_view.clone())]));
+
+            // Alpha blending
+            renderer.gpu.set_blend_state(12);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            // Unbind the depth buffer so it can be used as a depth target again
+            ctx.PSSetShaderResources(0, Some(&[None]));
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct ScopeAlkahestShadowCoverage {
+    cascade_count: u32,
+    opacity: f32,
+    _pad: [f32; 2],
+    splits: Vec4,
+    colors: [Vec4; MAX_CASCADES],
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use alkahest_data::{geometry::EPrimitiveType, tfx::TfxShaderStage};
+use glam::{Mat4, Vec4};
+use windows::Win32::Graphics::Direct3D11::{ID3D11PixelShader, ID3D11VertexShader};
+
+use crate::{
+    ecs::{render::light::ShadowMapRenderer, Scene},
+    gpu::{buffer::ConstantBuffer, util::DxDeviceExt, GpuContext},
+    include_dxbc,
+    renderer::Renderer,
+    Color, ColorExt,
+};
+
+const MAX_SHADOW_MAPS: usize = 8;
+
+/// Tints shadowed geometry by whether the shadow comes from the cached stationary pass or the per-frame moving pass
+pub struct ShadowSplitRenderer {
+    shader_vs: ID3D11VertexShader,
+    shader_ps: ID3D11PixelShader,
+
+    scope: ConstantBuffer<ScopeAlkahestShadowSplit>,
+}
+
+impl ShadowSplitRenderer {
+    pub fn new(gctx: Arc<GpuContext>) -> anyhow::Result<Self> {
+        let scope = ConstantBuffer::create(gctx.clone(), None)?;
+
+        let shader_vs = gctx
+            .device
+            .load_vertex_shader(include_dxbc!(vs "misc/shadow_split.hlsl"))?;
+        let shader_ps = gctx
+            .device
+            .load_pixel_shader(include_dxbc!(ps "misc/shadow_split.hlsl"))?;
+
+        Ok(Self {
+            shader_vs,
+            shader_ps,
+            scope,
+        })
+    }
+
+    /// Draws the shadow split of the first few shadow maps in the scene onto the currently bound render target.
+    /// The depth buffer must not be bound as a render target
+    pub fn draw(&self, renderer: &Renderer, scene: &mut Scene) {
+        let mut scope = ScopeAlkahestShadowSplit {
+            opacity: 0.45,
+            // Blue for cached stationary shadows, orange for shadows of moving geometry
+            color_stationary: Color::from_rgb(0.2, 0.45, 1.0).to_vec4(),
+            color_moving: Color::from_rgb(1.0, 0.55, 0.1).to_vec4(),
+            ..Default::default()
+        };
+
+        let mut stationary_views = vec![None; MAX_SHADOW_MAPS];
+        let mut full_views = vec![None; MAX_SHADOW_MAPS];
+        for (i, shadow) in scene
+            .query::<&ShadowMapRenderer>()
+            .iter(scene)
+            .take(MAX_SHADOW_MAPS)
+            .enumerate()
+        {
+            scope.world_to_shadow[i] = shadow.world_to_projective();
+            scope.resolution[i] = Vec4::new(
+                shadow.resolution() as f32,
+                shadow.resolution() as f32,
+                0.0,
+                0.0,
+            );
+            stationary_views[i] = Some(shadow.depth_stationary().texture_view.clone());
+            full_views[i] = Some(shadow.depth().texture_view.clone());
+            scope.shadow_map_count += 1;
+        }
+
+        if scope.shadow_map_count == 0 {
+            return;
+        }
+
+        self.scope.write(&scope).unwrap();
+
+        unsafe {
+            let data = renderer.data.lock();
+            let ctx = renderer.gpu.lock_context();
+
+            self.scope.bind(0, TfxShaderStage::Pixel);
+            ctx.PSSetShaderResources(0, Some(&[Some(data.gbuffers.depth.texture_view.clone())]));
+            ctx.PSSetShaderResources(1, Some(stationary_views.as_slice()));
+            ctx.PSSetShaderResources(1 + MAX_SHADOW_MAPS as u32, Some(full_views.as_slice()));
+
+            // Alpha blending
+            renderer.gpu.set_blend_state(12);
+            ctx.RSSetState(None);
+            renderer.gpu.set_input_topology(EPrimitiveType::Triangles);
+            ctx.OMSetDepthStencilState(None, 0);
+            ctx.VSSetShader(&self.shader_vs, None);
+            ctx.PSSetShader(&self.shader_ps, None);
+
+            ctx.Draw(3, 0);
+
+            // Unbind the depth buffers so they can be used as depth targets again
+            ctx.PSSetShaderResources(0, Some(vec![None; 1 + MAX_SHADOW_MAPS * 2].as_slice()));
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct ScopeAlkahestShadowSplit {
+    shadow_map_count: u32,
+    opacity: f32,
+    _pad: [f32; 2],
+    world_to_shadow: [Mat4; MAX_SHADOW_MAPS],
+    resolution: [Vec4; MAX_SHADOW_MAPS],
+    color_stationary: Vec4,
+    color_moving: Vec4,
+}
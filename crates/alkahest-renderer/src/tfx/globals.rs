@@ -409,6 +409,14 @@ impl GlobalPipelines {
             RenderDebugView::ValidSourceColorSaturation => {
                 &self.debug_valid_source_color_saturation
             }
+
+            // Atmosphere lookups are blitted over the final combine by the renderer
+            RenderDebugView::AtmosphereFarLookup
+            | RenderDebugView::AtmosphereNearLookup
+            | RenderDebugView::AtmosphereDepthAngleDensity => &self.final_combine,
+
+            // Feature colors are written to the GBuffer in place of the material colors
+            RenderDebugView::FeatureColors => &self.debug_source_color,
         }
     }
 }
@@ -21,7 +21,7 @@ use crate::{
     handle::Handle,
     renderer::Renderer,
     tfx::bytecode::interpreter::TfxBytecodeInterpreter,
-    util::d3d::D3dResource,
+    util::{d3d::D3dResource, Hocus},
 };
 
 pub struct Technique {
@@ -219,6 +219,10 @@ pub struct TechniqueStage {
     // instead of in the same task as the technique loaderin order to avoid loading textures multiple times
     pub textures: Vec<(u32, Handle<Texture>)>,
     pub samplers: Vec<Option<ID3D11SamplerState>>,
+    /// Tags the samplers were loaded from, used to fetch them again when the material sampler settings change
+    pub sampler_hashes: Vec<TagHash>,
+    /// Generation of [`crate::gpu::sampler::MaterialSamplers`] the samplers were fetched at
+    pub sampler_generation: usize,
     pub shader_module: ShaderModule,
 
     pub cbuffer: Option<ConstantBufferCached<Vec4>>,
@@ -232,6 +236,17 @@ impl TechniqueStage {
         object_channels: Option<&ObjectChannels>,
     ) -> anyhow::Result<()> {
         self.shader_module.bind(&renderer.gpu);
+
+        let material_samplers = &renderer.gpu.material_samplers;
+        if self.sampler_generation != material_samplers.generation() {
+            self.pocus().samplers = self
+                .sampler_hashes
+                .iter()
+                .map(|&hash| material_samplers.get(hash))
+                .collect();
+            self.pocus().sampler_generation = material_samplers.generation();
+        }
+
         for (slot, tex) in &self.textures {
             if let Some(tex) = renderer.data.lock().asset_manager.textures.get_shared(tex) {
                 tex.bind(&renderer.gpu, *slot, self.stage);
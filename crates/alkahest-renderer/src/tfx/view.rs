@@ -48,6 +48,13 @@ impl RenderStageSubscriptions {
         self.contains(Self::from_bits_truncate(1 << stage as u32))
     }
 
+    /// Returns the render stages contained in these subscriptions, in stage order
+    pub fn stages(&self) -> impl Iterator<Item = TfxRenderStage> + '_ {
+        TfxRenderStage::VARIANTS
+            .into_iter()
+            .filter(|&stage| self.is_subscribed(stage))
+    }
+
     pub fn from_partrange_list(part_ranges: &[u16]) -> Self {
         let mut flags = Self::empty();
         for i in 0..24 {
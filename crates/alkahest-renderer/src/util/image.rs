@@ -1,5 +1,5 @@
 use std::{
-    io::{Cursor, Read, Seek},
+    io::{Cursor, Read, Seek, Write},
     sync::Arc,
 };
 
@@ -115,4 +115,17 @@ impl Png {
     //     writer.finish()?;
     //     Ok(result)
     // }
+
+    /// Encodes the image as a PNG file into the given writer
+    pub fn write<W: Write>(&self, writer: W) -> Result<()> {
+        let mut encoder =
+            png::Encoder::new(writer, self.dimensions[0] as u32, self.dimensions[1] as u32);
+        encoder.set_color(self.color_type);
+        encoder.set_depth(self.bit_depth);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        writer.finish()?;
+
+        Ok(())
+    }
 }
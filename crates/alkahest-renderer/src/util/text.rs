@@ -45,3 +45,21 @@ pub fn prettify_distance(meters: f32) -> String {
         format!("{:.2}", meters)
     }
 }
+
+/// Formats a byte count using binary units (B, KiB, MiB, GiB)
+pub fn prettify_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.2} {}", UNITS[unit])
+    }
+}
@@ -92,7 +92,7 @@ impl TestHarness {
         let adapter = GpuAdapter::create_headless().expect("Failed to create headless adapter");
         let gpu = GpuContext::create(&adapter).expect("Failed to create headless GPU context");
         let renderer =
-            Renderer::create(gpu, (4, 4), true).expect("Failed to create headless renderer");
+            Renderer::create(gpu, (4, 4), 1, true).expect("Failed to create headless renderer");
 
         Self { renderer }
     }
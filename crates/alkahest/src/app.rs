@@ -8,6 +8,7 @@ use alkahest_renderer::{
         new_scene,
         resources::SelectedEntity,
         tags::{NodeFilter, NodeFilterSet},
+        visibility::ViewVisibility,
         Scene,
     },
     gpu::{adapter::GpuAdapter, texture::LOW_RES, GpuContext},
@@ -20,6 +21,7 @@ use bevy_tasks::{ComputeTaskPool, TaskPool};
 use egui::{Key, KeyboardShortcut, Modifiers};
 use gilrs::{EventType, Gilrs};
 use glam::Vec2;
+use itertools::Itertools;
 use strum::IntoEnumIterator;
 use transform_gizmo_egui::{EnumSet, Gizmo, GizmoConfig, GizmoOrientation};
 use windows::core::HRESULT;
@@ -34,11 +36,13 @@ use crate::{
     config,
     gui::{
         activity_select::{get_map_name, set_activity, ActivityBrowser, CurrentActivity},
+        bookmarks::CameraBookmarks,
         console,
-        context::{GuiContext, GuiViewManager, HiddenWindows},
+        context::{GuiContext, GuiViewManager, HiddenWindows, ICON_FONT},
         gizmo::draw_transform_gizmos,
         hotkeys,
         inspector::FnvWordlist,
+        minimap::Minimap,
         updater::{ChannelSelector, UpdateDownload},
         SelectionGizmoMode,
     },
@@ -47,16 +51,16 @@ use crate::{
     updater::UpdateCheck,
     util::{
         action::{ActionBuffer, ActionList},
+        feature_capture::FeatureCapture,
+        frame_stats::{FrameStats, FrameStatsLogger},
         iron,
     },
     ApplicationArgs,
 };
 
+/// Fields are dropped in declaration order. Everything that holds GPU resources comes before the GPU context, and the
+/// window comes after it so the swapchain is released before the window it presents to.
 pub struct AlkahestApp {
-    pub window: Arc<winit::window::Window>,
-    pub event_loop: EventLoop<()>,
-
-    pub gctx: Arc<GpuContext>,
     pub gui: GuiContext,
     pub resources: AppResources,
 
@@ -70,6 +74,10 @@ pub struct AlkahestApp {
     updater_gui: Option<UpdateDownload>,
 
     next_config_save: std::time::Instant,
+
+    pub gctx: Arc<GpuContext>,
+    pub window: Arc<winit::window::Window>,
+    pub event_loop: EventLoop<()>,
 }
 
 impl AlkahestApp {
@@ -121,7 +129,12 @@ impl AlkahestApp {
         //     profiling::tracy_client::Client::start();
         // }
 
-        let adapter = GpuAdapter::create(&window).expect("Failed to create GpuAdapter");
+        let adapter = if config::with(|c| c.renderer.waitable_swapchain) {
+            GpuAdapter::create_waitable(&window)
+        } else {
+            GpuAdapter::create(&window)
+        }
+        .expect("Failed to create GpuAdapter");
         let gctx = GpuContext::create(&adapter).expect("Failed to create GpuContext");
         let gui = GuiContext::create(&window, gctx.clone());
         let mut resources = AppResources::default();
@@ -138,15 +151,24 @@ impl AlkahestApp {
         resources.insert(maps);
         resources.insert(SelectionGizmoMode::default());
         resources.insert(HiddenWindows::default());
+        resources.insert(Toast::default());
         resources.insert(ActionList::default());
         resources.insert(ActionBuffer::default());
+        resources.insert(FrameStatsLogger::default());
+        resources.insert(FeatureCapture::default());
+        resources.insert(Minimap::default());
+        resources.insert(CameraBookmarks::load());
         let renderer = Renderer::create(
             gctx.clone(),
             (window.inner_size().width, window.inner_size().height),
+            config::with(|c| c.renderer.asset_load_threads),
             false,
         )
         .unwrap();
         renderer.set_render_settings(config::with(|c| c.renderer.clone()));
+        if let Err(e) = renderer.icon_billboards.set_font(ICON_FONT) {
+            error!("Failed to load the icon font for billboards: {e:?}");
+        }
         resources.insert(renderer.clone());
         let stringmap = Arc::new(StringContainer::load_all_global());
         resources.insert(stringmap);
@@ -242,6 +264,8 @@ impl AlkahestApp {
         } = self;
 
         let mut active_gamepad = None;
+        let mut was_minimized = false;
+        let mut window_map_label = String::new();
 
         #[allow(deprecated)]
         event_loop.run_on_demand(move |event, target| {
@@ -285,48 +309,57 @@ impl AlkahestApp {
                         ..
                     } => {
                         if !egui_event_response.consumed {
-                            resources
-                                .get_mut::<Camera>()
-                                .update_mouse(Vec2::ZERO, scroll_y);
+                            let mut camera = resources.get_mut::<Camera>();
+                            if resources.get::<InputState>().ctrl() {
+                                camera.zoom(scroll_y);
+                            } else {
+                                camera.update_mouse(Vec2::ZERO, scroll_y);
+                            }
                         }
                     }
                     WindowEvent::Resized(new_dims) => {
                         let minimized = window.is_minimized().unwrap_or(false);
                         if !minimized && new_dims.width > 0 && new_dims.height > 0 {
-                            if let Some(swap_chain) = gctx.swap_chain.as_ref() {
-                                let _ = gui.renderer.as_mut().map(|renderer| {
-                                    let _ = renderer
-                                        .resize_buffers(swap_chain, || {
-                                            gctx.resize_swapchain(new_dims.width, new_dims.height);
-                                            HRESULT(0)
-                                        })
-                                        .unwrap();
-                                });
-                            }
-
-                            renderer.resize_buffers(new_dims.width, new_dims.height);
-
-                            resources.get_mut::<Camera>().set_viewport(Viewport {
-                                size: glam::UVec2::new(new_dims.width, new_dims.height),
-                                origin: glam::UVec2::ZERO,
-                            });
-
-                            config::with_mut(|c| {
-                                (c.window.width, c.window.height) =
-                                    (new_dims.width, new_dims.height)
-                            });
+                            Self::resize(gctx, gui, renderer, new_dims);
                         }
                     }
                     WindowEvent::RedrawRequested => {
+                        let window_size = window.inner_size();
+                        if window.is_minimized().unwrap_or(false)
+                            || window_size.width == 0
+                            || window_size.height == 0
+                        {
+                            // There's nothing to render to while minimized, so just keep pumping events until the window is restored
+                            was_minimized = true;
+                            std::thread::sleep(std::time::Duration::from_millis(50));
+                            window.request_redraw();
+                            return;
+                        }
+
+                        if std::mem::take(&mut was_minimized) {
+                            // Make sure we're not rendering to stale buffers if the resize event on restore didn't come through
+                            if gctx.swapchain_resolution.load()
+                                != (window_size.width, window_size.height)
+                            {
+                                Self::resize(gctx, gui, renderer, window_size);
+                            }
+                        }
+
                         if *next_config_save < std::time::Instant::now() {
                             config::try_persist().ok();
                             *next_config_save =
                                 std::time::Instant::now() + Self::CONFIG_SAVE_INTERVAL;
                         }
 
+                        gctx.wait_for_frame_latency();
+
                         resources.get_mut::<SelectedEntity>().changed_this_frame = false;
                         renderer.data.lock().asset_manager.poll();
 
+                        // Timestamp queries are only worth their overhead while something reads them
+                        gctx.set_timestamp_profiling(
+                            resources.get::<FrameStatsLogger>().is_running(),
+                        );
                         gctx.begin_frame();
 
                         {
@@ -357,6 +390,20 @@ impl AlkahestApp {
                                 action_list.process(resources);
                             }
 
+                            {
+                                // The render resolution changes with both the window size and render scale
+                                let (render_width, render_height) = renderer.render_resolution();
+                                let mut camera = resources.get_mut::<Camera>();
+                                if camera.viewport().size
+                                    != glam::UVec2::new(render_width, render_height)
+                                {
+                                    camera.set_viewport(Viewport {
+                                        size: glam::UVec2::new(render_width, render_height),
+                                        origin: glam::UVec2::ZERO,
+                                    });
+                                }
+                            }
+
                             resources
                                 .get_mut::<Camera>()
                                 .update(&resources.get::<InputState>(), renderer.delta_time as f32);
@@ -418,7 +465,7 @@ impl AlkahestApp {
                             let mut maps = resources.get_mut::<MapList>();
                             maps.update_maps(resources);
 
-                            if let Some(map) = maps.current_map_mut() {
+                            for map in maps.visible_maps_mut() {
                                 map.scene.run_system_once_with(
                                     resources.get::<RendererShared>().clone(),
                                     object_channels_discovery_system,
@@ -427,12 +474,68 @@ impl AlkahestApp {
                                 map.update();
                             }
 
-                            let scene = maps
-                                .current_map_mut()
+                            let map_label = maps.current_map_label();
+                            if map_label != window_map_label {
+                                window.set_title(&format!("Alkahest - {map_label}"));
+                                window_map_label = map_label;
+                            }
+
+                            let map_name = maps
+                                .current_map()
+                                .map(|m| m.name.clone())
+                                .unwrap_or_default();
+                            let mut scenes = maps
+                                .visible_maps_mut()
+                                .into_iter()
                                 .map(|m| &mut m.scene)
-                                .unwrap_or(scratch_map);
+                                .collect_vec();
+                            if scenes.is_empty() {
+                                scenes.push(scratch_map);
+                            }
+
+                            if resources.get::<HiddenWindows>().minimap {
+                                resources.get_mut::<Minimap>().render(
+                                    renderer,
+                                    &resources.get::<Camera>(),
+                                    &mut scenes,
+                                    resources,
+                                );
+                            }
 
-                            renderer.render_world(&*resources.get::<Camera>(), scene, resources);
+                            resources
+                                .get::<FeatureCapture>()
+                                .prepare_frame(renderer, &mut scenes);
+                            renderer.render_world(
+                                &*resources.get::<Camera>(),
+                                &mut scenes,
+                                resources,
+                            );
+                            resources
+                                .get_mut::<FeatureCapture>()
+                                .finish_frame(resources);
+
+                            let mut frame_stats = resources.get_mut::<FrameStatsLogger>();
+                            if frame_stats.is_running() {
+                                let stats = FrameStats {
+                                    frame_index: gctx.frame_index(),
+                                    cpu_frame_time: renderer.delta_time as f32,
+                                    draw_calls: gctx.draw_calls(),
+                                    visible_entities: scenes
+                                        .iter_mut()
+                                        .map(|scene| {
+                                            scene
+                                                .query::<&ViewVisibility>()
+                                                .iter(scene)
+                                                .filter(|v| v.is_visible(0))
+                                                .count()
+                                        })
+                                        .sum(),
+                                    camera_position: resources.get::<Camera>().position(),
+                                    map_name,
+                                };
+                                // GPU timings lag behind by a few frames, as they are only resolved once the GPU has finished them
+                                frame_stats.log(stats, gctx.take_frame_timings());
+                            }
                         }
 
                         unsafe {
@@ -500,7 +603,7 @@ impl AlkahestApp {
                                     let mut gui_views = resources.get_mut::<GuiViewManager>();
                                     gui_views.draw(ectx, window, resources, ctx);
 
-                                    if !gui_views.hide_views {
+                                    if gui_views.show_ui() {
                                         draw_transform_gizmos(renderer, ectx, resources);
                                     }
 
@@ -510,7 +613,10 @@ impl AlkahestApp {
                             });
 
                         window.pre_present_notify();
-                        gctx.present(config::with(|c| c.renderer.vsync));
+                        let (vsync, max_frame_latency) =
+                            config::with(|c| (c.renderer.vsync, c.renderer.max_frame_latency));
+                        gctx.set_max_frame_latency(max_frame_latency);
+                        gctx.present(vsync);
 
                         window.request_redraw();
                         #[cfg(feature = "profiler")]
@@ -550,6 +656,31 @@ impl AlkahestApp {
     }
 }
 
+impl AlkahestApp {
+    /// Resizes the swapchain and render targets to the new window size
+    fn resize(
+        gctx: &GpuContext,
+        gui: &mut GuiContext,
+        renderer: &RendererShared,
+        new_dims: PhysicalSize<u32>,
+    ) {
+        if let Some(swap_chain) = gctx.swap_chain.as_ref() {
+            let _ = gui.renderer.as_mut().map(|renderer| {
+                let _ = renderer
+                    .resize_buffers(swap_chain, || {
+                        gctx.resize_swapchain(new_dims.width, new_dims.height);
+                        HRESULT(0)
+                    })
+                    .unwrap();
+            });
+        }
+
+        renderer.resize_buffers(new_dims.width, new_dims.height);
+
+        config::with_mut(|c| (c.window.width, c.window.height) = (new_dims.width, new_dims.height));
+    }
+}
+
 impl Drop for AlkahestApp {
     fn drop(&mut self) {
         config::persist();
@@ -1,4 +1,7 @@
-use alkahest_renderer::{ecs::tags::NodeFilter, renderer::RendererSettings};
+use alkahest_renderer::{
+    ecs::{common::ResourceOrigin, tags::NodeFilter},
+    renderer::RendererSettings,
+};
 use egui::ahash::HashSet;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
@@ -79,6 +82,36 @@ pub struct VisualSettings {
     pub node_nametags: bool,
     pub node_nametags_named_only: bool,
     pub node_filters: HashSet<String>,
+    /// Origins of the nodes to show, by name
+    pub node_origins: HashSet<String>,
+    /// Scale of the node icons and the markers drawn around them
+    pub node_icon_scale: f32,
+    /// Font size of the node labels
+    pub node_label_size: f32,
+    /// Group nodes that overlap on screen into a single marker
+    pub node_clustering: bool,
+    /// Distance in pixels within which nodes are clustered
+    pub node_cluster_radius: f32,
+    /// Move overlapping labels apart, connecting them to their icon with a line
+    pub node_label_declutter: bool,
+    /// Draw node icons as billboards in the scene, so they are hidden behind geometry
+    pub node_icon_billboards: bool,
+    /// Show the camera position/orientation in the bottom bar
+    pub status_bar_camera: bool,
+    /// Distance from the surface the camera is placed at when snapping to it
+    pub surface_snap_offset: f32,
+    /// Move the camera to overlook the map once it has loaded
+    pub camera_spawn_on_load: bool,
+    /// Keep the camera position, speed and zoom when switching maps, instead of moving it to overlook the new map
+    pub keep_camera_on_map_switch: bool,
+    /// Unload the previous map once the new one has loaded when switching maps, freeing the assets only it used
+    pub unload_previous_map: bool,
+    /// Fade out overlays (FPS counter, etc.) after a period without any input
+    pub overlay_auto_hide: bool,
+    /// Seconds of inactivity before overlays start fading out
+    pub overlay_auto_hide_timeout: f32,
+    /// Also hide the panels and menu bar once the overlays have faded out
+    pub overlay_auto_hide_panels: bool,
 }
 
 impl Default for VisualSettings {
@@ -103,6 +136,21 @@ impl Default for VisualSettings {
                     }
                 })
                 .collect(),
+            node_origins: ResourceOrigin::iter().map(|o| o.to_string()).collect(),
+            node_icon_scale: 1.0,
+            node_label_size: 14.0,
+            node_clustering: false,
+            node_cluster_radius: 24.0,
+            node_label_declutter: false,
+            node_icon_billboards: false,
+            status_bar_camera: true,
+            surface_snap_offset: 0.5,
+            camera_spawn_on_load: true,
+            keep_camera_on_map_switch: true,
+            unload_previous_map: true,
+            overlay_auto_hide: false,
+            overlay_auto_hide_timeout: 5.0,
+            overlay_auto_hide_panels: false,
         }
     }
 }
@@ -0,0 +1,104 @@
+use alkahest_renderer::{
+    handle::AssetRegistryStats, renderer::RendererShared, util::text::prettify_bytes,
+};
+use winit::window::Window;
+
+use crate::{
+    gui::{
+        context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+        toast::Toast,
+    },
+    resources::AppResources,
+};
+
+/// Shows the contents of the asset manager cache, and allows dropping assets that aren't used by any loaded map
+#[derive(Default)]
+pub struct AssetCachePanel;
+
+impl GuiView for AssetCachePanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut open = resources.get::<HiddenWindows>().asset_cache;
+        if !open {
+            return None;
+        }
+
+        let renderer = resources.get::<RendererShared>().clone();
+        let stats = renderer.data.lock().asset_manager.stats();
+        let mut purge = false;
+        egui::Window::new("Asset Cache")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let registries = [
+                    ("Textures", stats.textures),
+                    ("Techniques", stats.techniques),
+                    ("Vertex Buffers", stats.vertex_buffers),
+                    ("Index Buffers", stats.index_buffers),
+                ];
+
+                let mut total = AssetRegistryStats::default();
+                egui::Grid::new("asset_cache")
+                    .striped(true)
+                    .num_columns(4)
+                    .show(ui, |ui| {
+                        ui.strong("Kind");
+                        ui.strong("Cached");
+                        ui.strong("Loaded");
+                        ui.strong("Size");
+                        ui.end_row();
+
+                        for (name, registry) in registries {
+                            ui.label(name);
+                            ui.label(registry.count.to_string());
+                            ui.label(registry.loaded.to_string());
+                            ui.label(prettify_bytes(registry.size));
+                            ui.end_row();
+
+                            total.count += registry.count;
+                            total.loaded += registry.loaded;
+                            total.size += registry.size;
+                        }
+
+                        ui.strong("Total");
+                        ui.strong(total.count.to_string());
+                        ui.strong(total.loaded.to_string());
+                        ui.strong(prettify_bytes(total.size));
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                purge = ui
+                    .button("Purge Unused")
+                    .on_hover_text(
+                        "Drops every cached asset that is no longer referenced by any loaded map",
+                    )
+                    .clicked();
+            });
+
+        if purge {
+            let assets_removed = renderer.data.lock().asset_manager.purge_unused();
+            let size_freed = stats
+                .total_size()
+                .saturating_sub(renderer.data.lock().asset_manager.stats().total_size());
+
+            info!(
+                "Purged {assets_removed} assets ({})",
+                prettify_bytes(size_freed)
+            );
+            resources.get_mut::<Toast>().show(format!(
+                "Purged {assets_removed} assets ({})",
+                prettify_bytes(size_freed)
+            ));
+        }
+
+        resources.get_mut::<HiddenWindows>().asset_cache = open;
+
+        None
+    }
+}
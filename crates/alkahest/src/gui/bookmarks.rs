@@ -0,0 +1,219 @@
+use alkahest_renderer::{
+    camera::{tween::ease_out_exponential, Camera, CameraProjection},
+    icons::{ICON_DELETE, ICON_PLUS},
+    resources::AppResources,
+};
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use winit::window::Window;
+
+use crate::{
+    gui::{
+        context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+        toast::Toast,
+    },
+    paths,
+    util::action::{ActionList, TweenAction},
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub position: [f32; 3],
+    /// Pitch and yaw, in degrees
+    pub orientation: [f32; 2],
+    /// Field of view in degrees
+    pub fov: f32,
+}
+
+/// Named camera positions, persisted to `bookmarks.ron` in the config directory
+#[derive(Default)]
+pub struct CameraBookmarks {
+    pub bookmarks: Vec<CameraBookmark>,
+    /// Index of the bookmark that was jumped to last, used for cycling
+    current: Option<usize>,
+}
+
+impl CameraBookmarks {
+    const FILE_NAME: &'static str = "bookmarks.ron";
+
+    pub fn load() -> Self {
+        let bookmarks = match std::fs::read_to_string(paths::config_dir().join(Self::FILE_NAME)) {
+            Ok(data) => ron::from_str(&data).unwrap_or_else(|e| {
+                error!("Failed to parse camera bookmarks: {e}");
+                vec![]
+            }),
+            Err(_) => vec![],
+        };
+
+        Self {
+            bookmarks,
+            current: None,
+        }
+    }
+
+    pub fn persist(&self) {
+        let result = ron::ser::to_string_pretty(&self.bookmarks, Default::default())
+            .map_err(anyhow::Error::from)
+            .and_then(|data| {
+                std::fs::write(paths::config_dir().join(Self::FILE_NAME), data)
+                    .map_err(anyhow::Error::from)
+            });
+
+        if let Err(e) = result {
+            error!("Failed to write camera bookmarks: {e}");
+        }
+    }
+
+    pub fn add(&mut self, name: String, camera: &Camera) {
+        let position = camera.position();
+        let orientation = camera.view_angle();
+        self.bookmarks.push(CameraBookmark {
+            name,
+            position: position.to_array(),
+            orientation: orientation.to_array(),
+            fov: camera.fov(),
+        });
+        self.persist();
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.bookmarks.len() {
+            self.bookmarks.remove(index);
+            self.current = None;
+            self.persist();
+        }
+    }
+
+    /// Eases the camera to the bookmark at the given index
+    pub fn goto(&mut self, index: usize, resources: &AppResources) {
+        let Some(bookmark) = self.bookmarks.get(index).cloned() else {
+            return;
+        };
+        self.current = Some(index);
+
+        let mut camera = resources.get_mut::<Camera>();
+        // The tween only covers position and orientation, so the FOV is applied straight away
+        if let CameraProjection::Perspective { fov, .. } = &mut camera.projection {
+            *fov = bookmark.fov;
+        }
+
+        // Turn the shortest way around, the yaw isn't wrapped
+        let start_angle = camera.view_angle();
+        let mut target_angle = Vec2::from_array(bookmark.orientation);
+        target_angle.y =
+            start_angle.y + (target_angle.y - start_angle.y + 180.0).rem_euclid(360.0) - 180.0;
+
+        let mut action_list = resources.get_mut::<ActionList>();
+        // Avoid potential weird interactions with routes
+        action_list.clear_actions();
+        action_list.add_action(TweenAction::new(
+            ease_out_exponential,
+            Some((camera.position(), Vec3::from_array(bookmark.position))),
+            Some((start_angle, target_angle)),
+            0.7,
+        ));
+    }
+
+    /// Jumps to the bookmark after the one that was jumped to last
+    pub fn goto_next(&mut self, resources: &AppResources) {
+        if self.bookmarks.is_empty() {
+            resources
+                .get_mut::<Toast>()
+                .show("No camera bookmarks, add one from the bookmarks window first");
+            return;
+        }
+
+        let index = self.current.map_or(0, |i| (i + 1) % self.bookmarks.len());
+        self.goto(index, resources);
+        resources.get_mut::<Toast>().show(format!(
+            "Bookmark {}/{}: {}",
+            index + 1,
+            self.bookmarks.len(),
+            self.bookmarks[index].name
+        ));
+    }
+}
+
+#[derive(Default)]
+pub struct CameraBookmarksPanel {
+    name: String,
+}
+
+impl GuiView for CameraBookmarksPanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut windows = resources.get_mut::<HiddenWindows>();
+        let mut bookmarks = resources.get_mut::<CameraBookmarks>();
+        let mut goto = None;
+        let mut remove = None;
+        egui::Window::new("Camera Bookmarks")
+            .open(&mut windows.camera_bookmarks)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.name)
+                            .hint_text(format!("Bookmark {}", bookmarks.bookmarks.len() + 1))
+                            .desired_width(200.0),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button(format!("{ICON_PLUS} Add")).clicked() || submitted {
+                        let name = if self.name.trim().is_empty() {
+                            format!("Bookmark {}", bookmarks.bookmarks.len() + 1)
+                        } else {
+                            std::mem::take(&mut self.name)
+                        };
+                        bookmarks.add(name, &resources.get::<Camera>());
+                    }
+                });
+
+                ui.separator();
+                if bookmarks.bookmarks.is_empty() {
+                    ui.weak("No bookmarks yet");
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for (i, bookmark) in bookmarks.bookmarks.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.button(ICON_DELETE.to_string()).clicked() {
+                                    remove = Some(i);
+                                }
+
+                                let [x, y, z] = bookmark.position;
+                                if ui
+                                    .selectable_label(
+                                        bookmarks.current == Some(i),
+                                        bookmark.name.as_str(),
+                                    )
+                                    .on_hover_text(format!(
+                                        "XYZ: {x:.2} / {y:.2} / {z:.2}\nFOV: {:.1}°",
+                                        bookmark.fov
+                                    ))
+                                    .clicked()
+                                {
+                                    goto = Some(i);
+                                }
+                            });
+                        }
+                    });
+            });
+
+        if let Some(index) = remove {
+            bookmarks.remove(index);
+        }
+
+        if let Some(index) = goto {
+            bookmarks.goto(index, resources);
+        }
+
+        None
+    }
+}
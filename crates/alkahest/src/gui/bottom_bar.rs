@@ -1,14 +1,17 @@
 use std::time::Instant;
 
+use alkahest_renderer::camera::Camera;
 use egui::{Color32, Context, RichText};
 use once_cell::sync::Lazy;
 use winit::window::Window;
 
 use crate::{
+    config,
     gui::{
-        context::{GuiCtx, GuiView, ViewAction},
+        context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
         icons,
         icons::{ICON_ALERT_CIRCLE_OUTLINE, ICON_CHECK_CIRCLE, ICON_CIRCLE, ICON_CIRCLE_OUTLINE},
+        load_indicator::BackgroundTasks,
     },
     maplist::{MapList, MapLoadState},
     resources::AppResources,
@@ -25,22 +28,24 @@ impl GuiView for BottomBar {
         _gui: &GuiCtx<'_>,
     ) -> Option<ViewAction> {
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
-            let mut maplist = resources.get_mut::<MapList>();
-            if !maplist.maps.is_empty() {
-                let mut current_map = maplist.current_map_index();
-
-                let amount_loaded = maplist.count_loaded();
-                let combo_postlabel = if maplist.count_loading() != 0 {
-                    format!(
-                        "({} loading {}/{})",
-                        LoadingIcon::Circle.get_frame(),
-                        amount_loaded + 1,
-                        maplist.maps.len()
-                    )
-                } else {
-                    "".to_string()
-                };
-                ui.horizontal(|ui| {
+            ui.horizontal(|ui| {
+                let tasks = BackgroundTasks::collect(resources);
+                let mut maplist = resources.get_mut::<MapList>();
+                if !maplist.maps.is_empty() {
+                    let mut current_map = maplist.current_map_index();
+
+                    let amount_loaded = maplist.count_loaded();
+                    let combo_postlabel = if maplist.count_loading() != 0 {
+                        format!(
+                            "({} loading {}/{})",
+                            LoadingIcon::Circle.get_frame(),
+                            amount_loaded + 1,
+                            maplist.maps.len()
+                        )
+                    } else {
+                        "".to_string()
+                    };
+
                     let map_changed = egui::ComboBox::from_label("Map")
                         .width(192.0)
                         .show_index(ui, &mut current_map, maplist.maps.len(), |i| {
@@ -64,15 +69,84 @@ impl GuiView for BottomBar {
                         })
                         .changed();
 
+                    if let Some(map) = maplist.current_map() {
+                        ui.label(RichText::new(map.hash.to_string()).weak());
+                    }
                     ui.label(combo_postlabel);
 
                     ui.checkbox(&mut maplist.load_all_maps, "Load all maps");
 
+                    let current_map_index = maplist.current_map_index();
+                    ui.menu_button(
+                        format!("Active maps ({})", maplist.count_active()),
+                        |ui| {
+                            ui.label("Active maps are rendered alongside the current map");
+                            egui::ScrollArea::vertical()
+                                .max_height(320.0)
+                                .show(ui, |ui| {
+                                    for (i, map) in maplist.maps.iter_mut().enumerate() {
+                                        let is_current = i == current_map_index;
+                                        ui.add_enabled_ui(!is_current, |ui| {
+                                            let mut active = map.active || is_current;
+                                            if ui.checkbox(&mut active, &map.name).changed() {
+                                                map.active = active;
+                                            }
+                                        });
+                                    }
+                                });
+
+                            if ui.button("Clear").clicked() {
+                                for map in maplist.maps.iter_mut() {
+                                    map.active = false;
+                                }
+                            }
+                        },
+                    );
+
                     if map_changed {
                         maplist.set_current_map(current_map);
                     }
+                } else {
+                    ui.label(maplist.current_map_label());
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if let Some(phase) = tasks.phase() {
+                        let response = ui
+                            .add(
+                                egui::Label::new(format!(
+                                    "{} {phase} ({})",
+                                    LoadingIcon::Circle.get_frame(),
+                                    tasks.count()
+                                ))
+                                .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text("Click to show details");
+
+                        if response.clicked() {
+                            resources.get_mut::<HiddenWindows>().background_tasks ^= true;
+                        }
+                    }
+
+                    if config::with(|c| c.visual.status_bar_camera) {
+                        let camera = resources.get::<Camera>();
+                        let position = camera.position();
+                        let orientation = camera.orientation();
+                        ui.label(
+                            RichText::new(format!(
+                                "XYZ {:.2} / {:.2} / {:.2}   Yaw {:.1}° Pitch {:.1}°   Speed {:.2}x",
+                                position.x,
+                                position.y,
+                                position.z,
+                                orientation.y,
+                                orientation.x,
+                                camera.speed_mul
+                            ))
+                            .monospace(),
+                        );
+                    }
                 });
-            }
+            });
         });
 
         None
@@ -1,19 +1,31 @@
+use alkahest_data::occlusion::Aabb;
 use alkahest_renderer::{
-    camera::{Camera, CameraProjection},
-    ecs::tags::{NodeFilter, NodeFilterSet},
+    camera::{orbit::GazeFocusMode, Camera, CameraProjection},
+    ecs::{
+        common::ResourceOrigin,
+        resources::SelectedEntity,
+        tags::{NodeFilter, NodeFilterSet},
+        transform::Transform,
+    },
     icons::{ICON_CLIPBOARD, ICON_CURSOR_DEFAULT, ICON_EYE},
-    renderer::{RenderDebugView, RenderFeatureVisibility, RendererShared, ShadowQuality},
+    renderer::{
+        Anisotropy, RenderDebugView, RenderFeatureVisibility, RenderPreset, RendererSettings,
+        RendererShared, ShadowPcfSamples, ShadowQuality, ToneMapping, UpscaleFilter,
+    },
     util::text::StringExt,
+    Color,
 };
 use egui::{Context, CornerRadius, RichText, Widget};
+use glam::Vec3;
+use rustc_hash::FxHashMap;
 use strum::IntoEnumIterator;
 use transform_gizmo_egui::{EnumSet, GizmoMode};
 use winit::window::Window;
 
-use super::console;
 use crate::{
     config,
     gui::context::{GuiCtx, GuiView, ViewAction},
+    maplist::MapList,
     resources::AppResources,
 };
 
@@ -70,16 +82,44 @@ impl GuiView for RenderSettingsPanel {
                 ui.label("Speed");
             });
 
-            if let CameraProjection::Perspective { fov, .. } = &mut camera.projection {
-                ui.horizontal(|ui| {
-                    egui::DragValue::new(fov)
-                        .range(5f32..=120.0)
-                        .speed(0.05)
-                        .ui(ui);
-                    ui.label("FOV");
+            let mut orthographic = camera.projection.is_orthographic();
+            if ui
+                .checkbox(&mut orthographic, "Orthographic")
+                .on_hover_text("Useful for top-down and side captures of a map")
+                .changed()
+            {
+                camera.set_projection(if orthographic {
+                    CameraProjection::orthographic_sized(64.0)
+                } else {
+                    CameraProjection::perspective(90.0, 0.01)
                 });
             }
 
+            match &mut camera.projection {
+                CameraProjection::Perspective { fov, .. } => {
+                    ui.horizontal(|ui| {
+                        egui::DragValue::new(fov)
+                            .range(5f32..=120.0)
+                            .speed(0.05)
+                            .ui(ui);
+                        ui.label("FOV");
+                    });
+                }
+                CameraProjection::OrthographicSized { size } => {
+                    ui.horizontal(|ui| {
+                        egui::DragValue::new(size)
+                            .range(1f32..=4096.0)
+                            .speed(0.5)
+                            .suffix("m")
+                            .ui(ui);
+                        ui.label("Size");
+                    })
+                    .response
+                    .on_hover_text("Half the height of the view");
+                }
+                _ => {}
+            }
+
             ui.horizontal(|ui| {
                 egui::DragValue::new(&mut camera.smooth_movement)
                     .range(0f32..=5.0)
@@ -96,38 +136,399 @@ impl GuiView for RenderSettingsPanel {
                 ui.label("Smooth look");
             });
 
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut camera.fov_kick, "FOV kick");
+                ui.add_enabled(
+                    camera.fov_kick,
+                    egui::DragValue::new(&mut camera.fov_kick_strength)
+                        .range(0f32..=Camera::FOV_KICK_MAX_STRENGTH)
+                        .speed(0.1)
+                        .suffix("°"),
+                )
+                .on_hover_text("Widens the field of view as the camera moves faster");
+            });
+
+            egui::ComboBox::from_label("Gaze focus")
+                .selected_text(camera.gaze_focus.to_string().split_pascalcase())
+                .show_ui(ui, |ui| {
+                    for mode in GazeFocusMode::iter() {
+                        ui.selectable_value(
+                            &mut camera.gaze_focus,
+                            mode,
+                            mode.to_string().split_pascalcase(),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text("Fly to the gaze point, or orbit around it from where you are");
+
+            config::with_mut(|c| {
+                ui.checkbox(&mut c.visual.camera_spawn_on_load, "Overlook map on load")
+                    .on_hover_text("Move the camera to overlook the whole map once it has loaded");
+                ui.checkbox(&mut c.visual.keep_camera_on_map_switch, "Keep camera on map switch")
+                    .on_hover_text(
+                        "Keep the camera position, speed and zoom when switching maps. \
+                         Otherwise, the camera is reset to overlook the new map",
+                    );
+                ui.checkbox(&mut c.visual.unload_previous_map, "Unload previous map")
+                    .on_hover_text(
+                        "Unload the previous map once the new one has loaded, freeing the assets \
+                         that only it used. Switching back to it will load it again",
+                    );
+            });
+
             ui.separator();
 
             config::with_mut(|c| {
                 ui.collapsing(RichText::new("Graphics").heading(), |ui| {
+                    let current_preset = c.renderer.current_preset();
+                    egui::ComboBox::from_label("Preset")
+                        .selected_text(
+                            current_preset.map_or("Custom".to_string(), |p| p.to_string()),
+                        )
+                        .show_ui(ui, |ui| {
+                            for preset in RenderPreset::iter() {
+                                if ui
+                                    .selectable_label(
+                                        current_preset == Some(preset),
+                                        preset.to_string(),
+                                    )
+                                    .clicked()
+                                {
+                                    c.renderer.apply_preset(preset);
+                                }
+                            }
+                        });
+
+                    ui.horizontal(|ui| {
+                        egui::Slider::new(&mut c.renderer.render_scale, 0.25..=1.0)
+                            .step_by(0.05)
+                            .custom_formatter(|v, _| format!("{:.0}%", v * 100.0))
+                            .ui(ui);
+                        ui.label("Render Scale");
+                    });
+                    ui.add_enabled_ui(c.renderer.render_scale < 1.0, |ui| {
+                        egui::ComboBox::from_label("Upscale Filter")
+                            .selected_text(c.renderer.upscale_filter.to_string())
+                            .show_ui(ui, |ui| {
+                                for filter in UpscaleFilter::iter() {
+                                    ui.selectable_value(
+                                        &mut c.renderer.upscale_filter,
+                                        filter,
+                                        filter.to_string(),
+                                    );
+                                }
+                            });
+                    });
+                    egui::ComboBox::from_label("Anisotropic Filtering")
+                        .selected_text(c.renderer.anisotropy.to_string())
+                        .show_ui(ui, |ui| {
+                            for anisotropy in Anisotropy::iter() {
+                                ui.selectable_value(
+                                    &mut c.renderer.anisotropy,
+                                    anisotropy,
+                                    anisotropy.to_string(),
+                                );
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        egui::Slider::new(&mut c.renderer.mip_lod_bias, -2.0..=2.0)
+                            .step_by(0.25)
+                            .ui(ui);
+                        ui.label("Texture Mip Bias")
+                            .on_hover_text("Negative values make textures sharper");
+                    });
                     ui.checkbox(&mut c.renderer.vsync, "VSync");
+                    ui.horizontal(|ui| {
+                        egui::Slider::new(&mut c.renderer.max_frame_latency, 1..=3).ui(ui);
+                        ui.label("Max Frame Latency").on_hover_text(
+                            "Number of frames that can be queued up ahead of the GPU.\nLower values make the camera more responsive, especially with VSync on, at a slight cost in framerate",
+                        );
+                    });
+                    ui.checkbox(&mut c.renderer.waitable_swapchain, "Waitable Swapchain")
+                        .on_hover_text("Waits for the swapchain before starting a frame, which further reduces input latency.\nRequires a restart");
                     ui.checkbox(&mut c.renderer.matcap, "Matcap");
+                    ui.checkbox(&mut c.renderer.wireframe, "Wireframe");
                     ui.checkbox(&mut c.renderer.draw_selection_outline, "Selection Outline");
+                    ui.checkbox(&mut c.renderer.draw_light_direction, "Light Direction")
+                        .on_hover_text("Show the direction of the global light in the corner of the view");
+                    ui.checkbox(&mut c.renderer.draw_shadow_coverage, "Shadow Cascade Coverage")
+                        .on_hover_text("Tint geometry by the shadow cascade that would sample it, based on its distance from the camera");
+                    ui.checkbox(&mut c.renderer.draw_shadow_split, "Shadow Cache Split")
+                        .on_hover_text("Tint shadows cast by cached stationary geometry blue, and shadows cast by moving geometry orange");
+                    ui.checkbox(&mut c.renderer.draw_shadow_frusta, "Shadow Frusta")
+                        .on_hover_text("Outline the area covered by each shadow-casting light");
+                    ui.checkbox(&mut c.renderer.draw_map_tint, "Map Tint")
+                        .on_hover_text("Tint geometry by the map it belongs to and outline the seams between active maps");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut c.renderer.draw_grid, "Ground Grid")
+                            .on_hover_text("Draw a grid on the ground plane (Z=0), with a major line every 10 minor lines");
+                        ui.add_enabled(
+                            c.renderer.draw_grid,
+                            egui::DragValue::new(&mut c.renderer.grid_spacing)
+                                .range(0.1..=100.0)
+                                .speed(0.05)
+                                .suffix(" m"),
+                        );
+                    });
+                    ui.checkbox(&mut c.renderer.draw_model_placeholders, "Model Placeholders")
+                        .on_hover_text("Show a cube in place of models that failed to load");
+                    ui.checkbox(&mut c.renderer.rt0_float, "Float Albedo")
+                        .on_hover_text("Store RT0 as R11G11B10 float instead of 8-bit sRGB (Ctrl+Shift+R)");
+                    egui::ComboBox::from_label("MSAA")
+                        .selected_text(if c.renderer.msaa_samples > 1 {
+                            format!("{}x", c.renderer.msaa_samples)
+                        } else {
+                            "Off".to_string()
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut c.renderer.msaa_samples, 1, "Off");
+                            for samples in [2, 4, 8] {
+                                ui.selectable_value(&mut c.renderer.msaa_samples, samples, format!("{samples}x"));
+                            }
+                        })
+                        .response
+                        .on_hover_text("Multisample the G-buffer for smoother geometry edges. Lighting and postprocessing still run once per pixel");
+                    ui.checkbox(&mut c.renderer.force_transparent_depth_readonly, "Read-only Transparent Depth")
+                        .on_hover_text("Disable depth writes for transparent materials, even if their technique enables them");
+                    egui::ComboBox::from_label("Skinning Shader")
+                        .selected_text(match c.renderer.force_skinning_vs {
+                            None => "Auto",
+                            Some(true) => "Always",
+                            Some(false) => "Never",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut c.renderer.force_skinning_vs, None, "Auto");
+                            ui.selectable_value(&mut c.renderer.force_skinning_vs, Some(true), "Always");
+                            ui.selectable_value(&mut c.renderer.force_skinning_vs, Some(false), "Never");
+                        })
+                        .response
+                        .on_hover_text("Whether dynamic models are drawn with the skinning vertex shader override. Auto uses it for parts that need skinning");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut c.renderer.near_fade, "Near Fade")
+                            .on_hover_text("Dither out geometry close to the camera");
+                        ui.add_enabled(
+                            c.renderer.near_fade,
+                            egui::DragValue::new(&mut c.renderer.near_fade_distance)
+                                .range(0.05..=5.0)
+                                .speed(0.01)
+                                .suffix(" m"),
+                        );
+                    });
+                    egui::ComboBox::from_label("Tone Mapping")
+                        .selected_text(c.renderer.tone_mapping.to_string())
+                        .show_ui(ui, |ui| {
+                            for tone_mapping in ToneMapping::iter() {
+                                ui.selectable_value(
+                                    &mut c.renderer.tone_mapping,
+                                    tone_mapping,
+                                    tone_mapping.to_string(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Operators other than None replace the game's film curve instead of \
+                             being applied on top of it",
+                        );
+                    ui.horizontal(|ui| {
+                        ui.label("Exposure");
+                        egui::DragValue::new(&mut c.renderer.exposure)
+                            .range(0.0..=16.0)
+                            .speed(0.01)
+                            .ui(ui);
+                    })
+                    .response
+                    .on_hover_text("Brightness multiplier applied before tone mapping, on top of auto-exposure");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut c.renderer.auto_exposure, "Auto-exposure")
+                            .on_hover_text("Adapt the exposure to the average brightness of the scene");
+                        ui.add_enabled(
+                            c.renderer.auto_exposure,
+                            egui::DragValue::new(&mut c.renderer.auto_exposure_speed)
+                                .range(0.1..=10.0)
+                                .speed(0.01)
+                                .prefix("Speed: "),
+                        );
+                    });
+                    ui.checkbox(&mut c.renderer.bloom, "Bloom");
+                    ui.add_enabled_ui(c.renderer.bloom, |ui| {
+                        ui.indent("bloom", |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Intensity");
+                                egui::DragValue::new(&mut c.renderer.bloom_intensity)
+                                    .range(0.0..=4.0)
+                                    .speed(0.01)
+                                    .ui(ui);
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Threshold");
+                                egui::DragValue::new(&mut c.renderer.bloom_threshold)
+                                    .range(0.0..=16.0)
+                                    .speed(0.01)
+                                    .ui(ui);
+                            })
+                            .response
+                            .on_hover_text("Brightness above which pixels start to bloom");
+                        });
+                    });
+                    ui.checkbox(&mut c.renderer.dof, "Depth of Field");
+                    ui.add_enabled_ui(c.renderer.dof, |ui| {
+                        ui.indent("dof", |ui| {
+                            ui.checkbox(&mut c.renderer.dof_autofocus, "Auto-focus")
+                                .on_hover_text("Focus on whatever is at the center of the screen");
+                            ui.horizontal(|ui| {
+                                ui.label("Focal Distance");
+                                ui.add_enabled(
+                                    !c.renderer.dof_autofocus,
+                                    egui::DragValue::new(&mut c.renderer.dof_focal_distance)
+                                        .range(0.1..=5000.0)
+                                        .speed(0.1)
+                                        .suffix(" m"),
+                                );
+
+                                let selected_distance =
+                                    selected_entity_distance(resources, camera.position());
+                                if ui
+                                    .add_enabled(
+                                        selected_distance.is_some(),
+                                        egui::Button::new("Focus on Selection"),
+                                    )
+                                    .on_hover_text("Set the focal distance to the distance of the selected entity, and turn off auto-focus")
+                                    .clicked()
+                                {
+                                    if let Some(distance) = selected_distance {
+                                        c.renderer.dof_autofocus = false;
+                                        c.renderer.dof_focal_distance = distance.max(0.1);
+                                    }
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Focal Range");
+                                egui::DragValue::new(&mut c.renderer.dof_focal_range)
+                                    .range(0.1..=1000.0)
+                                    .speed(0.1)
+                                    .suffix(" m")
+                                    .ui(ui);
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Blur Strength");
+                                egui::DragValue::new(&mut c.renderer.dof_blur_strength)
+                                    .range(1.0..=32.0)
+                                    .speed(0.1)
+                                    .suffix(" px")
+                                    .ui(ui);
+                            });
+                        });
+                    });
+                    ui.checkbox(&mut c.renderer.vignette, "Vignette");
+                    ui.add_enabled_ui(c.renderer.vignette, |ui| {
+                        ui.indent("vignette", |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Strength");
+                                egui::DragValue::new(&mut c.renderer.vignette_strength)
+                                    .range(0.0..=1.0)
+                                    .speed(0.01)
+                                    .ui(ui);
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Radius");
+                                egui::DragValue::new(&mut c.renderer.vignette_radius)
+                                    .range(0.0..=1.0)
+                                    .speed(0.01)
+                                    .ui(ui);
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Color");
+                                ui.color_edit_button_rgb(&mut c.renderer.vignette_color);
+                            });
+                        });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut c.renderer.chromatic_aberration, "Chromatic Aberration");
+                        ui.add_enabled(
+                            c.renderer.chromatic_aberration,
+                            egui::DragValue::new(&mut c.renderer.chromatic_aberration_strength)
+                                .range(0.0..=20.0)
+                                .speed(0.05)
+                                .suffix(" px"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Film Grain");
+                        egui::Slider::new(&mut c.renderer.grain, 0.0..=1.0).ui(ui);
+                        ui.checkbox(&mut c.renderer.grain_frozen, "Freeze")
+                            .on_hover_text("Use the same grain pattern every frame");
+                    });
 
-                    if egui::ComboBox::from_label("Shadows")
+                    // Shadow maps are recreated by the renderer when the resolution changes
+                    egui::ComboBox::from_label("Shadows")
                         .selected_text(c.renderer.shadow_quality.to_string().split_pascalcase())
                         .show_ui(ui, |ui| {
-                            let mut changed = false;
                             for quality in ShadowQuality::iter() {
-                                changed |= ui
-                                    .selectable_value(
-                                        &mut c.renderer.shadow_quality,
-                                        quality,
-                                        quality.to_string().split_pascalcase(),
-                                    )
-                                    .clicked();
+                                ui.selectable_value(
+                                    &mut c.renderer.shadow_quality,
+                                    quality,
+                                    quality.to_string().split_pascalcase(),
+                                );
                             }
-                            changed
+                        });
+                    let default_pcf_samples = c.renderer.shadow_quality.pcf_samples().count();
+                    egui::ComboBox::from_label("Shadow Filtering")
+                        .selected_text(match c.renderer.shadow_pcf_samples {
+                            Some(samples) => format!("{} samples", samples.count()),
+                            None => format!("Default ({default_pcf_samples} samples)"),
                         })
-                        .inner
-                        .unwrap_or_default()
-                    {
-                        console::queue_command("recreate_shadowmaps", &[]);
-                    }
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut c.renderer.shadow_pcf_samples, None, "Default");
+                            for samples in ShadowPcfSamples::iter() {
+                                ui.selectable_value(
+                                    &mut c.renderer.shadow_pcf_samples,
+                                    Some(samples),
+                                    format!("{} samples", samples.count()),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text("More samples give softer, cleaner shadow edges at a higher cost");
+                    ui.add_enabled_ui(!c.renderer.force_lod0, |ui| {
+                        ui.horizontal(|ui| {
+                            egui::Slider::new(&mut c.renderer.lod_bias, 0.25..=4.0)
+                                .logarithmic(true)
+                                .ui(ui);
+                            ui.label("LOD Bias");
+                        });
+                    });
+                    ui.checkbox(&mut c.renderer.force_lod0, "Force LOD0");
+                    ui.horizontal(|ui| {
+                        let max_threads =
+                            std::thread::available_parallelism().map_or(16, |n| n.get());
+                        // Only applied once the slider is released, instead of resizing the loader pool on every step of a drag
+                        let id = ui.id().with("asset_load_threads");
+                        let mut threads = ui
+                            .data(|d| d.get_temp::<usize>(id))
+                            .unwrap_or(c.renderer.asset_load_threads);
+                        let response = egui::Slider::new(&mut threads, 1..=max_threads).ui(ui);
+                        if response.dragged() {
+                            ui.data_mut(|d| d.insert_temp(id, threads));
+                        } else {
+                            c.renderer.asset_load_threads = threads;
+                            ui.data_mut(|d| d.remove::<usize>(id));
+                        }
+                        ui.label("Asset Loader Threads");
+                    })
+                    .response
+                    .on_hover_text(
+                        "Threads used to load assets. Fewer threads use less memory while \
+                         loading, more threads load maps faster on fast storage",
+                    );
                     ui.checkbox(&mut c.renderer.ssao, "SSAO");
                     ui.collapsing("SSAO Settings", |ui| {
                         let renderer = resources.get::<RendererShared>();
-                        let ssao_data = renderer.ssao.scope.data();
+                        let Some(&current) = renderer.ssao.scope.data_ref() else {
+                            return;
+                        };
+                        let mut ssao_data = current;
                         ui.horizontal(|ui| {
                             ui.label("Radius");
                             egui::DragValue::new(&mut ssao_data.radius)
@@ -145,6 +546,13 @@ impl GuiView for RenderSettingsPanel {
                                 .suffix("m")
                                 .ui(ui);
                         });
+
+                        ui.checkbox(&mut c.renderer.ssao_linear_depth, "Linear Depth Sampling")
+                            .on_hover_text("Debug: sample depth with linear filtering instead of point sampling, which smears AO across depth edges");
+
+                        if ssao_data.radius != current.radius || ssao_data.bias != current.bias {
+                            *renderer.ssao.scope.data() = ssao_data;
+                        }
                     });
                     // ui.checkbox(&mut c.renderer.depth_prepass, "⚠ Depth Prepass");
 
@@ -155,28 +563,108 @@ impl GuiView for RenderSettingsPanel {
                             &mut c.visual.node_nametags_named_only,
                             "Only show named nodes",
                         );
+                        node_origin_filter(ui, &mut c.visual.node_origins);
+                        ui.horizontal(|ui| {
+                            ui.label("Icon Scale");
+                            egui::DragValue::new(&mut c.visual.node_icon_scale)
+                                .range(0.5..=4.0)
+                                .speed(0.01)
+                                .ui(ui);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Label Size");
+                            egui::DragValue::new(&mut c.visual.node_label_size)
+                                .range(6.0..=48.0)
+                                .speed(0.1)
+                                .ui(ui);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut c.visual.node_clustering, "Cluster")
+                                .on_hover_text("Group overlapping nodes into a single marker");
+                            ui.add_enabled(
+                                c.visual.node_clustering,
+                                egui::DragValue::new(&mut c.visual.node_cluster_radius)
+                                    .range(4.0..=256.0)
+                                    .speed(0.5)
+                                    .suffix(" px"),
+                            );
+                        });
+                        ui.checkbox(&mut c.visual.node_label_declutter, "Declutter Labels")
+                            .on_hover_text(
+                                "Move overlapping labels apart and connect them to their icon with a line",
+                            );
+                        ui.checkbox(&mut c.visual.node_icon_billboards, "3D Icons")
+                            .on_hover_text("Draw node icons as billboards in the scene, so they are hidden behind geometry. Labels stay on top");
                         let mut filters = resources.get_mut::<NodeFilterSet>();
-                        for filter in NodeFilter::iter() {
-                            let filter_text = RichText::new(format!(
-                                "{} {}",
-                                filter.icon(),
-                                filter.to_string().split_pascalcase()
-                            ))
-                            .color(filter.color());
-
-                            let mut checked = filters.contains(&filter);
-                            if ui.checkbox(&mut checked, filter_text).changed() {
-                                if checked {
-                                    filters.insert(filter);
-                                    c.visual.node_filters.insert(filter.to_string());
-                                } else {
-                                    filters.remove(&filter);
-                                    c.visual.node_filters.remove(&filter.to_string());
-                                }
+                        let mut changed = false;
+                        ui.horizontal(|ui| {
+                            if ui.button("All").clicked() {
+                                *filters = NodeFilter::iter().collect();
+                                changed = true;
+                            }
+                            if ui.button("None").clicked() {
+                                filters.clear();
+                                changed = true;
+                            }
+                            if ui.button("Invert").clicked() {
+                                *filters = NodeFilter::iter()
+                                    .filter(|f| !filters.contains(f))
+                                    .collect();
+                                changed = true;
                             }
+                        });
+
+                        let counts = count_node_filters(resources);
+                        egui::Grid::new("node_filters")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for filter in NodeFilter::iter() {
+                                    let filter_text = RichText::new(format!(
+                                        "{} {}",
+                                        filter.icon(),
+                                        filter.to_string().split_pascalcase()
+                                    ))
+                                    .color(filter.color());
+
+                                    let mut checked = filters.contains(&filter);
+                                    if ui.checkbox(&mut checked, filter_text).changed() {
+                                        if checked {
+                                            filters.insert(filter);
+                                        } else {
+                                            filters.remove(&filter);
+                                        }
+                                        changed = true;
+                                    }
+
+                                    let count = counts.get(&filter).copied().unwrap_or_default();
+                                    if count == 0 {
+                                        ui.weak("0");
+                                    } else {
+                                        ui.label(count.to_string());
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+
+                        if changed {
+                            c.visual.node_filters = filters.iter().map(|f| f.to_string()).collect();
                         }
                     });
 
+                    ui.checkbox(&mut c.visual.overlay_auto_hide, "Auto-hide Overlays")
+                        .on_hover_text("Fade out overlays after a period without any input");
+                    ui.add_enabled_ui(c.visual.overlay_auto_hide, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Timeout");
+                            egui::DragValue::new(&mut c.visual.overlay_auto_hide_timeout)
+                                .speed(0.1)
+                                .range(0.5..=120.0)
+                                .suffix("s")
+                                .ui(ui);
+                        });
+                        ui.checkbox(&mut c.visual.overlay_auto_hide_panels, "Also hide panels");
+                    });
+
                     egui::ComboBox::from_label("Debug View")
                         .selected_text(c.renderer.debug_view.to_string().split_pascalcase())
                         .show_ui(ui, |ui| {
@@ -192,11 +680,23 @@ impl GuiView for RenderSettingsPanel {
 
                 ui.separator();
                 ui.collapsing(RichText::new("Feature Renderers").heading(), |ui| {
+                    geometry_quick_toggle(
+                        ui,
+                        &mut c.renderer.feature_statics,
+                        &mut c.renderer.feature_dynamics,
+                    );
+                    environment_toggle(ui, &mut c.renderer);
+                    ui.separator();
+
                     render_feat_vis_select(ui, "Statics", &mut c.renderer.feature_statics);
                     render_feat_vis_select(ui, "Terrain", &mut c.renderer.feature_terrain);
                     render_feat_vis_select(ui, "Dynamics", &mut c.renderer.feature_dynamics);
                     render_feat_vis_select(ui, "Sky Objects", &mut c.renderer.feature_sky);
                     render_feat_vis_select(ui, "Water", &mut c.renderer.feature_water);
+                    ui.indent("water", |ui| {
+                        ui.checkbox(&mut c.renderer.water_tint, "Tint Water")
+                            .on_hover_text("Draw water in a flat blue, to tell it apart from other transparents");
+                    });
                     render_feat_vis_select(
                         ui,
                         "Trees/Decorators",
@@ -222,9 +722,12 @@ impl GuiView for RenderSettingsPanel {
                     ui.checkbox(&mut c.renderer.stage_decals_additive, "Decals (additive)");
                 });
 
-                resources
-                    .get::<RendererShared>()
-                    .set_render_settings(c.renderer.clone());
+                let renderer = resources.get::<RendererShared>();
+                renderer.set_render_settings(c.renderer.clone());
+                // Targets that failed to be recreated revert their setting, which has to be reflected here
+                // so it isn't retried every frame
+                c.renderer.rt0_float = renderer.settings.rt0_float;
+                c.renderer.msaa_samples = renderer.settings.msaa_samples;
             })
         });
 
@@ -304,6 +807,132 @@ fn render_feat_vis_select(ui: &mut egui::Ui, name: &str, mode: &mut RenderFeatur
     });
 }
 
+/// Segmented control for quickly switching between rendering only statics, only dynamics or both
+fn geometry_quick_toggle(
+    ui: &mut egui::Ui,
+    statics: &mut RenderFeatureVisibility,
+    dynamics: &mut RenderFeatureVisibility,
+) {
+    let statics_visible = statics.contains(RenderFeatureVisibility::VISIBLE);
+    let dynamics_visible = dynamics.contains(RenderFeatureVisibility::VISIBLE);
+
+    ui.horizontal(|ui| {
+        ui.label("Geometry");
+
+        let mut set_visible = |statics_visible: bool, dynamics_visible: bool| {
+            // Only the visible flag is touched, so selectability is kept when toggling back
+            statics.set(RenderFeatureVisibility::VISIBLE, statics_visible);
+            dynamics.set(RenderFeatureVisibility::VISIBLE, dynamics_visible);
+        };
+
+        if ui
+            .selectable_label(statics_visible && dynamics_visible, "All")
+            .clicked()
+        {
+            set_visible(true, true);
+        }
+        if ui
+            .selectable_label(statics_visible && !dynamics_visible, "Statics")
+            .clicked()
+        {
+            set_visible(true, false);
+        }
+        if ui
+            .selectable_label(!statics_visible && dynamics_visible, "Dynamics")
+            .clicked()
+        {
+            set_visible(false, true);
+        }
+
+        if ui
+            .button("Invert")
+            .on_hover_text("Swap which of statics/dynamics are visible")
+            .clicked()
+        {
+            set_visible(!statics_visible, !dynamics_visible);
+        }
+    });
+}
+
+/// Distance from the given position to the center of the selected entity in the current map
+fn selected_entity_distance(resources: &AppResources, position: Vec3) -> Option<f32> {
+    let selected_entity = resources.get::<SelectedEntity>().selected()?;
+    let maps = resources.get::<MapList>();
+    let scene = &maps.current_map()?.scene;
+
+    let bounds = scene.get::<Aabb>(selected_entity);
+    let center = match (scene.get::<Transform>(selected_entity), bounds) {
+        (Some(transform), Some(bounds)) => {
+            transform.local_to_world().transform_point3(bounds.center())
+        }
+        (Some(transform), None) => transform.translation,
+        (None, Some(bounds)) => bounds.center(),
+        (None, None) => return None,
+    };
+
+    Some(center.distance(position))
+}
+
+/// Number of nodes of each type in the current map. Like the node gizmos, nodes without a type are counted as [`NodeFilter::Unknown`]
+fn count_node_filters(resources: &AppResources) -> FxHashMap<NodeFilter, usize> {
+    let mut counts = FxHashMap::default();
+    let maps = resources.get::<MapList>();
+    if let Some(map) = maps.current_map() {
+        for e in map.scene.iter_entities() {
+            if !e.contains::<Transform>() {
+                continue;
+            }
+
+            let filter = e
+                .get::<NodeFilter>()
+                .copied()
+                .unwrap_or(NodeFilter::Unknown);
+            *counts.entry(filter).or_default() += 1;
+        }
+    }
+
+    counts
+}
+
+/// Presets and per-origin toggles for node visibility. The colored markers double as a legend for the ones drawn next to node icons
+fn node_origin_filter(ui: &mut egui::Ui, origins: &mut egui::ahash::HashSet<String>) {
+    let mut set_preset = |preset: &[ResourceOrigin]| {
+        origins.clear();
+        origins.extend(preset.iter().map(|o| o.to_string()));
+    };
+
+    ui.horizontal(|ui| {
+        ui.label("Origins");
+        if ui.button("All").clicked() {
+            set_preset(&ResourceOrigin::iter().collect::<Vec<_>>());
+        }
+        if ui.button("Map only").clicked() {
+            set_preset(&[ResourceOrigin::Map]);
+        }
+        if ui.button("Activity only").clicked() {
+            set_preset(&[ResourceOrigin::Activity, ResourceOrigin::ActivityBruteforce]);
+        }
+    });
+
+    for origin in ResourceOrigin::iter() {
+        let text = RichText::new(format!(
+            "[{}] {}",
+            origin.short_name(),
+            origin.to_string().split_pascalcase()
+        ))
+        .color(origin.color());
+
+        let mut checked = origins.contains(&origin.to_string());
+        if ui.checkbox(&mut checked, text).changed() {
+            if checked {
+                origins.insert(origin.to_string());
+            } else {
+                origins.remove(&origin.to_string());
+            }
+        }
+    }
+}
+
 fn render_feat_vis(ui: &mut egui::Ui, name: &str, visible: &mut bool) {
     ui.horizontal(|ui| {
         ui.label(name);
@@ -329,3 +958,41 @@ fn render_feat_vis(ui: &mut egui::Ui, name: &str, visible: &mut bool) {
         }
     });
 }
+
+fn environment_toggle(ui: &mut egui::Ui, settings: &mut RendererSettings) {
+    ui.horizontal(|ui| {
+        let mut hidden = settings.is_environment_hidden();
+        if ui
+            .toggle_value(&mut hidden, "Hide Environment")
+            .on_hover_text(
+                "Disables sky objects and atmosphere and clears the background to a solid color",
+            )
+            .changed()
+        {
+            if hidden {
+                settings.hide_environment(Color::from_gray(0.18));
+            } else {
+                settings.restore_environment();
+            }
+        }
+
+        if let Some(color) = &mut settings.background_color {
+            // Transparency only shows up in screenshots
+            egui::color_picker::color_edit_button_rgba(
+                ui,
+                color,
+                egui::color_picker::Alpha::OnlyBlend,
+            );
+
+            for (name, preset) in [
+                ("Gray", Color::from_gray(0.18)),
+                ("Magenta", Color::from_rgb(1.0, 0.0, 1.0)),
+                ("Transparent", Color::TRANSPARENT),
+            ] {
+                if ui.selectable_label(*color == preset, name).clicked() {
+                    *color = preset;
+                }
+            }
+        }
+    });
+}
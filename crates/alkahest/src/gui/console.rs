@@ -32,7 +32,7 @@ use alkahest_renderer::{
 use anyhow::Context;
 use bevy_ecs::bundle::Bundle;
 use binrw::BinReaderExt;
-use destiny_pkg::{TagHash, TagHash64};
+use destiny_pkg::TagHash;
 use egui::{Color32, RichText, TextStyle};
 use glam::{Mat4, Vec2, Vec3, Vec4Swizzles};
 use itertools::Itertools;
@@ -48,12 +48,17 @@ use tracing_subscriber::Layer;
 use winit::{dpi::PhysicalSize, window::Window};
 
 use crate::{
+    config,
     gui::{
         commands::load_pkg_entities,
-        context::{GuiCtx, GuiView, ViewAction},
+        context::{GuiCtx, GuiView, GuiViewManager, ViewAction},
+        hotkeys::snap_to_surface,
     },
     maplist::MapList,
-    util::action::{ActionList, ActivitySwapAction, SpawnRouteAction},
+    util::{
+        action::{ActionList, ActivitySwapAction, SpawnRouteAction},
+        hash::parse_tag_hash,
+    },
 };
 
 lazy_static! {
@@ -193,7 +198,8 @@ impl GuiView for ConsolePanel {
                             let command = cmd[0];
                             let args = &cmd[1..];
 
-                            execute_command(command, args, resources);
+                            // Commands are run once the GUI is done drawing, as some of them need the view manager
+                            queue_command(command, args);
                         }
 
                         self.command_buffer.clear();
@@ -321,7 +327,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
         //         return;
         //     }
         //
-        //     let tag = match parse_extended_hash(args[0]) {
+        //     let tag = match parse_tag_hash(args[0]) {
         //         Ok(o) => o,
         //         Err(e) => {
         //             error!("Failed to parse tag: {e}");
@@ -352,7 +358,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
         //         return;
         //     }
         //
-        //     let tag = match parse_extended_hash(args[0]) {
+        //     let tag = match parse_tag_hash(args[0]) {
         //         Ok(o) => o,
         //         Err(e) => {
         //             error!("Failed to parse tag: {e}");
@@ -390,7 +396,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                 return;
             }
 
-            let tag = match parse_extended_hash(args[0]) {
+            let tag = match parse_tag_hash(args[0]) {
                 Ok(o) => o,
                 Err(e) => {
                     error!("Failed to parse tag: {e}");
@@ -425,7 +431,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                 return;
             }
 
-            let tag = match parse_extended_hash(args[0]) {
+            let tag = match parse_tag_hash(args[0]) {
                 Ok(o) => o,
                 Err(e) => {
                     error!("Failed to parse tag: {e}");
@@ -460,7 +466,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                 return;
             }
 
-            let tag = match parse_extended_hash(args[0]) {
+            let tag = match parse_tag_hash(args[0]) {
                 Ok(o) => o,
                 Err(e) => {
                     error!("Failed to parse tag: {e}");
@@ -536,7 +542,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                 return;
             }
 
-            let tag = match parse_extended_hash(args[0]) {
+            let tag = match parse_tag_hash(args[0]) {
                 Ok(o) => o.hash32(),
                 Err(e) => {
                     error!("Failed to parse tag: {e}");
@@ -612,6 +618,11 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                     .for_each(|mut v| *v = Visibility::Visible);
             }
         }
+        "cinematic" | "toggle_ui" => {
+            resources
+                .get_mut::<GuiViewManager>()
+                .toggle_cinematic_mode();
+        }
         "clear_maplist" => {
             let mut maps = resources.get_mut::<MapList>();
             maps.set_maps(resources, &[]);
@@ -740,7 +751,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                 return;
             }
 
-            let tag = match parse_extended_hash(args[0]) {
+            let tag = match parse_tag_hash(args[0]) {
                 Ok(o) => o.hash32(),
                 Err(e) => {
                     error!("Failed to parse tag: {e}");
@@ -777,7 +788,7 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
                 return;
             }
 
-            let tag = match parse_extended_hash(args[0]) {
+            let tag = match parse_tag_hash(args[0]) {
                 Ok(o) => o.hash32(),
                 Err(e) => {
                     error!("Failed to parse tag: {e}");
@@ -888,6 +899,17 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
             camera.set_forward(-camera_to_world.transpose().row(2).xyz());
             camera.set_projection(CameraProjection::perspective(fov, 0.01));
         }
+        "snap_to_surface" => {
+            if let Some(offset_arg) = args.first() {
+                let Ok(offset) = str::parse::<f32>(offset_arg) else {
+                    error!("Invalid offset argument");
+                    return;
+                };
+                config::with_mut(|c| c.visual.surface_snap_offset = offset);
+            }
+
+            snap_to_surface(resources);
+        }
         "lock_time" => {
             let renderer = resources.get_mut::<RendererShared>();
             let mut t = renderer.time.load().elapsed();
@@ -914,9 +936,26 @@ fn execute_command(command: &str, args: &[&str], resources: &AppResources) {
             };
 
             for mut shadowmap in scene.query::<&mut ShadowMapRenderer>().iter_mut(scene) {
-                shadowmap.resize(&renderer.gpu, renderer.settings.shadow_quality.resolution());
+                let resolution = shadowmap.target_resolution(renderer.settings.shadow_quality);
+                shadowmap.resize(&renderer.gpu, resolution);
             }
         }
+        "export_pickbuffer" => {
+            let path = args.first().map_or_else(
+                || {
+                    format!(
+                        "./pickbuffer_{}.png",
+                        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+                    )
+                },
+                |p| p.to_string(),
+            );
+
+            resources
+                .get::<RendererShared>()
+                .pickbuffer
+                .request_debug_export(path);
+        }
         _ => error!("Unknown command '{command}'"),
     }
 }
@@ -1002,24 +1041,3 @@ pub fn load_entity(
 
     Err(anyhow::anyhow!("No entitymodel found in entity"))
 }
-
-fn parse_extended_hash(s: &str) -> anyhow::Result<WideHash> {
-    let tag_parsed: anyhow::Result<WideHash> = (|| {
-        if s.len() > 8 {
-            let h = u64::from_be(u64::from_str_radix(s, 16)?);
-            Ok(WideHash::Hash64(TagHash64(h)))
-        } else {
-            let h = u32::from_be(u32::from_str_radix(s, 16)?);
-            Ok(WideHash::Hash32(TagHash(h)))
-        }
-    })();
-
-    let tag = match tag_parsed {
-        Ok(o) => o,
-        Err(e) => {
-            anyhow::bail!("Failed to parse tag: {e}");
-        }
-    };
-
-    Ok(tag)
-}
@@ -1,4 +1,9 @@
-use std::{any::TypeId, sync::Arc};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use alkahest_renderer::{
     gpu::GpuContext,
@@ -14,19 +19,33 @@ use winit::{event::WindowEvent, window::Window};
 
 use super::sodi::Sodi;
 use crate::{
+    config,
     gui::{
+        asset_cache::AssetCachePanel,
+        bookmarks::CameraBookmarksPanel,
         bottom_bar::BottomBar,
         configuration::RenderSettingsPanel,
         console::ConsolePanel,
         crosshair::CrosshairOverlay,
         fps_display::FpsDisplayOverlay,
         gizmo::GizmoSelector,
+        gpu_monitor::GpuObjectMonitor,
+        input_layouts::InputLayoutsPanel,
         inspector::InspectorPanel,
         load_indicator::ResourceLoadIndicatorOverlay,
+        map_legend::{FeatureColorLegendOverlay, MapTintLegendOverlay},
         menu::MenuBar,
+        minimap::MinimapPanel,
         node_gizmos::NodeGizmoOverlay,
         outliner::OutlinerPanel,
+        rt0_display::Rt0FormatOverlay,
+        scene_stats::SceneStatisticsPanel,
+        scene_validation::SceneValidationPanel,
+        spawn_by_hash::SpawnByHashPanel,
         tfx::{TfxErrorViewer, TfxExternEditor},
+        toast::ToastOverlay,
+        util::OVERLAY_OPACITY_ID,
+        zoom_display::ZoomDisplayOverlay,
     },
     paths,
     resources::AppResources,
@@ -73,9 +92,7 @@ impl GuiContext {
 
         fonts.font_data.insert(
             "materialdesignicons".into(),
-            Arc::new(egui::FontData::from_static(include_bytes!(
-                "../../assets/fonts/materialdesignicons-webfont.ttf"
-            ))),
+            Arc::new(egui::FontData::from_static(ICON_FONT)),
         );
         fonts.font_data.insert(
             "Destiny_Keys".into(),
@@ -135,7 +152,7 @@ impl GuiContext {
                         paint(
                             &GuiCtx {
                                 icons: &self.resources,
-                                _integration: renderer,
+                                _integration: RefCell::new(renderer),
                             },
                             context,
                         )
@@ -191,12 +208,22 @@ pub trait GuiView {
     fn dispose(&mut self, _ctx: &egui::Context, _resources: &AppResources, _gui: &GuiCtx<'_>) {}
 }
 
+/// Material Design Icons, which the `ICON_*` constants index into
+pub const ICON_FONT: &[u8] = include_bytes!("../../assets/fonts/materialdesignicons-webfont.ttf");
+
+pub const SHORTCUT_CINEMATIC_MODE: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::CTRL.plus(Modifiers::ALT), Key::H);
+
 #[derive(Default)]
 pub struct GuiViewManager {
     views: IndexMap<TypeId, Box<dyn GuiView>>,
     views_overlay: IndexMap<TypeId, Box<dyn GuiView>>,
 
     pub hide_views: bool,
+    /// Hides all views, overlays and gizmos, for clean screenshots
+    pub cinematic_mode: bool,
+    /// Time of the last user input, used to auto-hide overlays
+    last_input: Option<Instant>,
 }
 
 impl GuiViewManager {
@@ -216,12 +243,27 @@ impl GuiViewManager {
         views.insert(ResourceLoadIndicatorOverlay);
         views.insert(GizmoSelector);
         views.insert(Sodi::default());
+        views.insert(GpuObjectMonitor::default());
+        views.insert(MinimapPanel::default());
+        views.insert(SpawnByHashPanel::default());
+        views.insert(SceneStatisticsPanel::default());
+        views.insert(SceneValidationPanel::default());
+        views.insert(AssetCachePanel::default());
+        views.insert(CameraBookmarksPanel::default());
+        views.insert(InputLayoutsPanel);
 
         views.insert_overlay(FpsDisplayOverlay::default());
+        views.insert_overlay(ZoomDisplayOverlay::default());
+        views.insert_overlay(Rt0FormatOverlay::default());
+        views.insert_overlay(MapTintLegendOverlay);
+        views.insert_overlay(FeatureColorLegendOverlay);
+        views.insert_overlay(ToastOverlay);
 
         views
     }
 
+    const OVERLAY_FADE_TIME: Duration = Duration::from_millis(500);
+
     pub fn insert<T: GuiView + 'static>(&mut self, view: T) {
         self.views.insert(TypeId::of::<T>(), Box::new(view));
     }
@@ -229,6 +271,37 @@ impl GuiViewManager {
         self.views_overlay.insert(TypeId::of::<T>(), Box::new(view));
     }
 
+    pub fn toggle_cinematic_mode(&mut self) {
+        self.cinematic_mode = !self.cinematic_mode;
+    }
+
+    /// Should gizmos and other UI drawn outside of the views be shown?
+    pub fn show_ui(&self) -> bool {
+        !self.hide_views && !self.cinematic_mode
+    }
+
+    /// Updates the inactivity timer and returns the opacity overlays should be drawn with
+    fn update_overlay_opacity(&mut self, ctx: &egui::Context) -> f32 {
+        let had_input =
+            ctx.input(|i| !i.events.is_empty() || !i.keys_down.is_empty() || i.pointer.any_down());
+        if had_input || self.last_input.is_none() {
+            self.last_input = Some(Instant::now());
+        }
+
+        let (enabled, timeout) = config::with(|c| {
+            (
+                c.visual.overlay_auto_hide,
+                c.visual.overlay_auto_hide_timeout,
+            )
+        });
+        if !enabled {
+            return 1.0;
+        }
+
+        let idle = self.last_input.map_or(0.0, |t| t.elapsed().as_secs_f32()) - timeout;
+        1.0 - (idle / Self::OVERLAY_FADE_TIME.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
     // pub fn remove<T: GuiView + 'static>(&mut self) {
     //     self.views.shift_remove(&TypeId::of::<T>());
     // }
@@ -249,7 +322,22 @@ impl GuiViewManager {
             self.hide_views = !self.hide_views;
         }
 
-        if !self.hide_views {
+        if ctx.input_mut(|input| input.consume_shortcut(&SHORTCUT_CINEMATIC_MODE)) {
+            self.toggle_cinematic_mode();
+        }
+
+        let overlay_opacity = self.update_overlay_opacity(ctx);
+        ctx.data_mut(|d| d.insert_temp(OVERLAY_OPACITY_ID.into(), overlay_opacity));
+
+        if self.cinematic_mode {
+            return;
+        }
+
+        // Panels are only hidden by the auto-hide once the overlays have fully faded out
+        let panels_hidden =
+            overlay_opacity == 0.0 && config::with(|c| c.visual.overlay_auto_hide_panels);
+
+        if !self.hide_views && !panels_hidden {
             let mut to_remove = SmallVec::<[TypeId; 4]>::new();
             for (tid, view) in self.views.iter_mut() {
                 if let Some(result) = view.draw(ctx, window, resources, gui) {
@@ -274,7 +362,8 @@ impl GuiViewManager {
 
 pub struct GuiCtx<'a> {
     pub icons: &'a GuiResources,
-    pub _integration: &'a mut DirectX11Renderer,
+    /// Views that register their own textures need mutable access to the egui renderer
+    pub _integration: RefCell<&'a mut DirectX11Renderer>,
 }
 
 pub struct GuiResources {
@@ -306,6 +395,15 @@ impl GuiResources {
 pub struct HiddenWindows {
     pub tfx_extern_editor: bool,
     pub tfx_extern_debugger: bool,
+    pub gpu_object_monitor: bool,
+    pub minimap: bool,
+    pub spawn_by_hash: bool,
+    pub scene_statistics: bool,
+    pub scene_validation: bool,
+    pub asset_cache: bool,
+    pub background_tasks: bool,
+    pub camera_bookmarks: bool,
+    pub input_layouts: bool,
 }
 
 mod style {
@@ -7,7 +7,7 @@ use winit::window::Window;
 use crate::{
     gui::{
         context::{GuiCtx, GuiView, ViewAction},
-        util::PainterExt,
+        util::{overlay_painter, PainterExt},
     },
     resources::AppResources,
 };
@@ -43,7 +43,7 @@ impl GuiView for FpsDisplayOverlay {
             _ => Color32::RED,
         };
 
-        let painter = ctx.layer_painter(egui::LayerId::debug());
+        let painter = overlay_painter(ctx);
         painter.text_with_shadow(
             [ctx.input(|i| i.screen_rect.right()) - 20.0, 22.0].into(),
             egui::Align2::RIGHT_TOP,
@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+
+use alkahest_renderer::gpu::debug::GpuObjectKind;
+use egui::{Color32, RichText};
+use ringbuffer::{ConstGenericRingBuffer, RingBuffer};
+use strum::{EnumCount, IntoEnumIterator};
+use winit::window::Window;
+
+use crate::{
+    gui::context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+    resources::AppResources,
+};
+
+type GpuObjectCounts = [usize; GpuObjectKind::COUNT];
+
+/// Keeps track of the amount of live GPU objects over time. A count that keeps growing while idle indicates a leak.
+pub struct GpuObjectMonitor {
+    /// One sample per [`Self::SAMPLE_INTERVAL`]
+    samples: ConstGenericRingBuffer<GpuObjectCounts, 60>,
+    last_sample: Instant,
+}
+
+impl Default for GpuObjectMonitor {
+    fn default() -> Self {
+        Self {
+            samples: Default::default(),
+            last_sample: Instant::now() - Self::SAMPLE_INTERVAL,
+        }
+    }
+}
+
+impl GpuObjectMonitor {
+    const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+    fn sample() -> GpuObjectCounts {
+        let mut counts = [0; GpuObjectKind::COUNT];
+        for kind in GpuObjectKind::iter() {
+            counts[kind as usize] = kind.live_count();
+        }
+        counts
+    }
+}
+
+impl GuiView for GpuObjectMonitor {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        if self.last_sample.elapsed() >= Self::SAMPLE_INTERVAL {
+            self.samples.push(Self::sample());
+            self.last_sample = Instant::now();
+        }
+
+        let mut windows = resources.get_mut::<HiddenWindows>();
+        egui::Window::new("GPU Object Monitor")
+            .open(&mut windows.gpu_object_monitor)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let (Some(oldest), Some(latest)) = (self.samples.front(), self.samples.back())
+                else {
+                    return;
+                };
+
+                egui::Grid::new("gpu_object_monitor")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.strong("Kind");
+                        ui.strong("Live");
+                        ui.strong(format!("Δ {}s", self.samples.len()));
+                        ui.end_row();
+
+                        for kind in GpuObjectKind::iter() {
+                            let delta =
+                                latest[kind as usize] as isize - oldest[kind as usize] as isize;
+
+                            ui.label(kind.to_string());
+                            ui.label(latest[kind as usize].to_string());
+                            ui.label(RichText::new(format!("{delta:+}")).color(if delta > 0 {
+                                Color32::RED
+                            } else {
+                                Color32::GRAY
+                            }));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.label(
+                    RichText::new("Counts that keep growing while idle indicate a resource leak")
+                        .weak(),
+                );
+            });
+
+        None
+    }
+}
@@ -1,11 +1,15 @@
+use std::path::PathBuf;
+
 use alkahest_data::occlusion::Aabb;
 use alkahest_renderer::{
     camera::{
+        orbit::GazeFocusMode,
         tween::{ease_out_exponential, Tween},
         Camera,
     },
     ecs::{
         hierarchy::{Children, Parent},
+        render::dynamic_geometry::DynamicModelComponent,
         resources::SelectedEntity,
         transform::Transform,
         visibility::{Visibility, VisibilityHelper},
@@ -18,6 +22,8 @@ use bevy_ecs::entity::Entity;
 use rustc_hash::FxHashSet;
 
 use crate::{
+    config,
+    gui::{bookmarks::CameraBookmarks, toast::Toast},
     maplist::MapList,
     resources::AppResources,
     util::action::{ActionList, TweenAction},
@@ -46,6 +52,9 @@ pub const SHORTCUT_FOCUS: egui::KeyboardShortcut =
 pub const SHORTCUT_GAZE: egui::KeyboardShortcut =
     egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::G);
 
+pub const SHORTCUT_SNAP_TO_SURFACE: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::SHIFT, egui::Key::G);
+
 pub const SHORTCUT_MAP_SWAP: egui::KeyboardShortcut =
     egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::I);
 
@@ -73,6 +82,27 @@ pub const SHORTCUT_SELECT_NEXT_CHILD: egui::KeyboardShortcut =
 pub const SHORTCUT_SELECT_PREV_CHILD: egui::KeyboardShortcut =
     egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::ArrowLeft);
 
+// Ctrl+0 is taken by egui to reset the UI zoom
+pub const SHORTCUT_RESET_ZOOM: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Z);
+
+pub const SHORTCUT_SCREENSHOT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F12);
+
+pub const SHORTCUT_PREV_VARIANT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::OpenBracket);
+
+pub const SHORTCUT_NEXT_VARIANT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::CloseBracket);
+
+pub const SHORTCUT_NEXT_BOOKMARK: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::B);
+
+pub const SHORTCUT_TOGGLE_RT0_FORMAT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::CTRL.plus(egui::Modifiers::SHIFT),
+    egui::Key::R,
+);
+
 pub fn process_hotkeys(ctx: &egui::Context, resources: &mut AppResources) {
     // We're in a text input field, don't process hotkeys
     if ctx.wants_keyboard_input() {
@@ -110,6 +140,11 @@ pub fn process_hotkeys(ctx: &egui::Context, resources: &mut AppResources) {
         resources.get_mut::<MapList>().set_current_map_next();
     }
 
+    // Checked before the gaze shortcut, which would otherwise also match Shift+G
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_SNAP_TO_SURFACE)) {
+        snap_to_surface(resources);
+    }
+
     if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_GAZE)) {
         goto_gaze(resources);
     }
@@ -133,6 +168,35 @@ pub fn process_hotkeys(ctx: &egui::Context, resources: &mut AppResources) {
     if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_SELECT_PREV_CHILD)) {
         select_child_offset(resources, false);
     }
+
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_RESET_ZOOM)) {
+        resources.get_mut::<Camera>().reset_zoom();
+    }
+
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_SCREENSHOT)) {
+        capture_screenshot(resources);
+    }
+
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_PREV_VARIANT)) {
+        cycle_material_variant(resources, false);
+    }
+
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_NEXT_VARIANT)) {
+        cycle_material_variant(resources, true);
+    }
+
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_NEXT_BOOKMARK)) {
+        resources.get_mut::<CameraBookmarks>().goto_next(resources);
+    }
+
+    if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT_TOGGLE_RT0_FORMAT)) {
+        config::with_mut(|c| {
+            c.renderer.rt0_float = !c.renderer.rt0_float;
+            resources
+                .get::<RendererShared>()
+                .set_render_settings(c.renderer.clone());
+        });
+    }
 }
 
 fn focus_selected(resources: &mut AppResources) {
@@ -166,14 +230,8 @@ fn focus_selected(resources: &mut AppResources) {
         }
     };
 
-    // Calculate the vertical field of view in radians
-    let half_fov_y = (cam.fov() * 0.5).to_radians();
-
-    // Calculate the distance required to fit the sphere in the frustum vertically
-    let distance = radius / half_fov_y.tan();
-
-    // Adjust the camera's position to ensure the sphere fits in the frustum
-    let target_position = center - cam.forward().normalize() * (distance * 1.75);
+    // Leave some room around the sphere
+    let target_position = cam.framing_position(center, radius * 1.75);
     cam.tween = Some(Tween::new(
         ease_out_exponential,
         Some((cam.position(), target_position)),
@@ -252,14 +310,24 @@ fn unhide_all(resources: &mut AppResources) {
 }
 
 fn goto_gaze(resources: &mut AppResources) {
-    let camera = resources.get_mut::<Camera>();
+    let mut camera = resources.get_mut::<Camera>();
     let (d, pos) = resources
         .get::<RendererShared>()
         .data
         .lock()
         .gbuffers
         .depth_buffer_distance_pos_center(&camera);
-    if d.is_finite() {
+    if !d.is_finite() {
+        return;
+    }
+
+    if camera.gaze_focus == GazeFocusMode::OrbitPivot {
+        // The gaze point is already in the center of the view, so the camera can stay where it is
+        camera.set_orbit_pivot(pos);
+        resources
+            .get_mut::<Toast>()
+            .show("Orbiting around the gaze point, move to stop");
+    } else {
         let mut action_list = resources.get_mut::<ActionList>();
         // Avoid potential weird interactions with routes
         action_list.clear_actions();
@@ -272,6 +340,52 @@ fn goto_gaze(resources: &mut AppResources) {
     }
 }
 
+/// Saves the current frame to a timestamped PNG file in the working directory
+pub fn capture_screenshot(resources: &AppResources) {
+    let path = PathBuf::from(format!(
+        "./screenshot_{}.png",
+        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+    ));
+
+    let message = match resources.get::<RendererShared>().capture_screenshot(&path) {
+        Ok(()) => format!("Saved screenshot to {}", path.display()),
+        Err(e) => {
+            error!("Failed to capture screenshot: {e:?}");
+            format!("Failed to capture screenshot: {e}")
+        }
+    };
+    resources.get_mut::<Toast>().show(message);
+}
+
+/// Moves the camera to just above the surface at the center of the screen
+pub fn snap_to_surface(resources: &AppResources) {
+    let camera = resources.get::<Camera>();
+    let surface = resources
+        .get::<RendererShared>()
+        .data
+        .lock()
+        .gbuffers
+        .depth_buffer_surface_center(&camera);
+
+    let Some((pos, normal)) = surface else {
+        resources
+            .get_mut::<Toast>()
+            .show("Nothing to snap to, look at a surface first");
+        return;
+    };
+
+    let offset = config::with(|c| c.visual.surface_snap_offset);
+    let mut action_list = resources.get_mut::<ActionList>();
+    // Avoid potential weird interactions with routes
+    action_list.clear_actions();
+    action_list.add_action(TweenAction::new(
+        ease_out_exponential,
+        Some((camera.position(), pos + normal * offset)),
+        None,
+        0.7,
+    ));
+}
+
 fn select_parent(resources: &mut AppResources) {
     let mut selected = resources.get_mut::<SelectedEntity>();
     let mut maps = resources.get_mut::<MapList>();
@@ -319,3 +433,40 @@ fn select_child_offset(resources: &mut AppResources, add: bool) {
         }
     }
 }
+
+/// Selects the next or previous material variant of the selected dynamic model, wrapping around at either end
+fn cycle_material_variant(resources: &AppResources, forward: bool) {
+    let Some(selected) = resources.get::<SelectedEntity>().selected() else {
+        return;
+    };
+
+    let variant = {
+        let mut maps = resources.get_mut::<MapList>();
+        let Some(map) = maps.current_map_mut() else {
+            return;
+        };
+        let Some(mut dynamic) = map.scene.get_mut::<DynamicModelComponent>(selected) else {
+            return;
+        };
+
+        let count = dynamic.model.variant_count();
+        if count == 0 {
+            return;
+        }
+
+        let current = dynamic.model.selected_variant % count;
+        dynamic.model.selected_variant = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        };
+        dynamic.mark_dirty();
+
+        format!(
+            "Material variant {}/{count}",
+            dynamic.model.selected_variant + 1
+        )
+    };
+
+    resources.get_mut::<Toast>().show(variant);
+}
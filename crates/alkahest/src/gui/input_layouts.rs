@@ -0,0 +1,101 @@
+use alkahest_renderer::{gpu::global_state::RenderStates, renderer::RendererShared};
+use egui::{Color32, RichText};
+use winit::window::Window;
+
+use crate::{
+    gui::context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+    resources::AppResources,
+};
+
+/// Lists the registered input layouts and their elements, along with any out of range layout indices meshes have requested
+#[derive(Default)]
+pub struct InputLayoutsPanel;
+
+impl GuiView for InputLayoutsPanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut open = resources.get::<HiddenWindows>().input_layouts;
+        if !open {
+            return None;
+        }
+
+        let invalid = resources
+            .get::<RendererShared>()
+            .gpu
+            .invalid_input_layouts();
+        let layouts = RenderStates::input_layout_descs();
+        egui::Window::new("Input Layouts")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if invalid.is_empty() {
+                    ui.label(format!(
+                        "{} layouts, no invalid indices requested",
+                        layouts.len()
+                    ));
+                } else {
+                    let indices = invalid.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+                    ui.label(
+                        RichText::new(format!(
+                            "Out of range indices requested: {}",
+                            indices.join(", ")
+                        ))
+                        .color(Color32::LIGHT_RED),
+                    )
+                    .on_hover_text("Draws using these indices were skipped");
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(480.0)
+                    .show(ui, |ui| {
+                        for (i, layout) in layouts.iter().enumerate() {
+                            let instanced = layout.elements.iter().any(|e| e.is_instance_data);
+                            egui::CollapsingHeader::new(format!(
+                                "Layout {i} ({} elements{})",
+                                layout.elements.len(),
+                                if instanced { ", instanced" } else { "" }
+                            ))
+                            .id_salt(i)
+                            .show(ui, |ui| {
+                                egui::Grid::new(("input_layout", i))
+                                    .striped(true)
+                                    .num_columns(4)
+                                    .show(ui, |ui| {
+                                        ui.strong("Semantic");
+                                        ui.strong("Format");
+                                        ui.strong("Slot");
+                                        ui.strong("Rate");
+                                        ui.end_row();
+
+                                        for e in layout.elements {
+                                            ui.label(format!(
+                                                "{}{} ({})",
+                                                e.semantic_name.to_string_lossy(),
+                                                e.semantic_index,
+                                                e.hlsl_type
+                                            ));
+                                            ui.label(format!("{:?}", e.format));
+                                            ui.label(e.buffer_index.to_string());
+                                            ui.label(if e.is_instance_data {
+                                                "Instance"
+                                            } else {
+                                                "Vertex"
+                                            });
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                        }
+                    });
+            });
+
+        resources.get_mut::<HiddenWindows>().input_layouts = open;
+
+        None
+    }
+}
@@ -1,12 +1,15 @@
 use alkahest_data::map::{SLight, SLightCollection, SShadowingLight};
 use alkahest_renderer::{
     ecs::{
-        hierarchy::Children, map::CubemapVolume, render::light::LightRenderer,
-        transform::Transform, Scene,
+        hierarchy::Children,
+        map::CubemapVolume,
+        render::light::{LightRenderer, ShadowMapRenderer},
+        transform::Transform,
+        Scene,
     },
     icons::{ICON_LIGHTBULB_GROUP, ICON_LIGHTBULB_ON},
     renderer::RendererShared,
-    util::color::Color,
+    util::{black_magic::EntityRefDarkMagic, color::Color},
 };
 use bevy_ecs::{prelude::EntityRef, system::Commands};
 use egui::{Color32, RichText, Ui};
@@ -109,6 +112,27 @@ impl ComponentPanel for LightRenderer {
             });
         }
 
+        if let Some(mut shadowmap) = e.get_mut::<ShadowMapRenderer>() {
+            let global_resolution = renderer.settings.shadow_quality.resolution();
+            let mut resolution_override = shadowmap.resolution_override;
+            egui::ComboBox::from_label("Shadow Resolution")
+                .selected_text(match resolution_override {
+                    Some(r) => format!("{r}x{r}"),
+                    None => format!("Global ({global_resolution}x{global_resolution})"),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut resolution_override, None, "Global");
+                    for r in [256, 512, 1024, 2048, 4096] {
+                        ui.selectable_value(&mut resolution_override, Some(r), format!("{r}x{r}"));
+                    }
+                });
+
+            // The shadow map is resized the next time shadow maps are updated
+            if resolution_override != shadowmap.resolution_override {
+                shadowmap.resolution_override = resolution_override;
+            }
+        }
+
         if let Some(transform) = e.get::<Transform>() {
             renderer.immediate.cube_outline(
                 transform.local_to_world() * self.projection_matrix,
@@ -26,8 +26,9 @@ use alkahest_renderer::{
         ICON_ACCOUNT_CONVERT, ICON_EYE_ARROW_RIGHT_OUTLINE, ICON_HUMAN_MALE,
         ICON_HUMAN_MALE_FEMALE_CHILD, ICON_POKEBALL,
     },
-    renderer::RendererShared,
+    renderer::{entity_feature_renderer, LightingSetup, RendererShared},
     shader::shader_ball::ShaderBallComponent,
+    tfx::view::RenderStageSubscriptions,
     util::{black_magic::EntityRefDarkMagic, Hocus},
 };
 use bevy_ecs::{entity::Entity, prelude::EntityRef, system::Commands};
@@ -37,6 +38,7 @@ use glam::{Quat, Vec3};
 use winit::window::Window;
 
 use crate::{
+    config,
     gui::{
         chip::EcsTagsExt,
         context::{GuiCtx, GuiView, ViewAction},
@@ -45,10 +47,12 @@ use crate::{
             ICON_AXIS_ARROW, ICON_CAMERA_CONTROL, ICON_CUBE_OUTLINE, ICON_DELETE, ICON_EYE,
             ICON_EYE_OFF, ICON_RADIUS_OUTLINE, ICON_RESIZE, ICON_ROTATE_ORBIT, ICON_TAG,
         },
+        toast::Toast,
     },
     input_float3,
     maplist::MapList,
     resources::AppResources,
+    util::hash::parse_tag_hash,
 };
 
 pub struct InspectorPanel;
@@ -257,6 +261,7 @@ pub fn show_inspector_panel(
         };
         ui.separator();
     }
+    show_draw_diagnostics(ui, scene, ent, resources);
     show_inspector_components(ui, scene.pocus(), &mut cmd, e, resources);
 
     if global_changed {
@@ -268,6 +273,29 @@ pub fn show_inspector_panel(
     }
 }
 
+fn show_draw_diagnostics(ui: &mut egui::Ui, scene: &Scene, ent: Entity, resources: &AppResources) {
+    if entity_feature_renderer(scene, ent).is_none() {
+        return;
+    }
+
+    // Only gathered while the panel is open, as the checks walk the whole model
+    egui::CollapsingHeader::new(RichText::new("Why isn't this rendering?").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            let Some(blockers) = resources.get::<RendererShared>().draw_blockers(scene, ent) else {
+                return;
+            };
+            if blockers.is_empty() {
+                ui.label("Nothing is preventing this entity from being drawn");
+            }
+
+            for blocker in blockers {
+                ui.colored_label(Color32::YELLOW, format!("• {blocker}"));
+            }
+        });
+    ui.separator();
+}
+
 fn show_inspector_components(
     ui: &mut egui::Ui,
     scene: &mut Scene,
@@ -514,15 +542,64 @@ impl ComponentPanel for DynamicModelComponent {
         _: &mut Commands<'_, '_>,
         _: EntityRef<'_>,
         ui: &mut egui::Ui,
-        _: &AppResources,
+        resources: &AppResources,
     ) {
         ui.horizontal(|ui| {
             ui.strong("Hash:");
             ui.label(self.model.hash.to_string());
         });
+        ui.horizontal(|ui| {
+            let mut export = None;
+            if ui
+                .button("Export OBJ")
+                .on_hover_text(
+                    "Save the highest detail LOD to a Wavefront OBJ file in the working directory",
+                )
+                .clicked()
+            {
+                let path = format!("./{}.obj", self.model.hash);
+                export = Some((self.model.export_obj(&path), path));
+            }
+            if ui
+                .button("Export glTF")
+                .on_hover_text(
+                    "Save the highest detail LOD to a glTF file in the working directory, with \
+                     materials named after their technique",
+                )
+                .clicked()
+            {
+                let path = format!("./{}.gltf", self.model.hash);
+                export = Some((self.model.export_gltf(self.ext.mesh_to_world, &path), path));
+            }
+            if ui
+                .button("Copy Structure")
+                .on_hover_text(
+                    "Copy a description of the meshes, parts and render stages of this model",
+                )
+                .clicked()
+            {
+                ui.ctx().copy_text(self.model.describe());
+                resources
+                    .get_mut::<Toast>()
+                    .show("Copied model structure to clipboard");
+            }
+
+            if let Some((result, path)) = export {
+                let message = match result {
+                    Ok(()) => format!("Exported model to {path}"),
+                    Err(e) => {
+                        error!("Failed to export model {}: {e:?}", self.model.hash);
+                        format!("Failed to export model: {e}")
+                    }
+                };
+                resources.get_mut::<Toast>().show(message);
+            }
+        });
         ui.separator();
 
         let mesh_count = self.model.mesh_count();
+        // The model might have been reloaded with fewer meshes
+        self.model.selected_mesh = self.model.selected_mesh.min(mesh_count.saturating_sub(1));
         if mesh_count > 1 {
             egui::ComboBox::from_label("Mesh").show_index(
                 ui,
@@ -531,9 +608,62 @@ impl ComponentPanel for DynamicModelComponent {
                 |i| format!("Mesh {i}"),
             );
         }
+        if let Some(mesh) = self.model.model.meshes.get(self.model.selected_mesh) {
+            ui.horizontal(|ui| {
+                ui.strong("Parts:");
+                ui.label(mesh.parts.len().to_string());
+            });
+
+            let part_count = mesh.parts.len();
+            let mut highlight = self.model.highlighted_part.is_some();
+            let mut part_index = self
+                .model
+                .highlighted_part
+                .unwrap_or(0)
+                .min(part_count.saturating_sub(1));
+            ui.horizontal(|ui| {
+                ui.add_enabled(
+                    part_count > 0,
+                    egui::Checkbox::new(&mut highlight, "Highlight part"),
+                )
+                .on_hover_text(
+                    "Only outline a single part of the mesh while the entity is selected",
+                );
+                ui.add_enabled(
+                    highlight,
+                    egui::DragValue::new(&mut part_index).range(0..=part_count.saturating_sub(1)),
+                );
+            });
+            self.model.highlighted_part = (highlight && part_count > 0).then_some(part_index);
+
+            if let Some(part) = self.model.highlighted_part.and_then(|i| mesh.parts.get(i)) {
+                ui.label(format!(
+                    "ID {}, {} indices, {:?}",
+                    part.external_identifier, part.index_count, part.lod_category
+                ));
+            }
+            ui.horizontal_wrapped(|ui| {
+                ui.strong("Stages:");
+                render_stage_list(ui, self.model.mesh_stages[self.model.selected_mesh]);
+            });
+        }
 
         let identifier_count = self.model.identifier_count();
+        // The model might have been reloaded with fewer identifiers
+        if self.identifier != u16::MAX && self.identifier as usize >= identifier_count {
+            self.identifier = u16::MAX;
+        }
         if identifier_count > 1 {
+            // Number of parts in the selected mesh for each identifier
+            let mut part_counts = vec![0; identifier_count];
+            if let Some(mesh) = self.model.model.meshes.get(self.model.selected_mesh) {
+                for part in &mesh.parts {
+                    if let Some(count) = part_counts.get_mut(part.external_identifier as usize) {
+                        *count += 1;
+                    }
+                }
+            }
+
             egui::ComboBox::from_label("Identifier")
                 .selected_text(if self.identifier == u16::MAX {
                     "All".to_string()
@@ -542,8 +672,12 @@ impl ComponentPanel for DynamicModelComponent {
                 })
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.identifier, u16::MAX, "All");
-                    for i in 0..identifier_count {
-                        ui.selectable_value(&mut self.identifier, i as u16, format!("ID {i}"));
+                    for (i, part_count) in part_counts.iter().enumerate() {
+                        ui.selectable_value(
+                            &mut self.identifier,
+                            i as u16,
+                            format!("ID {i} ({part_count} parts)"),
+                        );
                     }
 
                     if ui.input(|i| i.key_pressed(Key::ArrowUp)) {
@@ -564,7 +698,9 @@ impl ComponentPanel for DynamicModelComponent {
                             }
                         }
                     }
-                });
+                })
+                .response
+                .on_hover_text("Only draw the parts with this external identifier");
         }
 
         let variant_count = self.model.variant_count();
@@ -572,8 +708,43 @@ impl ComponentPanel for DynamicModelComponent {
             ui.style_mut().spacing.slider_width = 200.0;
             egui::Slider::new(&mut self.model.selected_variant, 0..=(variant_count - 1))
                 .text("Material Variant")
-                .ui(ui);
+                .ui(ui)
+                .on_hover_text("Cycle with [ and ] while the model is selected");
         }
+
+        ui.collapsing("Render Stages", |ui| {
+            ui.horizontal(|ui| {
+                ui.strong("Compute skinning:");
+                ui.label(
+                    if self
+                        .model
+                        .subscribed_stages
+                        .contains(RenderStageSubscriptions::COMPUTE_SKINNING)
+                    {
+                        "yes"
+                    } else {
+                        "no"
+                    },
+                );
+            });
+
+            ui.strong("Model:");
+            render_stage_list(ui, self.model.subscribed_stages);
+
+            for (i, stages) in self.model.mesh_stages.iter().enumerate() {
+                ui.strong(format!("Mesh {i}:"));
+                render_stage_list(ui, *stages);
+            }
+        });
+    }
+}
+
+fn render_stage_list(ui: &mut egui::Ui, stages: RenderStageSubscriptions) {
+    let stages: Vec<String> = stages.stages().map(|s| format!("{s:?}")).collect();
+    if stages.is_empty() {
+        ui.label(RichText::new("None").weak());
+    } else {
+        ui.label(stages.join(", "));
     }
 }
 
@@ -590,10 +761,42 @@ impl ComponentPanel for ShaderBallComponent {
         &mut self,
         _: &mut Scene,
         _: &mut Commands<'_, '_>,
-        _: EntityRef<'_>,
+        e: EntityRef<'_>,
         ui: &mut egui::Ui,
-        _: &AppResources,
+        resources: &AppResources,
     ) {
+        ui.horizontal(|ui| {
+            if ui
+                .button(format!("{ICON_CAMERA_CONTROL} Move to camera"))
+                .on_hover_text("Place the ball in front of the camera")
+                .clicked()
+            {
+                if let Some(mut transform) = e.get_mut::<Transform>() {
+                    transform.translation =
+                        ShaderBallComponent::position_in_front_of(&resources.get::<Camera>());
+                }
+            }
+
+            config::with_mut(|c| {
+                let current = c.renderer.current_lighting_setup();
+                if ui
+                    .button(format!(
+                        "Lighting: {}",
+                        current.map_or("Custom".to_string(), |s| s.to_string())
+                    ))
+                    .on_hover_text("Cycle through standard lighting setups")
+                    .clicked()
+                {
+                    c.renderer
+                        .apply_lighting_setup(current.map_or(LightingSetup::Map, |s| s.next()));
+                    resources
+                        .get::<RendererShared>()
+                        .set_render_settings(c.renderer.clone());
+                }
+            });
+        });
+        ui.separator();
+
         ui.horizontal(|ui| {
             ui.strong("Color:");
             ui.color_edit_button_rgb(self.color.as_mut());
@@ -618,6 +821,50 @@ impl ComponentPanel for ShaderBallComponent {
             ui.strong("Transmission:");
             egui::Slider::new(&mut self.transmission, 0.0..=1.0).ui(ui);
         });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.strong("Technique:");
+            let id = ui.id().with("shader_ball_technique");
+            let mut text = ui.data(|d| d.get_temp::<String>(id)).unwrap_or_default();
+            egui::TextEdit::singleline(&mut text)
+                .hint_text("Tag hash")
+                .desired_width(96.0)
+                .ui(ui);
+            if ui
+                .button("Assign")
+                .on_hover_text("Draw the ball with a game technique instead of the material above")
+                .clicked()
+            {
+                match parse_tag_hash(&text) {
+                    Ok(hash) => {
+                        let renderer = resources.get::<RendererShared>();
+                        self.technique = Some(
+                            renderer
+                                .data
+                                .lock()
+                                .asset_manager
+                                .get_or_load_technique(hash.hash32()),
+                        );
+                    }
+                    Err(e) => resources
+                        .get_mut::<Toast>()
+                        .show(format!("Invalid technique hash: {e}")),
+                }
+            }
+            if ui
+                .add_enabled(self.technique.is_some(), egui::Button::new("Clear"))
+                .clicked()
+            {
+                self.technique = None;
+            }
+            ui.data_mut(|d| d.insert_temp(id, text));
+        });
+        if let Some(hash) = self.technique.as_ref().and_then(|t| t.id().tiger_taghash()) {
+            ui.label(
+                RichText::new(format!("Drawing the GBuffer pass with technique {hash}")).weak(),
+            );
+        }
     }
 }
 
@@ -5,11 +5,68 @@ use winit::window::Window;
 use crate::{
     gui::{
         bottom_bar::LoadingIcon,
-        context::{GuiCtx, GuiView, ViewAction},
+        context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
     },
     maplist::{MapList, MapLoadState},
+    util::frame_stats::FrameStatsLogger,
 };
 
+/// Snapshot of the work that's currently being done in the background
+pub struct BackgroundTasks {
+    pub loading_maps: Vec<String>,
+    pub asset_requests: usize,
+    pub asset_requests_in_flight: usize,
+    pub logging_frame_stats: bool,
+}
+
+impl BackgroundTasks {
+    pub fn collect(resources: &AppResources) -> Self {
+        let (asset_requests, asset_requests_in_flight) = {
+            let renderer = resources.get::<RendererShared>();
+            let am = &renderer.data.lock().asset_manager;
+            if am.is_idle() {
+                (0, 0)
+            } else {
+                (am.remaining_requests(), am.in_flight_requests())
+            }
+        };
+
+        Self {
+            loading_maps: resources
+                .get::<MapList>()
+                .maps
+                .iter()
+                .filter(|m| m.load_state == MapLoadState::Loading)
+                .map(|m| m.name.clone())
+                .collect(),
+            asset_requests,
+            asset_requests_in_flight,
+            logging_frame_stats: resources.get::<FrameStatsLogger>().is_running(),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.loading_maps.len() + self.asset_requests + self.logging_frame_stats as usize
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// Short description of the most important task that's running
+    pub fn phase(&self) -> Option<String> {
+        if let Some(map) = self.loading_maps.first() {
+            Some(format!("Loading map '{map}'"))
+        } else if self.asset_requests != 0 {
+            Some("Loading resources".to_string())
+        } else if self.logging_frame_stats {
+            Some("Logging frame stats".to_string())
+        } else {
+            None
+        }
+    }
+}
+
 pub struct ResourceLoadIndicatorOverlay;
 
 impl GuiView for ResourceLoadIndicatorOverlay {
@@ -20,19 +77,30 @@ impl GuiView for ResourceLoadIndicatorOverlay {
         resources: &AppResources,
         _gui: &GuiCtx<'_>,
     ) -> Option<ViewAction> {
-        let renderer = resources.get::<RendererShared>();
-        let am = &renderer.data.lock().asset_manager;
-        let open = !am.is_idle();
-
-        if open {
+        // The details are opened from the background task indicator in the bottom bar
+        let tasks = BackgroundTasks::collect(resources);
+        if resources.get::<HiddenWindows>().background_tasks && !tasks.is_idle() {
             egui::Window::new("Loading")
                 .anchor(egui::Align2::RIGHT_TOP, [-12.0, 32.0])
                 .title_bar(false)
                 .show(ctx, |ui| {
-                    self.show_indicator(
-                        ui,
-                        format!("Loading {} resources", am.remaining_requests()),
-                    );
+                    for map in &tasks.loading_maps {
+                        self.show_indicator(ui, format!("Loading map '{map}'"));
+                    }
+
+                    if tasks.asset_requests != 0 {
+                        self.show_indicator(
+                            ui,
+                            format!(
+                                "Loading {} resources ({} in progress)",
+                                tasks.asset_requests, tasks.asset_requests_in_flight
+                            ),
+                        );
+                    }
+
+                    if tasks.logging_frame_stats {
+                        self.show_indicator(ui, "Logging frame stats");
+                    }
                 });
         }
 
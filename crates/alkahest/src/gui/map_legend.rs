@@ -0,0 +1,114 @@
+use alkahest_renderer::{
+    renderer::RenderDebugView,
+    shader::{feature_color::FeatureColorRenderer, map_tint::MapTintRenderer},
+};
+use egui::{pos2, vec2, Color32, Rect};
+use winit::window::Window;
+
+use crate::{
+    config,
+    gui::{
+        context::{GuiCtx, GuiView, ViewAction},
+        util::{overlay_painter, PainterExt},
+    },
+    maplist::MapList,
+    resources::AppResources,
+};
+
+/// Shows which color belongs to which map while the map tint is enabled
+pub struct MapTintLegendOverlay;
+
+impl MapTintLegendOverlay {
+    const ROW_HEIGHT: f32 = 18.0;
+    /// Keeps the legend clear of the bottom bar
+    const BOTTOM_MARGIN: f32 = 40.0;
+}
+
+impl GuiView for MapTintLegendOverlay {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        if !config::with(|c| c.renderer.draw_map_tint) {
+            return None;
+        }
+
+        let maps = resources.get::<MapList>();
+        let indices = maps.visible_map_indices();
+
+        let painter = overlay_painter(ctx);
+        let bottom_left = ctx.screen_rect().left_bottom() + vec2(8.0, -Self::BOTTOM_MARGIN);
+        for (i, &map_index) in indices.iter().enumerate() {
+            let map = &maps.maps[map_index];
+            let row_center = bottom_left.y - (indices.len() - i) as f32 * Self::ROW_HEIGHT
+                + Self::ROW_HEIGHT / 2.0;
+
+            let color = Color32::from(MapTintRenderer::color(i));
+            painter.rect_filled(
+                Rect::from_center_size(pos2(bottom_left.x + 6.0, row_center), vec2(12.0, 12.0)),
+                2.0,
+                color,
+            );
+            painter.text_with_shadow(
+                pos2(bottom_left.x + 18.0, row_center),
+                egui::Align2::LEFT_CENTER,
+                format!("{} ({})", map.name, map.hash),
+                egui::FontId::proportional(14.0),
+                Color32::WHITE,
+            );
+        }
+
+        None
+    }
+}
+
+/// Shows which color belongs to which feature renderer in the feature colors debug view
+pub struct FeatureColorLegendOverlay;
+
+impl GuiView for FeatureColorLegendOverlay {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        _resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        if config::with(|c| c.renderer.debug_view) != RenderDebugView::FeatureColors {
+            return None;
+        }
+
+        let rows = FeatureColorRenderer::LEGEND
+            .iter()
+            .map(|&(feature, name)| (FeatureColorRenderer::color(feature), name))
+            .chain([(FeatureColorRenderer::OTHER_COLOR, "Other")])
+            .collect::<Vec<_>>();
+
+        // Drawn in the bottom right corner, so it doesn't overlap the map tint legend
+        let painter = overlay_painter(ctx);
+        let bottom_right =
+            ctx.screen_rect().right_bottom() + vec2(-8.0, -MapTintLegendOverlay::BOTTOM_MARGIN);
+        for (i, (color, name)) in rows.iter().enumerate() {
+            let row_center = bottom_right.y
+                - (rows.len() - i) as f32 * MapTintLegendOverlay::ROW_HEIGHT
+                + MapTintLegendOverlay::ROW_HEIGHT / 2.0;
+
+            painter.rect_filled(
+                Rect::from_center_size(pos2(bottom_right.x - 6.0, row_center), vec2(12.0, 12.0)),
+                2.0,
+                Color32::from(*color),
+            );
+            painter.text_with_shadow(
+                pos2(bottom_right.x - 18.0, row_center),
+                egui::Align2::RIGHT_CENTER,
+                *name,
+                egui::FontId::proportional(14.0),
+                Color32::WHITE,
+            );
+        }
+
+        None
+    }
+}
@@ -1,22 +1,26 @@
 use alkahest_renderer::icons::{
     ICON_ALPHA_A_BOX_OUTLINE, ICON_ALPHA_D_BOX_OUTLINE, ICON_ALPHA_E_BOX_OUTLINE,
     ICON_ALPHA_G_BOX_OUTLINE, ICON_ALPHA_H_BOX_OUTLINE, ICON_ALPHA_I_BOX_OUTLINE,
-    ICON_ALPHA_Q_BOX_OUTLINE, ICON_ALPHA_S_BOX_OUTLINE, ICON_ALPHA_W_BOX_OUTLINE,
-    ICON_APPLE_KEYBOARD_SHIFT, ICON_ARROW_ALL, ICON_ARROW_DOWN_BOLD_BOX_OUTLINE,
-    ICON_ARROW_LEFT_BOLD_BOX_OUTLINE, ICON_ARROW_RIGHT_BOLD_BOX_OUTLINE,
-    ICON_ARROW_UP_BOLD_BOX_OUTLINE, ICON_KEYBOARD_SPACE, ICON_MINUS_BOX_OUTLINE,
-    ICON_MOUSE_LEFT_CLICK_OUTLINE, ICON_MOUSE_RIGHT_CLICK_OUTLINE, ICON_NUMERIC_1_BOX_OUTLINE,
-    ICON_NUMERIC_2_BOX_OUTLINE, ICON_NUMERIC_3_BOX_OUTLINE, ICON_NUMERIC_4_BOX_OUTLINE,
-    ICON_PLUS_BOX_OUTLINE,
+    ICON_ALPHA_Q_BOX_OUTLINE, ICON_ALPHA_R_BOX_OUTLINE, ICON_ALPHA_S_BOX_OUTLINE,
+    ICON_ALPHA_W_BOX_OUTLINE, ICON_ALPHA_Z_BOX_OUTLINE, ICON_APPLE_KEYBOARD_SHIFT, ICON_ARROW_ALL,
+    ICON_ARROW_DOWN_BOLD_BOX_OUTLINE, ICON_ARROW_LEFT_BOLD_BOX_OUTLINE,
+    ICON_ARROW_RIGHT_BOLD_BOX_OUTLINE, ICON_ARROW_UP_BOLD_BOX_OUTLINE, ICON_KEYBOARD_SPACE,
+    ICON_MINUS_BOX_OUTLINE, ICON_MOUSE_LEFT_CLICK_OUTLINE, ICON_MOUSE_RIGHT_CLICK_OUTLINE,
+    ICON_MOUSE_SCROLL_WHEEL, ICON_NUMERIC_1_BOX_OUTLINE, ICON_NUMERIC_2_BOX_OUTLINE,
+    ICON_NUMERIC_3_BOX_OUTLINE, ICON_NUMERIC_4_BOX_OUTLINE, ICON_PLUS_BOX_OUTLINE,
 };
 use egui::{vec2, Color32, RichText, Vec2};
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
 use winit::window::Window;
 
 use crate::{
+    config,
     gui::context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
     resources::AppResources,
-    util::{consts, consts::CHANGELOG_MD},
+    util::{
+        consts, consts::CHANGELOG_MD, feature_capture::FeatureCapture,
+        frame_stats::FrameStatsLogger,
+    },
 };
 
 mod help;
@@ -72,6 +76,57 @@ impl GuiView for MenuBar {
                     windows.tfx_extern_editor ^= ui
                         .selectable_label(windows.tfx_extern_editor, "TFX Extern Editor")
                         .clicked();
+                    windows.gpu_object_monitor ^= ui
+                        .selectable_label(windows.gpu_object_monitor, "GPU Object Monitor")
+                        .clicked();
+                    windows.asset_cache ^= ui
+                        .selectable_label(windows.asset_cache, "Asset Cache")
+                        .clicked();
+                    windows.input_layouts ^= ui
+                        .selectable_label(windows.input_layouts, "Input Layouts")
+                        .clicked();
+                    windows.minimap ^= ui.selectable_label(windows.minimap, "Minimap").clicked();
+                    windows.camera_bookmarks ^= ui
+                        .selectable_label(windows.camera_bookmarks, "Camera Bookmarks")
+                        .clicked();
+                    windows.scene_statistics ^= ui
+                        .selectable_label(windows.scene_statistics, "Scene Statistics")
+                        .clicked();
+                    config::with_mut(|c| {
+                        c.visual.status_bar_camera ^= ui
+                            .selectable_label(c.visual.status_bar_camera, "Camera Status")
+                            .clicked();
+                    });
+
+                    ui.separator();
+                    let mut frame_stats = resources.get_mut::<FrameStatsLogger>();
+                    if ui
+                        .selectable_label(frame_stats.is_running(), "Log Frame Stats to CSV")
+                        .clicked()
+                    {
+                        if frame_stats.is_running() {
+                            frame_stats.stop();
+                        } else if let Err(e) = frame_stats.start() {
+                            error!("Failed to start frame stats logger: {e}");
+                        }
+                    }
+
+                    let mut feature_capture = resources.get_mut::<FeatureCapture>();
+                    if ui
+                        .add_enabled(
+                            !feature_capture.is_running(),
+                            egui::Button::new("Capture Feature Layers"),
+                        )
+                        .on_hover_text(
+                            "Saves a screenshot of every feature renderer on its own, to a new feature_layers_<timestamp> folder",
+                        )
+                        .clicked()
+                    {
+                        if let Err(e) = feature_capture.start() {
+                            error!("Failed to start feature layer capture: {e}");
+                        }
+                        ui.close_menu();
+                    }
                 });
 
                 ui.menu_button("Help", |ui| {
@@ -216,9 +271,26 @@ impl MenuBar {
                             control_description!(
                                 ui,
                                 ICON_ALPHA_G_BOX_OUTLINE,
-                                "Move Camera to Position of Gaze"
+                                "Move Camera to Position of Gaze, or Orbit Around It"
                             );
 
+                            control_description!(
+                                ui,
+                                format!(
+                                    "{} Shift + {}",
+                                    ICON_APPLE_KEYBOARD_SHIFT, ICON_ALPHA_G_BOX_OUTLINE
+                                ),
+                                "Snap Camera to Surface"
+                            );
+
+                            control_description!(
+                                ui,
+                                format!("Ctrl + {}", ICON_MOUSE_SCROLL_WHEEL),
+                                "Zoom In/Out"
+                            );
+
+                            control_description!(ui, ICON_ALPHA_Z_BOX_OUTLINE, "Reset Zoom");
+
                             control_section_title!(ui, "Object Interactions");
 
                             control_description!(
@@ -354,6 +426,32 @@ impl MenuBar {
                                 ICON_MINUS_BOX_OUTLINE,
                                 "Add node before selected node"
                             );
+
+                            control_section_title!(ui, "Interface");
+
+                            control_description!(
+                                ui,
+                                format!(
+                                    "Ctrl + {} Shift + {}",
+                                    ICON_APPLE_KEYBOARD_SHIFT, ICON_ALPHA_H_BOX_OUTLINE
+                                ),
+                                "Toggle Panels"
+                            );
+
+                            control_description!(
+                                ui,
+                                format!("Ctrl + Alt + {}", ICON_ALPHA_H_BOX_OUTLINE),
+                                "Toggle Cinematic Mode (hides all UI)"
+                            );
+
+                            control_description!(
+                                ui,
+                                format!(
+                                    "Ctrl + {} Shift + {}",
+                                    ICON_APPLE_KEYBOARD_SHIFT, ICON_ALPHA_R_BOX_OUTLINE
+                                ),
+                                "Toggle Float Albedo (RT0)"
+                            );
                         });
                 });
             });
@@ -10,15 +10,22 @@ use alkahest_renderer::{
         utility::{Beacon, Ruler, Sphere, Utility},
         SceneInfo,
     },
-    icons::{ICON_MAP_MARKER_PATH, ICON_POKEBALL, ICON_RULER_SQUARE, ICON_SIGN_POLE, ICON_SPHERE},
+    icons::{
+        ICON_CAMERA_MARKER, ICON_CLIPBOARD_CHECK_OUTLINE, ICON_MAP_MARKER_PATH, ICON_POKEBALL,
+        ICON_POUND, ICON_RULER_SQUARE, ICON_SIGN_POLE, ICON_SPHERE,
+    },
     renderer::RendererShared,
     resources::AppResources,
     shader::shader_ball::ShaderBallComponent,
 };
-use egui::Ui;
+use egui::{Ui, Widget};
 use glam::Vec3;
 
-use crate::{gui::menu::MenuBar, maplist::MapList};
+use crate::{
+    config,
+    gui::{context::HiddenWindows, hotkeys::snap_to_surface, menu::MenuBar},
+    maplist::MapList,
+};
 
 impl MenuBar {
     pub(super) fn utility_menu(&self, ui: &mut Ui, resources: &AppResources) {
@@ -177,6 +184,28 @@ impl MenuBar {
 
         ui.separator();
 
+        ui.horizontal(|ui| {
+            if ui
+                .button(format!("{} Snap Camera to Surface", ICON_CAMERA_MARKER))
+                .on_hover_text("Moves the camera to just above the surface you're looking at")
+                .clicked()
+            {
+                snap_to_surface(resources);
+                ui.close_menu();
+            }
+
+            config::with_mut(|c| {
+                egui::DragValue::new(&mut c.visual.surface_snap_offset)
+                    .range(0.05..=10.0)
+                    .speed(0.01)
+                    .suffix(" m")
+                    .ui(ui)
+                    .on_hover_text("Distance from the surface");
+            });
+        });
+
+        ui.separator();
+
         if ui
             .button(format!("{} Material Ball", ICON_POKEBALL))
             .clicked()
@@ -185,11 +214,12 @@ impl MenuBar {
             let renderer = resources.get::<RendererShared>();
 
             if let Some(map) = maps.current_map_mut() {
-                let camera = resources.get::<Camera>();
+                let position =
+                    ShaderBallComponent::position_in_front_of(&resources.get::<Camera>());
                 let e = map.scene.spawn((
                     Icon::Unicode(ICON_POKEBALL),
                     Label::from("Material Ball"),
-                    Transform::from_translation(camera.position()),
+                    Transform::from_translation(position),
                     ShaderBallComponent::new(&renderer).unwrap(),
                     Tags::from_iter([EntityTag::Utility]),
                     Mutable,
@@ -201,5 +231,22 @@ impl MenuBar {
                 ui.close_menu();
             }
         }
+
+        if ui
+            .button(format!("{} Spawn by Tag Hash...", ICON_POUND))
+            .clicked()
+        {
+            resources.get_mut::<HiddenWindows>().spawn_by_hash = true;
+            ui.close_menu();
+        }
+
+        if ui
+            .button(format!("{} Validate Scene", ICON_CLIPBOARD_CHECK_OUTLINE))
+            .on_hover_text("Check the current map for entities with missing or broken components")
+            .clicked()
+        {
+            resources.get_mut::<HiddenWindows>().scene_validation = true;
+            ui.close_menu();
+        }
     }
 }
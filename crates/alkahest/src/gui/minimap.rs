@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant};
+
+use alkahest_data::dxgi::DxgiFormat;
+use alkahest_renderer::{
+    camera::{fps::FpsCamera, Camera, CameraProjection, Viewport},
+    ecs::Scene,
+    renderer::{gbuffer::RenderTarget, Renderer},
+    resources::AppResources,
+};
+use egui::{vec2, Color32, Sense, Stroke, TextureId, Widget};
+use glam::{UVec2, Vec2, Vec3};
+use windows::Win32::Graphics::Direct3D11::ID3D11ShaderResourceView;
+use winit::window::Window;
+
+use crate::gui::context::{GuiCtx, GuiView, HiddenWindows, ViewAction};
+
+/// Top-down orthographic view of the area around the camera
+pub struct Minimap {
+    camera: Camera,
+    target: Option<RenderTarget>,
+    /// Camera position at the time the minimap was last rendered
+    center: Vec3,
+    last_render: Instant,
+
+    /// Half the height of the area covered by the minimap, in meters
+    pub zoom: f32,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            camera: Camera::new(
+                Viewport {
+                    size: UVec2::ONE,
+                    origin: UVec2::ZERO,
+                },
+                CameraProjection::orthographic_sized(1.0),
+                Box::<FpsCamera>::default(),
+            ),
+            target: None,
+            center: Vec3::ZERO,
+            last_render: Instant::now() - Self::RENDER_INTERVAL,
+            zoom: 256.0,
+        }
+    }
+}
+
+impl Minimap {
+    /// The minimap doesn't need to be rendered every frame, as it's only used for rough navigation
+    const RENDER_INTERVAL: Duration = Duration::from_millis(250);
+    const RESOLUTION: u32 = 512;
+    /// Height of the minimap camera above the main camera. Everything up to [`CameraProjection::ORTHOGRAPHIC_DEPTH`] below it is visible
+    const CAMERA_HEIGHT: f32 = 2000.0;
+
+    /// Renders the minimap if it's due for an update. Must be called before the main view is rendered
+    pub fn render(
+        &mut self,
+        renderer: &Renderer,
+        main_camera: &Camera,
+        scenes: &mut [&mut Scene],
+        resources: &AppResources,
+    ) {
+        if self.last_render.elapsed() < Self::RENDER_INTERVAL {
+            return;
+        }
+        self.last_render = Instant::now();
+
+        // The minimap is rendered using the main GBuffer, so the viewport has to match the render resolution
+        let (render_width, render_height) = renderer.render_resolution();
+        let aspect = render_width as f32 / render_height as f32;
+        self.camera.set_viewport(Viewport {
+            size: UVec2::new(render_width, render_height),
+            origin: UVec2::ZERO,
+        });
+        self.camera
+            .set_projection(CameraProjection::orthographic_sized(self.zoom));
+
+        self.center = main_camera.position();
+        self.camera
+            .set_position(self.center + Vec3::Z * Self::CAMERA_HEIGHT);
+        // Not quite 90 degrees, as looking straight down makes the view matrix degenerate
+        self.camera.set_orientation(Vec2::new(89.9, 0.0));
+        self.camera.update_matrices();
+
+        let target_size = (
+            Self::RESOLUTION,
+            (Self::RESOLUTION as f32 / aspect).round() as u32,
+        );
+        if self.target.as_ref().map_or(true, |t| {
+            let desc = t.get_desc();
+            (desc.Width, desc.Height) != target_size
+        }) {
+            match RenderTarget::create(
+                target_size,
+                DxgiFormat::B8G8R8A8_UNORM,
+                renderer.gpu.clone(),
+                "Minimap",
+            ) {
+                Ok(target) => self.target = Some(target),
+                Err(e) => {
+                    error!("Failed to create minimap render target: {e}");
+                    return;
+                }
+            }
+        }
+
+        if let Some(target) = &self.target {
+            renderer.render_world_to(&self.camera, scenes, resources, target);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MinimapPanel {
+    texture: Option<(TextureId, ID3D11ShaderResourceView)>,
+}
+
+impl GuiView for MinimapPanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut windows = resources.get_mut::<HiddenWindows>();
+        egui::Window::new("Minimap")
+            .open(&mut windows.minimap)
+            .resizable(true)
+            .default_width(256.0)
+            .show(ctx, |ui| {
+                let mut minimap = resources.get_mut::<Minimap>();
+                egui::Slider::new(&mut minimap.zoom, 16.0..=4096.0)
+                    .logarithmic(true)
+                    .suffix("m")
+                    .text("Zoom")
+                    .ui(ui);
+
+                let Some(target) = &minimap.target else {
+                    ui.label("Waiting for minimap...");
+                    return;
+                };
+
+                // Re-register the texture with egui if the render target was recreated
+                if self
+                    .texture
+                    .as_ref()
+                    .map_or(true, |(_, view)| *view != target.view)
+                {
+                    let mut integration = gui._integration.borrow_mut();
+                    let textures = integration.textures_mut();
+                    if let Some((id, _)) = self.texture.take() {
+                        textures.free(id);
+                    }
+
+                    let id = textures
+                        .allocate_dx((target.view.clone(), Some(egui::TextureFilter::Linear)));
+                    self.texture = Some((id, target.view.clone()));
+                }
+
+                let Some((texture_id, _)) = self.texture else {
+                    return;
+                };
+
+                let desc = target.get_desc();
+                let aspect = desc.Width as f32 / desc.Height as f32;
+                let width = ui.available_width();
+                let size = vec2(width, width / aspect);
+                let response = ui.add(egui::Image::new((texture_id, size)).sense(Sense::click()));
+
+                let rect = response.rect;
+                let half_extents = vec2(minimap.zoom * aspect, minimap.zoom);
+                let world_to_minimap = |p: Vec3| {
+                    let offset = (p - minimap.center).truncate();
+                    rect.center() + vec2(offset.x, -offset.y) / half_extents * (rect.size() / 2.0)
+                };
+
+                let mut camera = resources.get_mut::<Camera>();
+                let marker_pos = world_to_minimap(camera.position());
+                let forward = camera.forward().truncate().normalize_or_zero();
+                let painter = ui.painter_at(rect);
+                painter.circle_filled(marker_pos, 4.0, Color32::RED);
+                painter.line_segment(
+                    [marker_pos, marker_pos + vec2(forward.x, -forward.y) * 12.0],
+                    Stroke::new(2.0, Color32::RED),
+                );
+
+                // Move the camera to the clicked location, keeping the current height
+                if response.clicked() {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        let offset = (pos - rect.center()) / (rect.size() / 2.0) * half_extents;
+                        let center = minimap.center;
+                        camera.set_position(Vec3::new(
+                            center.x + offset.x,
+                            center.y - offset.y,
+                            camera.position().z,
+                        ));
+                    }
+                }
+            });
+
+        None
+    }
+
+    fn dispose(&mut self, _ctx: &egui::Context, _resources: &AppResources, gui: &GuiCtx<'_>) {
+        if let Some((id, _)) = self.texture.take() {
+            gui._integration.borrow_mut().textures_mut().free(id);
+        }
+    }
+}
@@ -1,15 +1,24 @@
 use egui::Response;
 
 pub mod activity_select;
+mod asset_cache;
+pub mod bookmarks;
 mod configuration;
 pub mod context;
 mod fps_display;
+mod gpu_monitor;
 pub mod hotkeys;
 pub use alkahest_renderer::icons;
 mod input;
+mod input_layouts;
 pub mod inspector;
+pub mod minimap;
+mod scene_stats;
+mod scene_validation;
 mod sodi;
+mod spawn_by_hash;
 mod tfx;
+pub mod toast;
 
 // Custom widgets
 pub mod big_button;
@@ -20,11 +29,14 @@ pub mod console;
 mod crosshair;
 pub mod gizmo;
 mod load_indicator;
+mod map_legend;
 mod menu;
 mod node_gizmos;
 mod outliner;
+mod rt0_display;
 pub(crate) mod updater;
 mod util;
+mod zoom_display;
 
 pub use configuration::SelectionGizmoMode;
 
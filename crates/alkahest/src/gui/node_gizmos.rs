@@ -11,11 +11,14 @@ use alkahest_renderer::{
     icons::ICON_HELP,
     renderer::{ImmediateLabel, LabelAlign, RendererShared},
     resources::AppResources,
+    shader::icon_billboard::IconBillboard,
     ColorExt,
 };
 use bevy_ecs::entity::Entity;
 use egui::{Color32, Context, Pos2, Rect, Sense, Ui};
 use glam::{Vec2, Vec3};
+use rustc_hash::FxHashMap;
+use strum::IntoEnumIterator;
 use winit::window::Window;
 
 use crate::{
@@ -90,7 +93,29 @@ impl GuiView for NodeGizmoOverlay {
 
         // if self.debug_overlay.borrow().show_map_resources {
         if config::with(|c| c.visual.node_nametags) {
-            let named_nodes_only = config::with(|c| c.visual.node_nametags_named_only);
+            let (
+                named_nodes_only,
+                icon_scale,
+                label_size,
+                origins,
+                cluster_radius,
+                declutter,
+                billboards,
+            ) = config::with(|c| {
+                (
+                    c.visual.node_nametags_named_only,
+                    c.visual.node_icon_scale,
+                    c.visual.node_label_size,
+                    ResourceOrigin::iter()
+                        .filter(|o| c.visual.node_origins.contains(&o.to_string()))
+                        .collect::<Vec<_>>(),
+                    c.visual
+                        .node_clustering
+                        .then_some(c.visual.node_cluster_radius),
+                    c.visual.node_label_declutter,
+                    c.visual.node_icon_billboards,
+                )
+            });
             let mut maps = resources.get_mut::<MapList>();
             if let Some(map) = maps.current_map_mut() {
                 struct NodeDisplayPoint {
@@ -125,6 +150,10 @@ impl GuiView for NodeGizmoOverlay {
                         continue;
                     }
 
+                    if origin.is_some_and(|o| !origins.contains(o)) {
+                        continue;
+                    }
+
                     if let Some(filter) = filter {
                         if !filters.contains(filter) {
                             continue;
@@ -206,13 +235,79 @@ impl GuiView for NodeGizmoOverlay {
 
                 rp_list.reverse();
 
-                for (i, (e, _, translation, node)) in rp_list.iter().enumerate() {
-                    let projected_point = camera.world_to_projective.project_point3(*translation);
-
-                    let screen_point = Vec2::new(
+                let to_screen = |point: Vec3| {
+                    let projected_point = camera.world_to_projective.project_point3(point);
+                    Vec2::new(
                         ((projected_point.x + 1.0) * 0.5) * screen_size.x,
                         ((1.0 - projected_point.y) * 0.5) * screen_size.y,
-                    );
+                    )
+                };
+                let label_font = egui::FontId::proportional(label_size);
+                let label_pos = |screen_point: Vec2| -> Pos2 {
+                    (screen_point + Vec2::new(14.0 * icon_scale, 0.0))
+                        .to_array()
+                        .into()
+                };
+
+                // Billboards are sized in render target pixels, which can be scaled down from the window
+                let render_pixels_per_point = {
+                    let (render_width, _) = resources.get::<RendererShared>().render_resolution();
+                    render_width as f32 / screen_size.x
+                };
+                let mut icon_billboards = vec![];
+
+                // Nodes that are drawn as part of a cluster marker instead of individually
+                let mut clustered = vec![false; rp_list.len()];
+                let clusters = if let Some(radius) = cluster_radius {
+                    let points = rp_list
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .filter(|(_, (e, ..))| selected_entity.selected() != Some(*e))
+                        .map(|(i, (_, _, translation, _))| (i, to_screen(*translation)));
+
+                    let clusters = cluster_points(points, radius);
+                    for cluster in &clusters {
+                        for &i in &cluster.members {
+                            clustered[i] = true;
+                        }
+                    }
+                    clusters
+                } else {
+                    vec![]
+                };
+
+                let label_offsets = if declutter {
+                    let label_rects = rp_list
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .filter(|(i, _)| !clustered[*i])
+                        .take(MAX_DECLUTTERED_LABELS)
+                        .map(|(i, (_, _, translation, node))| {
+                            let galley = painter.layout_no_wrap(
+                                node.label.clone(),
+                                label_font.clone(),
+                                Color32::WHITE,
+                            );
+                            let rect = egui::Align2::LEFT_CENTER.anchor_rect(Rect::from_min_size(
+                                label_pos(to_screen(*translation)),
+                                galley.size(),
+                            ));
+                            (i, rect)
+                        });
+
+                    declutter_labels(label_rects, rp_list.len())
+                } else {
+                    vec![0.0; rp_list.len()]
+                };
+
+                for (i, (e, _, translation, node)) in rp_list.iter().enumerate() {
+                    if clustered[i] {
+                        continue;
+                    }
+
+                    let screen_point = to_screen(*translation);
 
                     let icon = node.icon.clone().unwrap_or(Icon::Unicode(ICON_HELP));
                     // let c = res.resource.debug_color();
@@ -223,9 +318,10 @@ impl GuiView for NodeGizmoOverlay {
                     if true {
                         let debug_string = &node.label;
 
-                        let debug_string_font = egui::FontId::proportional(14.0);
-                        let debug_string_pos: egui::Pos2 =
-                            (screen_point + Vec2::new(14.0, 0.0)).to_array().into();
+                        let debug_string_font = label_font.clone();
+                        let label_offset = label_offsets[i];
+                        let debug_string_pos =
+                            label_pos(screen_point) + egui::vec2(0.0, label_offset);
 
                         let debug_string_galley = painter.layout_no_wrap(
                             debug_string.clone(),
@@ -236,7 +332,19 @@ impl GuiView for NodeGizmoOverlay {
                         let mut debug_string_rect = egui::Align2::LEFT_CENTER.anchor_rect(
                             Rect::from_min_size(debug_string_pos, debug_string_galley.size()),
                         );
-                        debug_string_rect.extend_with_x(debug_string_pos.x - 11.0 - 14.0);
+                        if label_offset == 0.0 {
+                            debug_string_rect
+                                .extend_with_x(debug_string_pos.x - (11.0 + 14.0) * icon_scale);
+                        } else {
+                            // Moved labels are connected to their icon with a leader line instead
+                            painter.line_segment(
+                                [
+                                    Pos2::from(screen_point.to_array()),
+                                    debug_string_rect.left_center() - egui::vec2(4.0, 0.0),
+                                ],
+                                egui::Stroke::new(1.0, color.gamma_multiply(0.75)),
+                            );
+                        }
 
                         if selected_entity.selected() == Some(*e) {
                             painter.rect(
@@ -284,21 +392,30 @@ impl GuiView for NodeGizmoOverlay {
                         );
                     }
 
-                    painter.text(
-                        screen_point.to_array().into(),
-                        egui::Align2::CENTER_CENTER,
-                        icon.to_string(),
-                        egui::FontId::proportional(22.0),
-                        color,
-                    );
+                    if billboards {
+                        icon_billboards.push(IconBillboard {
+                            position: *translation,
+                            icon: icon.char(),
+                            color,
+                            size: 22.0 * icon_scale * render_pixels_per_point,
+                        });
+                    } else {
+                        painter.text(
+                            screen_point.to_array().into(),
+                            egui::Align2::CENTER_CENTER,
+                            icon.to_string(),
+                            egui::FontId::proportional(22.0 * icon_scale),
+                            color,
+                        );
+                    }
 
                     if node.has_havok_data {
                         painter.image(
                             gui.icons.icon_havok.id(),
                             egui::Rect::from_center_size(
                                 egui::Pos2::from(screen_point.to_array())
-                                    - egui::pos2(12., 12.).to_vec2(),
-                                egui::vec2(16.0, 16.0),
+                                    - egui::vec2(12.0, 12.0) * icon_scale,
+                                egui::vec2(16.0, 16.0) * icon_scale,
                             ),
                             egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
                             Color32::WHITE,
@@ -309,7 +426,7 @@ impl GuiView for NodeGizmoOverlay {
                         painter.rect(
                             egui::Rect::from_min_size(
                                 screen_point.to_array().into(),
-                                [11.0, 11.0].into(),
+                                egui::vec2(11.0, 11.0) * icon_scale,
                             ),
                             egui::CornerRadius::ZERO,
                             Color32::from_black_alpha(152),
@@ -319,26 +436,51 @@ impl GuiView for NodeGizmoOverlay {
 
                         if let Some(origin) = node.origin {
                             painter.text(
-                                egui::Pos2::from(screen_point.to_array()) + egui::vec2(5.5, 5.5),
+                                egui::Pos2::from(screen_point.to_array())
+                                    + egui::vec2(5.5, 5.5) * icon_scale,
                                 egui::Align2::CENTER_CENTER,
-                                match origin {
-                                    ResourceOrigin::Map => "M",
-                                    ResourceOrigin::Activity => "A",
-                                    ResourceOrigin::ActivityBruteforce => "Ab",
-                                    ResourceOrigin::Ambient => "AM",
-                                },
-                                egui::FontId::monospace(12.0),
-                                match origin {
-                                    ResourceOrigin::Map => Color32::LIGHT_RED,
-                                    ResourceOrigin::Activity => Color32::GREEN,
-                                    ResourceOrigin::ActivityBruteforce => Color32::RED,
-                                    ResourceOrigin::Ambient => Color32::from_rgb(0, 255, 255),
-                                },
+                                origin.short_name(),
+                                egui::FontId::monospace(12.0 * icon_scale),
+                                origin.color(),
                             );
                         }
                     }
                 }
 
+                for cluster in &clusters {
+                    let color = rp_list[cluster.nearest]
+                        .3
+                        .icon
+                        .as_ref()
+                        .map_or(Color32::WHITE, |icon| icon.color());
+                    let center: Pos2 = cluster.screen_point.to_array().into();
+                    let radius = 12.0 * icon_scale;
+
+                    painter.circle(
+                        center,
+                        radius,
+                        Color32::from_black_alpha(192),
+                        egui::Stroke::new(2.0, color),
+                    );
+                    painter.text(
+                        center,
+                        egui::Align2::CENTER_CENTER,
+                        cluster.members.len().to_string(),
+                        egui::FontId::proportional(13.0 * icon_scale),
+                        Color32::WHITE,
+                    );
+
+                    // Clicking a cluster selects the member closest to the camera
+                    let rect = Rect::from_center_size(center, egui::Vec2::splat(radius * 2.0));
+                    if response.hovered() {
+                        if let Some(mouse_pos) = ctx.input(|i| i.pointer.latest_pos()) {
+                            if rect.contains(mouse_pos) {
+                                top_hovered = Some((cluster.nearest, rect));
+                            }
+                        }
+                    }
+                }
+
                 if let Some((_top_index, top_rect)) = top_hovered {
                     let is_hovered = true;
 
@@ -358,6 +500,13 @@ impl GuiView for NodeGizmoOverlay {
                         egui::StrokeKind::Middle,
                     );
                 }
+
+                if billboards {
+                    resources
+                        .get::<RendererShared>()
+                        .icon_billboards
+                        .submit(icon_billboards);
+                }
             }
         }
 
@@ -367,9 +516,12 @@ impl GuiView for NodeGizmoOverlay {
             } else {
                 if let Some(mouse_pos) = ctx.pointer_interact_pos() {
                     let renderer = resources.get::<RendererShared>();
+                    // The pickbuffer is rendered at the render resolution, which lags behind the window while resizing
+                    let (render_width, render_height) = renderer.render_resolution();
+                    let screen_size = ctx.screen_rect().size();
                     renderer.pickbuffer.request_selection(
-                        (mouse_pos.x * ctx.pixels_per_point()).round() as u32,
-                        (mouse_pos.y * ctx.pixels_per_point()).round() as u32,
+                        (mouse_pos.x / screen_size.x * render_width as f32) as u32,
+                        (mouse_pos.y / screen_size.y * render_height as f32) as u32,
                     );
                 }
             }
@@ -378,3 +530,80 @@ impl GuiView for NodeGizmoOverlay {
         None
     }
 }
+
+struct NodeCluster {
+    /// Index of the member closest to the camera
+    nearest: usize,
+    /// Screen position of the nearest member
+    screen_point: Vec2,
+    members: Vec<usize>,
+}
+
+/// Greedily groups screen points that are within `radius` pixels of a cluster's nearest member.
+/// `points` are expected to be sorted from near to far. Only clusters with more than one member are returned
+fn cluster_points(points: impl Iterator<Item = (usize, Vec2)>, radius: f32) -> Vec<NodeCluster> {
+    let radius = radius.max(1.0);
+    let cell_of = |p: Vec2| ((p.x / radius).floor() as i32, (p.y / radius).floor() as i32);
+
+    let mut clusters: Vec<NodeCluster> = vec![];
+    // Clusters are bucketed by the cell of their position, so only neighbouring cells have to be checked
+    let mut grid: FxHashMap<(i32, i32), Vec<usize>> = FxHashMap::default();
+    for (i, point) in points {
+        let (cx, cy) = cell_of(point);
+        let existing = (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (cx + dx, cy + dy)))
+            .filter_map(|cell| grid.get(&cell))
+            .flatten()
+            .copied()
+            .find(|&c| clusters[c].screen_point.distance(point) <= radius);
+
+        match existing {
+            Some(c) => clusters[c].members.push(i),
+            None => {
+                grid.entry((cx, cy)).or_default().push(clusters.len());
+                clusters.push(NodeCluster {
+                    nearest: i,
+                    screen_point: point,
+                    members: vec![i],
+                });
+            }
+        }
+    }
+
+    clusters.retain(|c| c.members.len() > 1);
+    clusters
+}
+
+/// Maximum amount of labels (closest to the camera) that are moved apart by [`declutter_labels`]
+const MAX_DECLUTTERED_LABELS: usize = 128;
+
+/// Moves overlapping label rects down until they no longer overlap any of the labels placed before them.
+/// `rects` are expected to be sorted from near to far, so labels closer to the camera stay in place.
+/// Returns the vertical offset for every node index up to `node_count`
+fn declutter_labels(rects: impl Iterator<Item = (usize, Rect)>, node_count: usize) -> Vec<f32> {
+    const MAX_ITERATIONS: usize = 8;
+    // Room for the background drawn around labels
+    const MARGIN: f32 = 8.0;
+
+    let mut offsets = vec![0.0; node_count];
+    let mut placed: Vec<Rect> = vec![];
+    for (i, rect) in rects {
+        let mut offset = 0.0;
+        for _ in 0..MAX_ITERATIONS {
+            let moved = rect.translate(egui::vec2(0.0, offset));
+            let Some(other) = placed.iter().find(|p| {
+                p.expand(MARGIN / 2.0)
+                    .intersects(moved.expand(MARGIN / 2.0))
+            }) else {
+                break;
+            };
+
+            offset = other.bottom() + MARGIN - rect.top();
+        }
+
+        offsets[i] = offset;
+        placed.push(rect.translate(egui::vec2(0.0, offset)));
+    }
+
+    offsets
+}
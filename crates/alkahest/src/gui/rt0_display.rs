@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use winit::window::Window;
+
+use crate::{
+    config,
+    gui::{
+        context::{GuiCtx, GuiView, ViewAction},
+        util::FadingText,
+    },
+    resources::AppResources,
+};
+
+/// Briefly shows the format of RT0 after it's been toggled, for A/B comparisons
+#[derive(Default)]
+pub struct Rt0FormatOverlay {
+    last_float: Option<bool>,
+    text: FadingText,
+}
+
+impl Rt0FormatOverlay {
+    const DISPLAY_TIME: Duration = Duration::from_millis(1500);
+}
+
+impl GuiView for Rt0FormatOverlay {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        _resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let float = config::with(|c| c.renderer.rt0_float);
+        if self.last_float.is_some_and(|last| last != float) {
+            self.text.show();
+        }
+        self.last_float = Some(float);
+
+        self.text.draw(
+            ctx,
+            Self::DISPLAY_TIME,
+            ctx.screen_rect().center() + egui::vec2(0.0, 72.0),
+            egui::Align2::CENTER_TOP,
+            if float {
+                "RT0: R11G11B10 Float"
+            } else {
+                "RT0: 8-bit sRGB"
+            },
+        );
+
+        None
+    }
+}
@@ -0,0 +1,100 @@
+use alkahest_renderer::{
+    ecs::statistics::SceneStatistics, resources::AppResources, util::text::prettify_distance,
+};
+use destiny_pkg::TagHash;
+use winit::window::Window;
+
+use crate::{
+    gui::context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+    maplist::MapList,
+};
+
+#[derive(Default)]
+pub struct SceneStatisticsPanel {
+    /// Statistics for the current map, along with the map hash and entity count they were computed for
+    cached: Option<(TagHash, usize, SceneStatistics)>,
+}
+
+impl GuiView for SceneStatisticsPanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut windows = resources.get_mut::<HiddenWindows>();
+        if !windows.scene_statistics {
+            return None;
+        }
+
+        let maps = resources.get::<MapList>();
+        let map = maps.current_map()?;
+
+        // Spawning/despawning entities or switching maps invalidates the statistics
+        let entity_count = map.scene.entities().len() as usize;
+        let stale = self.cached.as_ref().map_or(true, |(hash, count, _)| {
+            *hash != map.hash || *count != entity_count
+        });
+        if stale {
+            self.cached = Some((map.hash, entity_count, SceneStatistics::compute(&map.scene)));
+        }
+
+        let (_, _, stats) = self.cached.as_ref()?;
+
+        egui::Window::new("Scene Statistics")
+            .open(&mut windows.scene_statistics)
+            .show(ctx, |ui| {
+                ui.heading(&map.name);
+                egui::Grid::new("scene_statistics")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.strong("Entities");
+                        ui.label(stats.entity_count.to_string());
+                        ui.end_row();
+
+                        ui.strong("Triangles");
+                        ui.label(stats.triangle_count.to_string())
+                            .on_hover_text("Highest detail LOD of all geometry");
+                        ui.end_row();
+
+                        ui.strong("Shadowing Lights");
+                        ui.label(stats.shadowing_lights.to_string());
+                        ui.end_row();
+
+                        if let Some(bounds) = stats.bounds {
+                            let dimensions = bounds.dimensions();
+                            ui.strong("Extents");
+                            ui.label(format!(
+                                "{} x {} x {}",
+                                prettify_distance(dimensions.x),
+                                prettify_distance(dimensions.y),
+                                prettify_distance(dimensions.z)
+                            ));
+                            ui.end_row();
+
+                            ui.strong("Center");
+                            let center = bounds.center();
+                            ui.label(format!("{:.1} {:.1} {:.1}", center.x, center.y, center.z));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.collapsing("Features", |ui| {
+                    egui::Grid::new("scene_statistics_features")
+                        .striped(true)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (feature, count) in &stats.feature_counts {
+                                ui.label(format!("{feature:?}"));
+                                ui.label(count.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+
+        None
+    }
+}
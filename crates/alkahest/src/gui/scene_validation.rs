@@ -0,0 +1,117 @@
+use alkahest_renderer::{
+    ecs::{resources::SelectedEntity, validation::SceneValidation},
+    resources::AppResources,
+};
+use destiny_pkg::TagHash;
+use winit::window::Window;
+
+use crate::{
+    gui::context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+    maplist::MapList,
+};
+
+#[derive(Default)]
+pub struct SceneValidationPanel {
+    /// Validation results, along with the map hash they were computed for
+    result: Option<(TagHash, SceneValidation)>,
+    was_open: bool,
+}
+
+impl SceneValidationPanel {
+    fn validate(&mut self, resources: &AppResources) {
+        let maps = resources.get::<MapList>();
+        let Some(map) = maps.current_map() else {
+            self.result = None;
+            return;
+        };
+
+        let validation = SceneValidation::run(&map.scene);
+        if validation.is_valid() {
+            info!(
+                "Scene validation of map {}: no issues in {} entities",
+                map.hash, validation.entity_count
+            );
+        }
+        for issue in &validation.issues {
+            warn!(
+                "Scene validation of map {}: {} ({} entities, eg. {:?})",
+                map.hash, issue.check.name, issue.count, issue.samples
+            );
+        }
+
+        self.result = Some((map.hash, validation));
+    }
+}
+
+impl GuiView for SceneValidationPanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut open = resources.get::<HiddenWindows>().scene_validation;
+        // Validation runs on demand, every time the window is opened
+        if open && !self.was_open {
+            self.validate(resources);
+        }
+        self.was_open = open;
+        if !open {
+            return None;
+        }
+
+        let current_hash = resources.get::<MapList>().current_map().map(|m| m.hash);
+        let mut revalidate = false;
+        egui::Window::new("Scene Validation")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let Some((hash, validation)) = &self.result else {
+                    ui.label("No map loaded");
+                    return;
+                };
+
+                if current_hash != Some(*hash) {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "The current map has changed since validating",
+                    );
+                }
+
+                if validation.is_valid() {
+                    ui.label(format!(
+                        "No issues found in {} entities",
+                        validation.entity_count
+                    ));
+                }
+
+                egui::Grid::new("scene_validation")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for issue in &validation.issues {
+                            ui.strong(issue.check.name);
+                            ui.label(issue.count.to_string());
+                            ui.horizontal_wrapped(|ui| {
+                                for &entity in &issue.samples {
+                                    if ui.small_button(entity.to_string()).clicked() {
+                                        resources.get_mut::<SelectedEntity>().select(entity);
+                                    }
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                revalidate = ui.button("Validate Again").clicked();
+            });
+
+        if revalidate {
+            self.validate(resources);
+        }
+        resources.get_mut::<HiddenWindows>().scene_validation = open;
+
+        None
+    }
+}
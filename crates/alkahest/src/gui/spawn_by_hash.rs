@@ -0,0 +1,114 @@
+use alkahest_pm::package_manager;
+use alkahest_renderer::{
+    camera::Camera,
+    ecs::{resources::SelectedEntity, transform::Transform},
+    renderer::RendererShared,
+    resources::AppResources,
+};
+use winit::window::Window;
+
+use crate::{
+    gui::{
+        console::load_entity_model,
+        context::{GuiCtx, GuiView, HiddenWindows, ViewAction},
+        toast::Toast,
+    },
+    maplist::MapList,
+    util::hash::parse_tag_hash,
+};
+
+/// Spawns an entity model from a (pasted) tag hash at the position the camera is looking at
+#[derive(Default)]
+pub struct SpawnByHashPanel {
+    input: String,
+}
+
+impl GuiView for SpawnByHashPanel {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut windows = resources.get_mut::<HiddenWindows>();
+        let mut spawn = false;
+        egui::Window::new("Spawn by Tag Hash")
+            .open(&mut windows.spawn_by_hash)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.input)
+                        .hint_text("80801234, 0x80801234, 2164264578 or pkg_0123/80801234")
+                        .desired_width(320.0),
+                );
+
+                // Pasting while the window is open replaces the hash, so it doesn't have to be focused first
+                if ui.memory(|m| m.focused().is_none()) {
+                    let pasted = ui.input(|i| {
+                        i.events.iter().rev().find_map(|e| match e {
+                            egui::Event::Paste(text) => Some(text.clone()),
+                            _ => None,
+                        })
+                    });
+
+                    if let Some(text) = pasted {
+                        self.input = text;
+                    }
+                }
+
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                spawn = ui.button("Spawn").clicked() || submitted;
+            });
+
+        if spawn {
+            match self.spawn(resources) {
+                Ok(()) => self.input.clear(),
+                Err(e) => resources.get_mut::<Toast>().show(e.to_string()),
+            }
+        }
+
+        None
+    }
+}
+
+impl SpawnByHashPanel {
+    fn spawn(&self, resources: &AppResources) -> anyhow::Result<()> {
+        let tag = parse_tag_hash(&self.input)?;
+        if package_manager().get_entry(tag).is_none() {
+            anyhow::bail!("Tag {tag} does not exist in the package manager");
+        }
+
+        let mut maps = resources.get_mut::<MapList>();
+        let Some(map) = maps.current_map_mut() else {
+            anyhow::bail!("No map is loaded");
+        };
+
+        let renderer = resources.get::<RendererShared>();
+        let camera = resources.get::<Camera>();
+        let (_, pos) = renderer
+            .data
+            .lock()
+            .gbuffers
+            .depth_buffer_distance_pos_center(&camera);
+
+        let transform = Transform {
+            translation: if pos.is_finite() {
+                pos
+            } else {
+                camera.position() + camera.forward() * 15.0
+            },
+            ..Default::default()
+        };
+
+        let bundle = load_entity_model(tag, transform, &renderer)
+            .map_err(|e| anyhow::anyhow!("Failed to load entity model {tag}: {e}"))?;
+        let e = map.scene.spawn(bundle);
+        resources.get_mut::<SelectedEntity>().select(e.id());
+
+        info!("Spawned entity model {tag}");
+
+        Ok(())
+    }
+}
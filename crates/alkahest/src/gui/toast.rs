@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use winit::window::Window;
+
+use crate::{
+    gui::{
+        context::{GuiCtx, GuiView, ViewAction},
+        util::FadingText,
+    },
+    resources::AppResources,
+};
+
+/// A short message shown at the bottom of the viewport, for feedback on actions that have no other visible result
+#[derive(Default)]
+pub struct Toast {
+    message: Option<String>,
+    text: FadingText,
+}
+
+impl Toast {
+    const DISPLAY_TIME: Duration = Duration::from_millis(2500);
+
+    pub fn show(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+        self.text.show();
+    }
+}
+
+pub struct ToastOverlay;
+
+impl GuiView for ToastOverlay {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let mut toast = resources.get_mut::<Toast>();
+        let toast = &mut *toast;
+        let message = toast.message.as_ref()?;
+
+        let visible = toast.text.draw(
+            ctx,
+            Toast::DISPLAY_TIME,
+            ctx.screen_rect().center_bottom() - egui::vec2(0.0, 96.0),
+            egui::Align2::CENTER_BOTTOM,
+            message,
+        );
+        if !visible {
+            toast.message = None;
+        }
+
+        None
+    }
+}
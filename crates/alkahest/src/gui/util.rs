@@ -1,5 +1,67 @@
+use std::time::{Duration, Instant};
+
 use egui::{vec2, Align2, Color32, FontId, Pos2, Rect};
 
+/// Memory ID of the opacity overlays should be drawn with, see [`overlay_painter`]
+pub const OVERLAY_OPACITY_ID: &str = "overlay_opacity";
+
+/// Painter for overlays drawn on top of everything, faded out by the overlay auto-hide
+pub fn overlay_painter(ctx: &egui::Context) -> egui::Painter {
+    let mut painter = ctx.layer_painter(egui::LayerId::debug());
+    let opacity = ctx.data(|d| d.get_temp::<f32>(OVERLAY_OPACITY_ID.into()));
+    painter.multiply_opacity(opacity.unwrap_or(1.0));
+    painter
+}
+
+/// Text that is shown for a while after being triggered, fading out at the end of its display time
+#[derive(Default)]
+pub struct FadingText {
+    shown_at: Option<Instant>,
+}
+
+impl FadingText {
+    const FADE_TIME: Duration = Duration::from_millis(300);
+
+    /// Shows the text again, starting over if it's already visible
+    pub fn show(&mut self) {
+        self.shown_at = Some(Instant::now());
+    }
+
+    /// Draws `text` on the overlay painter until `display_time` has passed since it was last shown.
+    /// Returns false once it's no longer visible
+    pub fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        display_time: Duration,
+        pos: Pos2,
+        anchor: Align2,
+        text: impl ToString,
+    ) -> bool {
+        let Some(shown_at) = self.shown_at else {
+            return false;
+        };
+
+        let elapsed = shown_at.elapsed();
+        if elapsed >= display_time {
+            self.shown_at = None;
+            return false;
+        }
+
+        let remaining = (display_time - elapsed).as_secs_f32();
+        let alpha = (remaining / Self::FADE_TIME.as_secs_f32()).min(1.0);
+
+        overlay_painter(ctx).text_with_shadow(
+            pos,
+            anchor,
+            text,
+            FontId::proportional(18.0),
+            Color32::WHITE.gamma_multiply(alpha),
+        );
+
+        true
+    }
+}
+
 pub trait PainterExt {
     fn text_with_shadow(
         &self,
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use alkahest_renderer::camera::Camera;
+use winit::window::Window;
+
+use crate::{
+    gui::{
+        context::{GuiCtx, GuiView, ViewAction},
+        util::FadingText,
+    },
+    resources::AppResources,
+};
+
+/// Briefly shows the field of view after it's been changed by zooming
+#[derive(Default)]
+pub struct ZoomDisplayOverlay {
+    last_fov: Option<f32>,
+    text: FadingText,
+}
+
+impl ZoomDisplayOverlay {
+    const DISPLAY_TIME: Duration = Duration::from_millis(1500);
+}
+
+impl GuiView for ZoomDisplayOverlay {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        _window: &Window,
+        resources: &AppResources,
+        _gui: &GuiCtx<'_>,
+    ) -> Option<ViewAction> {
+        let fov = resources.get::<Camera>().zoomed_fov();
+        if self.last_fov.is_some_and(|last| last != fov) {
+            self.text.show();
+        }
+        self.last_fov = Some(fov);
+
+        self.text.draw(
+            ctx,
+            Self::DISPLAY_TIME,
+            ctx.screen_rect().center() + egui::vec2(0.0, 48.0),
+            egui::Align2::CENTER_TOP,
+            format!("FOV {fov:.1}°"),
+        );
+
+        None
+    }
+}
@@ -1,14 +1,17 @@
 use alkahest_data::text::StringContainerShared;
 use alkahest_renderer::{
+    camera::Camera,
     ecs::{
         common::Global,
         hierarchy::{Children, Parent},
+        map::MapLoadFailures,
         render::{
             dynamic_geometry::update_dynamic_model_system, light::update_shadowrenderer_system,
             static_geometry::update_static_instances_system,
         },
         resources::SelectedEntity,
         route::Route,
+        statistics::SceneStatistics,
         visibility::propagate_entity_visibility_system,
         Scene, SceneInfo,
     },
@@ -27,12 +30,16 @@ use bevy_ecs::{
     world::CommandQueue,
 };
 use destiny_pkg::TagHash;
+use glam::Vec2;
 use itertools::Itertools;
 use poll_promise::Promise;
 use smallvec::SmallVec;
 
 use crate::{
-    discord, gui::activity_select::CurrentActivity, resources::AppResources, ApplicationArgs,
+    config, discord,
+    gui::{activity_select::CurrentActivity, toast::Toast},
+    resources::AppResources,
+    ApplicationArgs,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -49,6 +56,8 @@ pub struct Map {
     pub name: String,
     pub load_promise: Option<Box<Promise<anyhow::Result<Scene>>>>,
     pub load_state: MapLoadState,
+    /// Loaded and rendered alongside the current map
+    pub active: bool,
 
     pub command_queue: CommandQueue,
     pub scene: Scene,
@@ -109,6 +118,7 @@ impl Map {
             name: name.as_ref().to_string(),
             load_promise: Default::default(),
             load_state: Default::default(),
+            active: false,
 
             systems: Systems::create(&mut scene),
             scene,
@@ -116,7 +126,8 @@ impl Map {
         }
     }
 
-    pub(super) fn update_load(&mut self) {
+    /// Returns true when the map has just finished loading
+    pub(super) fn update_load(&mut self) -> bool {
         if let Some(promise) = self.load_promise.take() {
             if promise.ready().is_some() {
                 match promise.block_and_take() {
@@ -133,6 +144,7 @@ impl Map {
                         );
 
                         self.load_state = MapLoadState::Loaded;
+                        return true;
                     }
                     Err(e) => {
                         error!("Failed to load map {} '{}': {:?}", self.hash, self.name, e);
@@ -144,6 +156,23 @@ impl Map {
                 self.load_state = MapLoadState::Loading;
             }
         }
+
+        false
+    }
+
+    /// Moves the camera to a position overlooking the whole map
+    pub fn place_camera_at_spawn(&self, camera: &mut Camera) {
+        // Looking down at the map at an angle gives a better overview than looking at it head-on
+        const SPAWN_PITCH: f32 = 35.0;
+
+        let Some(bounds) = SceneStatistics::compute(&self.scene).bounds else {
+            return;
+        };
+
+        camera.tween = None;
+        camera.set_orientation(Vec2::new(SPAWN_PITCH, camera.orientation().y));
+        let position = camera.framing_position(bounds.center(), bounds.radius());
+        camera.set_position(position);
     }
 
     pub fn update(&mut self) {
@@ -259,6 +288,15 @@ impl Map {
     pub fn commands(&self) -> Commands<'_, '_> {
         Commands::new(&mut self.pocus().command_queue, &self.scene)
     }
+
+    /// Drops the scene, releasing its assets. The map will be loaded again when it becomes the current map
+    pub fn unload(&mut self) {
+        let activity_hash = self.scene.get_activity_hash();
+        self.scene = Scene::new_with_info(activity_hash, self.hash);
+        self.systems = Systems::create(&mut self.scene);
+        self.command_queue = Default::default();
+        self.load_state = MapLoadState::Unloaded;
+    }
 }
 
 #[derive(Default)]
@@ -267,6 +305,12 @@ pub struct MapList {
     pub previous_map: Option<usize>,
 
     pub load_all_maps: bool,
+    /// Reset the camera once the current map has loaded, set when switching maps
+    camera_reset_pending: bool,
+    /// Keep the camera where it is when the current map finishes loading, set when switching maps with [`crate::config::VisualSettings::keep_camera_on_map_switch`]
+    keep_camera_pending: bool,
+    /// Map to unload once the current map has loaded, set when switching maps
+    unload_pending: Option<usize>,
 
     pub maps: Vec<Map>,
 }
@@ -284,6 +328,14 @@ impl MapList {
         self.maps.get_mut(self.current_map)
     }
 
+    /// Name and tag hash of the current map, formatted as `<name> (<hash>)`
+    pub fn current_map_label(&self) -> String {
+        self.current_map().map_or_else(
+            || "No map".to_string(),
+            |m| format!("{} ({})", m.name, m.hash),
+        )
+    }
+
     // pub fn get_map_mut(&mut self, index: usize) -> Option<&mut Map> {
     //     self.maps.get_mut(index)
     // }
@@ -301,15 +353,110 @@ impl MapList {
             .filter(|m| m.load_state == MapLoadState::Loaded)
             .count()
     }
+
+    pub fn count_active(&self) -> usize {
+        self.maps.iter().filter(|m| m.active).count()
+    }
+
+    /// Indices of the maps that are rendered, in the order they are passed to the renderer.
+    /// The current map comes first, followed by every other active map that has been loaded
+    pub fn visible_map_indices(&self) -> Vec<usize> {
+        // Secondary scenes don't provide the atmosphere and globals, so the current map has to come first
+        std::iter::once(self.current_map)
+            .filter(|&i| i < self.maps.len())
+            .chain(self.maps.iter().enumerate().filter_map(|(i, m)| {
+                (i != self.current_map && m.active && m.load_state == MapLoadState::Loaded)
+                    .then_some(i)
+            }))
+            .collect()
+    }
+
+    /// Maps that are rendered, see [`Self::visible_map_indices`]
+    pub fn visible_maps_mut(&mut self) -> Vec<&mut Map> {
+        let indices = self.visible_map_indices();
+        let mut maps = self
+            .maps
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| indices.contains(i))
+            .collect_vec();
+
+        maps.sort_by_key(|(i, _)| indices.iter().position(|j| j == i));
+        maps.into_iter().map(|(_, m)| m).collect()
+    }
 }
 
 impl MapList {
     pub fn update_maps(&mut self, resources: &AppResources) {
         for (i, map) in self.maps.iter_mut().enumerate() {
-            map.update_load();
-            if i == self.current_map && map.load_state == MapLoadState::Unloaded {
+            let loaded = map.update_load();
+            if loaded {
+                if let Some(failures) = map.scene.get_resource::<MapLoadFailures>() {
+                    resources.get_mut::<Toast>().show(format!(
+                        "{} models failed to load in {}",
+                        failures.models.len(),
+                        map.name
+                    ));
+                }
+            }
+
+            if i != self.current_map && !map.active {
+                continue;
+            }
+
+            if map.load_state == MapLoadState::Unloaded {
                 map.start_load(resources);
             }
+
+            if i != self.current_map {
+                continue;
+            }
+
+            // Loads started by a map switch leave the camera alone when it's kept across switches
+            if loaded
+                && !std::mem::take(&mut self.keep_camera_pending)
+                && config::with(|c| c.visual.camera_spawn_on_load)
+            {
+                map.place_camera_at_spawn(&mut resources.get_mut::<Camera>());
+            }
+        }
+
+        // The map might have been loaded already when it was switched to
+        if self.keep_camera_pending
+            && !matches!(
+                self.current_map().map(|m| &m.load_state),
+                Some(MapLoadState::Loading | MapLoadState::Unloaded)
+            )
+        {
+            self.keep_camera_pending = false;
+        }
+
+        if self.camera_reset_pending {
+            match self.current_map().map(|m| m.load_state.clone()) {
+                Some(MapLoadState::Loaded) => {
+                    let mut camera = resources.get_mut::<Camera>();
+                    camera.speed_mul = 1.0;
+                    camera.reset_zoom();
+                    self.current_map()
+                        .unwrap()
+                        .place_camera_at_spawn(&mut camera);
+                    self.camera_reset_pending = false;
+                }
+                Some(MapLoadState::Loading | MapLoadState::Unloaded) => {}
+                _ => self.camera_reset_pending = false,
+            }
+        }
+
+        if let Some(previous_map) = self.unload_pending {
+            match self.current_map().map(|m| m.load_state.clone()) {
+                Some(MapLoadState::Loaded) => {
+                    self.unload_pending = None;
+                    self.unload_previous_map(previous_map, resources);
+                }
+                Some(MapLoadState::Loading | MapLoadState::Unloaded) => {}
+                // Keep the previous map around if the new one failed to load
+                _ => self.unload_pending = None,
+            }
         }
 
         if self.load_all_maps {
@@ -346,6 +493,10 @@ impl MapList {
 
         self.current_map = 0;
         self.previous_map = None;
+        // Left over from a switch in the previous map list
+        self.keep_camera_pending = false;
+        self.camera_reset_pending = false;
+        self.unload_pending = None;
 
         #[cfg(feature = "discord_rpc")]
         if let Some(map) = self.current_map() {
@@ -363,6 +514,34 @@ impl MapList {
         }
     }
 
+    /// Unloads a map that was switched away from, along with the assets that were exclusively used by it.
+    /// Assets shared with the current map are kept alive by the handles in its scene
+    fn unload_previous_map(&mut self, index: usize, resources: &AppResources) {
+        // They would just get loaded again on the next update
+        if index == self.current_map || self.load_all_maps {
+            return;
+        }
+
+        let Some(map) = self
+            .maps
+            .get_mut(index)
+            .filter(|m| !m.active && m.hash.is_some() && m.load_state == MapLoadState::Loaded)
+        else {
+            return;
+        };
+
+        info!("Unloading previous map {} '{}'", map.hash, map.name);
+        map.unload();
+
+        let removed = resources
+            .get::<RendererShared>()
+            .data
+            .lock()
+            .asset_manager
+            .purge_unused();
+        info!("Released {removed} assets that were only used by the previous map");
+    }
+
     pub fn set_current_map(&mut self, index: usize) {
         if index >= self.maps.len() {
             warn!(
@@ -375,6 +554,12 @@ impl MapList {
 
         self.previous_map = Some(self.current_map);
         self.current_map = index;
+        // Applied once the map has loaded
+        self.keep_camera_pending = config::with(|c| c.visual.keep_camera_on_map_switch);
+        self.camera_reset_pending = !self.keep_camera_pending;
+        self.unload_pending = self
+            .previous_map
+            .filter(|_| config::with(|c| c.visual.unload_previous_map));
 
         if let Some(previous_map) = self.previous_map {
             if previous_map >= self.maps.len() {
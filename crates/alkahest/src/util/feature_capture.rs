@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use alkahest_renderer::{
+    ecs::{render::light::ShadowMapRenderer, Scene},
+    renderer::{Renderer, RendererSettings, RendererShared},
+    resources::AppResources,
+};
+
+use crate::{config, gui::toast::Toast};
+
+/// Renders the scene once for every toggleable feature renderer with only that feature visible, saving each layer to a PNG.
+/// Layers are rendered by the regular render loop, one after another over the next few frames.
+#[derive(Default)]
+pub struct FeatureCapture {
+    active: Option<ActiveCapture>,
+}
+
+struct ActiveCapture {
+    directory: PathBuf,
+    /// Index into [`RendererSettings::TOGGLEABLE_FEATURES`]
+    layer: usize,
+    /// Frames rendered for the current layer so far
+    frames: usize,
+}
+
+impl FeatureCapture {
+    /// Frames to render before saving a layer, so shadows and temporal effects can catch up with the change in visible geometry
+    const SETTLE_FRAMES: usize = 3;
+
+    pub fn is_running(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn start(&mut self) -> anyhow::Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+
+        let directory = PathBuf::from(format!(
+            "./feature_layers_{}",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+        ));
+        std::fs::create_dir_all(&directory)?;
+        info!("Capturing feature layers to {}", directory.display());
+
+        self.active = Some(ActiveCapture {
+            directory,
+            layer: 0,
+            frames: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Isolates the feature of the current layer. Must be called right before rendering the world.
+    pub fn prepare_frame(&self, renderer: &Renderer, scenes: &mut [&mut Scene]) {
+        let Some(active) = &self.active else {
+            return;
+        };
+
+        let mut settings = config::with(|c| c.renderer.clone());
+        settings.isolate_feature(RendererSettings::TOGGLEABLE_FEATURES[active.layer]);
+        renderer.set_render_settings(settings);
+
+        if active.frames == 0 {
+            // Cached stationary shadows still contain the geometry of the previous layer
+            for scene in scenes.iter_mut() {
+                for mut shadow in scene.query::<&mut ShadowMapRenderer>().iter_mut(scene) {
+                    shadow.stationary_needs_update = true;
+                }
+            }
+        }
+    }
+
+    /// Saves the current layer once it has settled and moves on to the next one. Must be called right after rendering the world.
+    pub fn finish_frame(&mut self, resources: &AppResources) {
+        let Some(active) = &mut self.active else {
+            return;
+        };
+
+        active.frames += 1;
+        if active.frames < Self::SETTLE_FRAMES {
+            return;
+        }
+
+        let layer_count = RendererSettings::TOGGLEABLE_FEATURES.len();
+        let feature = RendererSettings::TOGGLEABLE_FEATURES[active.layer];
+        let path = active
+            .directory
+            .join(format!("{:02}_{}.png", active.layer, feature.short()));
+        let renderer = resources.get::<RendererShared>();
+        if let Err(e) = renderer.capture_screenshot(&path) {
+            error!("Failed to capture feature layer {feature:?}: {e:?}");
+            resources
+                .get_mut::<Toast>()
+                .show(format!("Failed to capture feature layer {feature:?}"));
+            self.active = None;
+            renderer.set_render_settings(config::with(|c| c.renderer.clone()));
+            return;
+        }
+
+        active.layer += 1;
+        active.frames = 0;
+        if active.layer < layer_count {
+            resources.get_mut::<Toast>().show(format!(
+                "Capturing feature layers ({}/{layer_count})",
+                active.layer
+            ));
+            return;
+        }
+
+        info!(
+            "Saved {layer_count} feature layers to {}",
+            active.directory.display()
+        );
+        resources.get_mut::<Toast>().show(format!(
+            "Saved {layer_count} feature layers to {}",
+            active.directory.display()
+        ));
+        self.active = None;
+        renderer.set_render_settings(config::with(|c| c.renderer.clone()));
+    }
+}
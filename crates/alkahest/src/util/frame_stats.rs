@@ -0,0 +1,193 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    thread::JoinHandle,
+    time::Instant,
+};
+
+use alkahest_renderer::gpu::GpuFrameTimings;
+use crossbeam::channel::Sender;
+use glam::Vec3;
+use itertools::Itertools;
+
+/// GPU profiling spans that get their own column in the CSV file. Spans that occur multiple times in a frame are summed.
+const GPU_SPANS: &[&str] = &[
+    "main",
+    "update_shadow_maps",
+    "view_0",
+    "generate_gbuffer",
+    "lighting_pass",
+    "ssao",
+    "shading_pass",
+    "atmosphere",
+    "transparents_pass",
+    "final_or_debug_view",
+    "view_overlay",
+    "egui",
+];
+
+/// How many frames a row waits for the GPU timings of its frame before it is written without them
+const MAX_GPU_LATENCY: usize = 16;
+
+pub struct FrameStats {
+    /// See [`GpuContext::frame_index`](alkahest_renderer::gpu::GpuContext::frame_index), which GPU timings are matched up with
+    pub frame_index: usize,
+    pub cpu_frame_time: f32,
+    pub draw_calls: usize,
+    pub visible_entities: usize,
+    pub camera_position: Vec3,
+    pub map_name: String,
+}
+
+/// Writes per-frame timing information to a CSV file. Rows are sent to a writer thread, so logging doesn't block the render loop.
+///
+/// GPU timings only become available a few frames later, so rows are held back until the timings of their frame have been resolved
+#[derive(Default)]
+pub struct FrameStatsLogger {
+    active: Option<(Sender<String>, JoinHandle<()>)>,
+    start_time: Option<Instant>,
+    /// Rows waiting for their GPU timings, along with the time they were logged at in milliseconds
+    pending: VecDeque<(f64, FrameStats)>,
+    /// GPU timings of frames that don't have a row yet
+    gpu_frames: VecDeque<GpuFrameTimings>,
+    pub path: Option<PathBuf>,
+}
+
+impl FrameStatsLogger {
+    pub fn is_running(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn start(&mut self) -> anyhow::Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+
+        let path = PathBuf::from(format!(
+            "./frame_stats_{}.csv",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+        ));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writeln!(
+            writer,
+            "timestamp_ms,frame,cpu_frame_ms,{},draw_calls,visible_entities,camera_x,camera_y,camera_z,map",
+            GPU_SPANS.iter().map(|s| format!("gpu_{s}_ms")).join(",")
+        )?;
+
+        let (sender, receiver) = crossbeam::channel::unbounded::<String>();
+        let thread = std::thread::Builder::new()
+            .name("frame-stats-writer".to_string())
+            .spawn(move || {
+                for row in receiver {
+                    if let Err(e) = writeln!(writer, "{row}") {
+                        error!("Failed to write frame stats: {e}");
+                        return;
+                    }
+                }
+
+                writer.flush().ok();
+            })?;
+
+        info!("Logging frame stats to {}", path.display());
+        self.active = Some((sender, thread));
+        self.start_time = Some(Instant::now());
+        self.path = Some(path);
+
+        Ok(())
+    }
+
+    /// Stops logging and waits for the remaining rows to be written.
+    /// Rows that are still waiting for GPU timings are written without them
+    pub fn stop(&mut self) {
+        while let Some((timestamp, stats)) = self.pending.pop_front() {
+            self.write_row(timestamp, &stats, None);
+        }
+        self.gpu_frames.clear();
+
+        if let Some((sender, thread)) = self.active.take() {
+            drop(sender);
+            thread.join().ok();
+
+            if let Some(path) = &self.path {
+                info!("Frame stats written to {}", path.display());
+            }
+        }
+    }
+
+    /// Queues a row for the current frame, and writes the queued rows whose GPU timings are in `gpu_frames`
+    /// (see [`GpuContext::take_frame_timings`](alkahest_renderer::gpu::GpuContext::take_frame_timings))
+    pub fn log(&mut self, stats: FrameStats, gpu_frames: Vec<GpuFrameTimings>) {
+        let (Some(_), Some(start_time)) = (&self.active, self.start_time) else {
+            return;
+        };
+
+        self.pending
+            .push_back((start_time.elapsed().as_secs_f64() * 1000.0, stats));
+        self.gpu_frames.extend(gpu_frames);
+
+        let newest_frame = self.pending.back().map_or(0, |(_, s)| s.frame_index);
+        while let Some((_, stats)) = self.pending.front() {
+            let frame = stats.frame_index;
+            // Timings of frames that were recorded before this row can't be matched up with anything anymore
+            self.gpu_frames.retain(|f| f.frame >= frame);
+
+            let gpu_frame = if self.gpu_frames.front().is_some_and(|f| f.frame == frame) {
+                self.gpu_frames.pop_front()
+            } else if newest_frame.saturating_sub(frame) < MAX_GPU_LATENCY {
+                break;
+            } else {
+                None
+            };
+
+            let (timestamp, stats) = self.pending.pop_front().unwrap();
+            self.write_row(timestamp, &stats, gpu_frame.as_ref());
+        }
+    }
+
+    fn write_row(&self, timestamp: f64, stats: &FrameStats, gpu_frame: Option<&GpuFrameTimings>) {
+        let Some((sender, _)) = &self.active else {
+            return;
+        };
+
+        let gpu_columns = GPU_SPANS
+            .iter()
+            .map(|span| {
+                let mut timings = gpu_frame
+                    .into_iter()
+                    .flat_map(|f| f.timings.iter())
+                    .filter(|(label, _)| label == span)
+                    .peekable();
+
+                if timings.peek().is_some() {
+                    format!("{:.3}", timings.map(|(_, ms)| ms).sum::<f32>())
+                } else {
+                    String::new()
+                }
+            })
+            .join(",");
+
+        sender
+            .send(format!(
+                "{:.3},{},{:.3},{},{},{},{:.2},{:.2},{:.2},\"{}\"",
+                timestamp,
+                stats.frame_index,
+                stats.cpu_frame_time * 1000.0,
+                gpu_columns,
+                stats.draw_calls,
+                stats.visible_entities,
+                stats.camera_position.x,
+                stats.camera_position.y,
+                stats.camera_position.z,
+                stats.map_name.replace('"', "\"\"")
+            ))
+            .ok();
+    }
+}
+
+impl Drop for FrameStatsLogger {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,80 @@
+use alkahest_data::WideHash;
+use destiny_pkg::{TagHash, TagHash64};
+
+/// Parses a tag hash as typed in the console or copied from other tools.
+/// Accepts (0x-prefixed) hex in display order, decimal values and hashes prefixed with their package name (`pkg_0123/80801234`)
+pub fn parse_tag_hash(s: &str) -> anyhow::Result<WideHash> {
+    let s = s.trim();
+    let s = s.rsplit_once('/').map_or(s, |(_, hash)| hash);
+    if s.is_empty() {
+        anyhow::bail!("No tag hash entered");
+    }
+
+    let (hex, is_hex) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, true),
+        // Decimal tags are at least 10 digits long, so 8 and 16 digit hashes are always treated as hex
+        None => (
+            s,
+            !s.chars().all(|c| c.is_ascii_digit()) || matches!(s.len(), 8 | 16),
+        ),
+    };
+
+    if is_hex {
+        if hex.len() > 8 {
+            let h = u64::from_str_radix(hex, 16)
+                .map_err(|e| anyhow::anyhow!("Invalid 64-bit hash '{hex}': {e}"))?;
+            Ok(WideHash::Hash64(TagHash64(u64::from_be(h))))
+        } else {
+            let h = u32::from_str_radix(hex, 16)
+                .map_err(|e| anyhow::anyhow!("Invalid hash '{hex}': {e}"))?;
+            Ok(WideHash::Hash32(TagHash(u32::from_be(h))))
+        }
+    } else {
+        let v: u64 = s
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid hash '{s}': {e}"))?;
+        Ok(match u32::try_from(v) {
+            Ok(v) => WideHash::Hash32(TagHash(v)),
+            Err(_) => WideHash::Hash64(TagHash64(v)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH32: WideHash = WideHash::Hash32(TagHash(0x34128080));
+    const HASH64: WideHash = WideHash::Hash64(TagHash64(0xEFCDAB8967452301));
+
+    #[test]
+    fn parse_hex() {
+        assert!(parse_tag_hash("80801234").unwrap() == HASH32);
+        assert!(parse_tag_hash("0x80801234").unwrap() == HASH32);
+        assert!(parse_tag_hash("0X80801234").unwrap() == HASH32);
+        assert!(parse_tag_hash("  80801234\n").unwrap() == HASH32);
+        assert!(parse_tag_hash("0123456789ABCDEF").unwrap() == HASH64);
+        assert!(parse_tag_hash("0x0123456789abcdef").unwrap() == HASH64);
+    }
+
+    #[test]
+    fn parse_decimal() {
+        assert!(parse_tag_hash("873627776").unwrap() == HASH32);
+        assert!(parse_tag_hash("17279655951921914625").unwrap() == HASH64);
+    }
+
+    #[test]
+    fn parse_package_prefixed() {
+        assert!(parse_tag_hash("pkg_0123/80801234").unwrap() == HASH32);
+        assert!(parse_tag_hash("w64_sandbox_0123_0/0x80801234").unwrap() == HASH32);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_tag_hash("").is_err());
+        assert!(parse_tag_hash("pkg_0123/").is_err());
+        assert!(parse_tag_hash("8080123G").is_err());
+        assert!(parse_tag_hash("0x").is_err());
+        assert!(parse_tag_hash("0x0123456789abcdef01").is_err());
+    }
+}
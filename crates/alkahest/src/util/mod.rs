@@ -3,7 +3,10 @@ pub mod consts;
 // pub mod dds;
 pub mod error;
 // pub mod export;
+pub mod feature_capture;
+pub mod frame_stats;
 pub mod action;
+pub mod hash;
 pub mod image;
 pub mod iron;
 pub mod text;